@@ -4,7 +4,7 @@ use chrono::{DateTime, NaiveDate, Utc};
 use std::path::PathBuf;
 use structopt::StructOpt;
 
-use mws::client::{Client, Method};
+use mws::client::Client;
 
 mod env;
 
@@ -15,15 +15,40 @@ use self::env::Env;
 struct Opt {
   #[structopt(long = "env", parse(from_os_str))]
   env: Option<PathBuf>,
+  /// Output format, `debug`, `json`, `xml`, `csv` or `ndjson`. Subcommands reject formats they
+  /// don't support.
+  #[structopt(long = "format", parse(try_from_str = parse_output_format))]
+  format: Option<OutputFormat>,
   #[structopt(subcommand)]
   cmd: Command,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum OutputFormat {
+  Debug,
+  Json,
+  Xml,
+  Csv,
+  Ndjson,
+}
+
+impl OutputFormat {
+  fn as_str(self) -> &'static str {
+    match self {
+      OutputFormat::Debug => "debug",
+      OutputFormat::Json => "json",
+      OutputFormat::Xml => "xml",
+      OutputFormat::Csv => "csv",
+      OutputFormat::Ndjson => "ndjson",
+    }
+  }
+}
+
 #[derive(Debug, StructOpt)]
 enum Command {
   ReportRequest {
     #[structopt(long = "report_type")]
-    report_type: String,
+    report_type: mws::reports::ReportType,
     #[structopt(long = "start_date", parse(try_from_str))]
     start_date: Option<NaiveDate>,
     #[structopt(long = "end_date", parse(try_from_str))]
@@ -39,7 +64,7 @@ enum Command {
   },
   ReportListRequestByTypes {
     #[structopt(long = "type")]
-    types: Vec<String>,
+    types: Vec<mws::reports::ReportType>,
   },
   ReportGet {
     #[structopt(long = "id")]
@@ -47,11 +72,40 @@ enum Command {
     #[structopt(long = "out", parse(from_os_str))]
     out: PathBuf,
   },
-  EncodingConvJp {
+  ReportFetch {
+    #[structopt(long = "report_type")]
+    report_type: mws::reports::ReportType,
+    #[structopt(long = "start_date", parse(try_from_str))]
+    start_date: Option<NaiveDate>,
+    #[structopt(long = "end_date", parse(try_from_str))]
+    end_date: Option<NaiveDate>,
+    #[structopt(long = "report_options")]
+    report_options: Option<String>,
+    #[structopt(long = "marketplace")]
+    marketplace_id_list: Option<Vec<String>>,
+    /// Seconds to wait for the report before giving up
+    #[structopt(long = "timeout", default_value = "3600")]
+    timeout: u64,
+    /// Encoding of the report, detected from the response or the marketplace if not specified
+    #[structopt(long = "encoding", parse(try_from_str = parse_encoding))]
+    encoding: Option<&'static mws::encoding::Encoding>,
+    /// Keep the report bytes as returned by MWS instead of transcoding to UTF-8
+    #[structopt(long = "raw")]
+    raw: bool,
+    #[structopt(long = "out", parse(from_os_str))]
+    out: PathBuf,
+  },
+  /// Transcodes a downloaded report to UTF-8
+  EncodingConv {
     #[structopt(long = "in", parse(from_os_str))]
     input: PathBuf,
     #[structopt(long = "out", parse(from_os_str))]
     out: PathBuf,
+    #[structopt(long = "encoding", parse(try_from_str = parse_encoding))]
+    encoding: Option<&'static mws::encoding::Encoding>,
+    /// Use the report encoding of this marketplace
+    #[structopt(long = "marketplace")]
+    marketplace_id: Option<String>,
   },
   ProductGetLowestPricedOffersForSKU {
     #[structopt(long = "marketplace")]
@@ -83,6 +137,9 @@ enum Command {
     #[structopt(long = "marketplace")]
     marketplace_id: String,
   },
+  /// Writes the financial events of each page to
+  /// `financial_events_{posted_after}_{posted_before}_{page}.json`, or the raw XML of the result to
+  /// `.xml` files with `--format xml`
   ListFinancialEvents {
     #[structopt(long = "posted_after", parse(try_from_str))]
     posted_after: NaiveDate,
@@ -91,6 +148,8 @@ enum Command {
     #[structopt(long = "out", parse(from_os_str))]
     outdir: PathBuf,
   },
+  /// Writes the orders and their items of each page to `orders_{page}.json`. With `--format csv`
+  /// or `--format ndjson` the rows of `OrderExporter` are written instead.
   ListOrders {
     #[structopt(long = "marketplace")]
     marketplace_id_list: Vec<String>,
@@ -98,11 +157,57 @@ enum Command {
     created_after: NaiveDate,
     #[structopt(long = "out", parse(from_os_str))]
     outdir: PathBuf,
+    /// Write one row per order instead of one row per order item, `csv` and `ndjson` only
+    #[structopt(long = "per_order")]
+    per_order: bool,
+  },
+  /// Calls any MWS action and prints the result element as XML, or as JSON with `--format json`,
+  /// e.g. `call --path /Finances/2015-05-01 --version 2015-05-01
+  /// --action ListFinancialEventGroups --param FinancialEventGroupStartedAfter=2019-01-01T00:00:00Z`
+  Call {
+    #[structopt(long = "path")]
+    path: String,
+    #[structopt(long = "version")]
+    version: String,
+    #[structopt(long = "action")]
+    action: String,
+    /// `Key=Value`, can be repeated
+    #[structopt(long = "param", parse(try_from_str = parse_param))]
+    params: Vec<(String, String)>,
+    /// `Key=A,B` expands to `Key.1=A&Key.2=B`, can be repeated
+    #[structopt(long = "list", parse(try_from_str = parse_list_param))]
+    lists: Vec<Vec<(String, String)>>,
+    /// Request the following pages with `{action}ByNextToken`
+    #[structopt(long = "follow_next_token")]
+    follow_next_token: bool,
+  },
+}
+
+impl Command {
+  /// Values of the global `--format` supported by the subcommand, the first one is the default
+  fn output_formats(&self) -> &'static [OutputFormat] {
+    match *self {
+      Command::ReportGet { .. } | Command::EncodingConv { .. } => &[],
+      Command::ListFinancialEvents { .. } => &[OutputFormat::Json, OutputFormat::Xml],
+      Command::ListOrders { .. } => &[OutputFormat::Json, OutputFormat::Csv, OutputFormat::Ndjson],
+      Command::Call { .. } => &[OutputFormat::Xml, OutputFormat::Debug, OutputFormat::Json],
+      _ => &[OutputFormat::Debug, OutputFormat::Json],
+    }
   }
 }
 
 fn main() {
   let opt = Opt::from_args();
+  let format = check_output_format(opt.format, opt.cmd.output_formats());
+  if let Command::ListOrders { per_order: true, .. } = opt.cmd {
+    if format == OutputFormat::Json {
+      structopt::clap::Error::with_description(
+        "--per_order requires `--format csv` or `--format ndjson`",
+        structopt::clap::ErrorKind::ArgumentConflict,
+      )
+      .exit()
+    }
+  }
 
   let env_path = opt.env.unwrap_or_else(|| PathBuf::from(".env"));
 
@@ -136,7 +241,7 @@ fn main() {
         },
       )
       .unwrap();
-      println!("{:#?}", res)
+      print_response(format, &res)
     }
     Command::ReportListRequestByIds { ids } => {
       use mws::reports::*;
@@ -149,7 +254,7 @@ fn main() {
       )
       .unwrap();
 
-      println!("{:#?}", res)
+      print_response(format, &res)
     }
     Command::ReportListRequestByTypes { types } => {
       use mws::reports::*;
@@ -162,22 +267,64 @@ fn main() {
       )
       .unwrap();
 
-      println!("{:#?}", res)
+      print_response(format, &res)
     }
     Command::ReportGet { id, out } => {
       use mws::reports::*;
       let mut out = std::fs::File::create(out).unwrap();
       GetReport(&client, id, &mut out).unwrap();
     }
-    Command::EncodingConvJp { input, out } => {
-      use encoding_rs::*;
-      let bytes = std::fs::read(input).unwrap();
-      let (cow, encoding_used, had_errors) = SHIFT_JIS.decode(&bytes);
-      if had_errors {
-        panic!("decode error.")
-      }
-      println!("encoding_used: {:?}", encoding_used);
-      std::fs::write(out, cow.as_ref()).unwrap();
+    Command::ReportFetch {
+      report_type,
+      start_date,
+      end_date,
+      report_options,
+      marketplace_id_list,
+      timeout,
+      encoding,
+      raw,
+      out,
+    } => {
+      use mws::encoding::ReportEncoding;
+      use mws::reports::*;
+      let mut f = std::fs::File::create(&out).unwrap();
+      let res = fetch_report(
+        &client,
+        RequestReportParameters {
+          ReportType: report_type,
+          StartDate: start_date.map(get_utc_datetime),
+          EndDate: end_date.map(get_utc_datetime),
+          ReportOptions: report_options,
+          MarketplaceIdList: marketplace_id_list,
+        },
+        &mut f,
+        PollPolicy {
+          timeout: std::time::Duration::from_secs(timeout),
+          ..Default::default()
+        },
+        match (raw, encoding) {
+          (true, _) => ReportEncoding::Raw,
+          (false, Some(encoding)) => ReportEncoding::Fixed(encoding),
+          (false, None) => ReportEncoding::Auto(None),
+        },
+      )
+      .unwrap();
+      print_response(format, &res)
+    }
+    Command::EncodingConv {
+      input,
+      out,
+      encoding,
+      marketplace_id,
+    } => {
+      use mws::encoding::*;
+      let encoding = encoding
+        .or_else(|| marketplace_id.and_then(|id| encoding_for_marketplace(&id)))
+        .expect("--encoding or a known --marketplace is required");
+      let input = std::fs::File::open(input).unwrap();
+      let mut out = std::fs::File::create(out).unwrap();
+      std::io::copy(&mut decode_reader(input, encoding), &mut out).unwrap();
+      println!("encoding_used: {}", encoding.name());
     }
     Command::ProductGetLowestPricedOffersForSKU {
       marketplace_id,
@@ -194,7 +341,7 @@ fn main() {
         },
       )
       .unwrap();
-      println!("{:#?}", res)
+      print_response(format, &res)
     }
     Command::ProductGetMyPriceForASIN {
       marketplace_id,
@@ -211,7 +358,7 @@ fn main() {
         },
       )
       .unwrap();
-      println!("{:#?}", res)
+      print_response(format, &res)
     }
     Command::SubmitFeed {
       feed_type,
@@ -236,112 +383,70 @@ fn main() {
         content_type,
       )
       .unwrap();
-      println!("{:#?}", res)
+      print_response(format, &res)
     }
     Command::ListSubscriptions { marketplace_id } => {
       use mws::subscriptions::*;
       let res = ListSubscriptions(&client, marketplace_id).unwrap();
-      println!("{:#?}", res)
+      print_response(format, &res)
     }
     Command::ListFinancialEvents {
       posted_after,
       posted_before,
       outdir,
     } => {
-      use std::thread::sleep;
-      use std::time::Duration;
+      use mws::finances::*;
+      let params = ListFinancialEventsParameters {
+        PostedAfter: Some(get_utc_datetime(posted_after)),
+        PostedBefore: Some(get_utc_datetime(posted_before)),
+        ..Default::default()
+      };
       let mut page = 1;
-      let mut next_token: Option<String> = None;
-
-      let duration = posted_before - posted_after;
-      let days = duration.num_days();
-
-      if days < 0 {
-        panic!("invalid date range")
-      }
-
-      let chunks = ((days as f64) / 180.0).ceil() as i64;
-      let ranges: Vec<[NaiveDate; 2]> = (0..chunks).into_iter().map(|chunk_idx| {
-        [posted_after + chrono::Duration::days(chunk_idx * 180), std::cmp::min(posted_before, posted_after + chrono::Duration::days((chunk_idx + 1) * 180))]
-      }).collect();
-
-      println!("ranges = {:?}", ranges);
-
-      for [posted_after, posted_before] in ranges {
-        loop {
-          println!("loading page {} ...", page);
-
-          let res = if let Some(next_token) = next_token.clone() {
-            client
-              .request_xml_generic(
-                Method::POST,
-                "/Finances",
-                "2015-05-01",
-                "ListFinancialEventsByNextToken",
-                vec![
-                  (
-                    "NextToken".to_string(),
-                    next_token,
-                  ),
-                ],
-              )
-          } else {
-            client
-              .request_xml_generic(
-                Method::POST,
-                "/Finances",
-                "2015-05-01",
-                "ListFinancialEvents",
-                vec![
-                  (
-                    "PostedAfter".to_string(),
-                    get_utc_datetime(posted_after).to_rfc3339(),
-                  ),
-                  (
-                    "PostedBefore".to_string(),
-                    get_utc_datetime(posted_before).to_rfc3339(),
-                  ),
-                ],
-              )
-          };
-
-          
-          let res = match res {
-            Ok(res) => res,
-            Err(err) => {
-              if err.should_try_again() {
-                eprintln!("RetriableException: {:?}", err);
-                continue;
-              } else {
-                Err(err).unwrap()
-              }
-            }
-          };
-
-          let filename = format!("financial_events_{}_{}_{}.xml", posted_after, posted_before, page);
-          let f = std::fs::File::create(outdir.join(filename)).unwrap();
-          res.result_element.write(f).unwrap();
-
-          next_token = res.next_token().clone().map(|v| v.to_string());
-          if next_token.is_none() {
-            break;
-          }
-
-          page += 1;
-
-          sleep(Duration::from_secs(1));
-        }
+      let mut create_file = || {
+        println!("loading page {} ...", page);
+        let filename = format!(
+          "financial_events_{}_{}_{}.{}",
+          posted_after,
+          posted_before,
+          page,
+          format.as_str()
+        );
+        page += 1;
+        std::fs::File::create(outdir.join(filename)).unwrap()
+      };
+      match format {
+        OutputFormat::Xml => list_financial_events_xml_by_date_range(&client, params, |res| {
+          res.result_element.write(create_file()).unwrap();
+          Ok(())
+        }),
+        _ => list_financial_events_by_date_range(&client, params, |res| {
+          serde_json::to_writer_pretty(create_file(), &res.FinancialEvents).unwrap();
+          Ok(())
+        }),
       }
+      .unwrap();
     }
     Command::ListOrders {
       marketplace_id_list,
       created_after,
-      outdir
+      outdir,
+      per_order,
     } => {
       use std::thread::sleep;
       use std::time::Duration;
       use mws::orders::*;
 
+      let export_format = match format {
+        OutputFormat::Csv => Some(OrderExportFormat::Csv),
+        OutputFormat::Ndjson => Some(OrderExportFormat::Ndjson),
+        _ => None,
+      };
+      let granularity = if per_order {
+        OrderExportGranularity::PerOrder
+      } else {
+        OrderExportGranularity::PerItem
+      };
+
       let mut page = 1;
       let mut next_token = None;
 
@@ -363,9 +468,14 @@ fn main() {
           Ok(res) =>{
             next_token = res.NextToken;
             let orders = res.Orders;
-            let mut json_orders = vec![];
             println!("orders = {}", orders.len());
 
+            let path = outdir.join(format!("orders_{}.{}", page, format.as_str()));
+            let mut exporter = export_format.map(|export_format| {
+              OrderExporter::new(std::fs::File::create(&path).unwrap(), export_format, granularity)
+            });
+            let mut json_orders = vec![];
+
             for order in orders {
               println!("loading items: {} {:?}", order.AmazonOrderId, order.PurchaseDate);
 
@@ -401,15 +511,24 @@ fn main() {
                 }
               }
 
-              json_orders.push(serde_json::json!({
-                "order": serde_json::to_value(&order).unwrap(),
-                "items": serde_json::to_value(&items).unwrap()
-              }));
+              match exporter {
+                Some(ref mut exporter) => exporter.write_order(&order, &items).unwrap(),
+                None => json_orders.push(serde_json::json!({
+                  "order": serde_json::to_value(&order).unwrap(),
+                  "items": serde_json::to_value(&items).unwrap()
+                })),
+              }
             }
 
-            let filename = format!("orders_{}.json", page);
-            let f = std::fs::File::create(outdir.join(filename)).unwrap();
-            serde_json::to_writer(f, &json_orders).unwrap();
+            match exporter {
+              Some(exporter) => {
+                exporter.finish().unwrap();
+              }
+              None => {
+                let f = std::fs::File::create(&path).unwrap();
+                serde_json::to_writer(f, &json_orders).unwrap();
+              }
+            }
           },
           Err(err) => {
             if err.should_try_again() {
@@ -429,6 +548,55 @@ fn main() {
         sleep(Duration::from_secs(5));
       }
     }
+    Command::Call {
+      path,
+      version,
+      action,
+      params,
+      lists,
+      follow_next_token,
+    } => {
+      use mws::client::Method;
+      use std::thread::sleep;
+      use std::time::Duration;
+      let mut params = params;
+      params.extend(lists.into_iter().flatten());
+      let mut res = client
+        .request_xml_generic(Method::POST, &path, &version, &action, params)
+        .unwrap();
+      loop {
+        match format {
+          OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&res.to_json()).unwrap()),
+          _ => {
+            res.result_element.write(std::io::stdout()).unwrap();
+            println!();
+          }
+        }
+
+        let next_token = match res.next_token() {
+          Some(token) if follow_next_token => token.to_string(),
+          _ => break,
+        };
+
+        sleep(Duration::from_secs(1));
+        res = loop {
+          let res = client.request_xml_generic(
+            Method::POST,
+            &path,
+            &version,
+            &format!("{}ByNextToken", action),
+            vec![("NextToken".to_string(), next_token.clone())],
+          );
+          match res {
+            Err(ref err) if err.should_try_again() => {
+              eprintln!("retrying: {}", err);
+              sleep(Duration::from_secs(10));
+            }
+            res => break res.unwrap(),
+          }
+        };
+      }
+    }
   }
 }
 
@@ -446,6 +614,72 @@ fn get_client(env: &Env) -> Client {
   Client::new(opts).unwrap()
 }
 
+fn parse_param(v: &str) -> Result<(String, String), String> {
+  match v.find('=') {
+    Some(pos) if pos > 0 => Ok((v[..pos].to_string(), v[pos + 1..].to_string())),
+    _ => Err(format!("invalid param: '{}', expecting `Key=Value`", v)),
+  }
+}
+
+fn parse_list_param(v: &str) -> Result<Vec<(String, String)>, String> {
+  let (key, values) = parse_param(v)?;
+  Ok(
+    values
+      .split(',')
+      .enumerate()
+      .map(|(i, value)| (format!("{}.{}", key, i + 1), value.to_string()))
+      .collect(),
+  )
+}
+
+fn parse_encoding(v: &str) -> Result<&'static mws::encoding::Encoding, String> {
+  mws::encoding::Encoding::for_label(v.as_bytes())
+    .ok_or_else(|| format!("unknown encoding: '{}'", v))
+}
+
+fn parse_output_format(v: &str) -> Result<OutputFormat, String> {
+  match v {
+    "debug" => Ok(OutputFormat::Debug),
+    "json" => Ok(OutputFormat::Json),
+    "xml" => Ok(OutputFormat::Xml),
+    "csv" => Ok(OutputFormat::Csv),
+    "ndjson" => Ok(OutputFormat::Ndjson),
+    _ => Err(format!(
+      "unknown format: '{}', expecting `debug`, `json`, `xml`, `csv` or `ndjson`",
+      v
+    )),
+  }
+}
+
+/// Returns the format to use, exits with an error if the subcommand doesn't support `format`
+fn check_output_format(format: Option<OutputFormat>, supported: &[OutputFormat]) -> OutputFormat {
+  use structopt::clap::{Error, ErrorKind};
+  match format {
+    Some(format) if supported.contains(&format) => format,
+    Some(format) => {
+      let expecting = if supported.is_empty() {
+        "the subcommand has no output format".to_string()
+      } else {
+        let names: Vec<_> = supported.iter().map(|v| format!("`{}`", v.as_str())).collect();
+        format!("expecting {}", names.join(" or "))
+      };
+      Error::with_description(
+        &format!("unsupported format: '{}', {}", format.as_str(), expecting),
+        ErrorKind::InvalidValue,
+      )
+      .exit()
+    }
+    None => supported.first().cloned().unwrap_or(OutputFormat::Debug),
+  }
+}
+
+fn print_response<T: std::fmt::Debug + serde::Serialize>(format: OutputFormat, res: &T) {
+  match format {
+    OutputFormat::Json => println!("{}", serde_json::to_string_pretty(res).unwrap()),
+    _ => println!("{:#?}", res),
+  }
+}
+
 fn get_utc_datetime(date: NaiveDate) -> DateTime<Utc> {
   Utc.from_utc_date(&date).and_hms(0, 0, 0)
 }
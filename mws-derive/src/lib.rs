@@ -360,7 +360,7 @@ pub fn derive_from_diff_row(input: TokenStream) -> TokenStream {
   let name = input.ident;
 
   let meta = if let Data::Struct(data) = input.data {
-    get_struct_meta(data, "from_tdff_row", &["key"])
+    get_struct_meta(data, "from_tdff_row", &["key", "required"])
   } else {
     panic!("only struct is supported.");
   };
@@ -400,9 +400,29 @@ pub fn derive_from_diff_row(input: TokenStream) -> TokenStream {
     })
     .collect();
 
+  let columns: Vec<_> = meta
+    .fields
+    .iter()
+    .map(|f| {
+      let keys: Vec<String> = match f.config_list.iter().find(|(k, _)| k == "key") {
+        Some(&(_, Some(ref v))) => v.split(',').map(|s| s.trim().to_string()).collect(),
+        _ => vec![format!("{}", f.ident)],
+      };
+      let required = f.config_list.iter().any(|(k, _)| k == "required");
+      quote! {
+        ::mws::tdff::TdffColumn { keys: &[#(#keys),*], required: #required },
+      }
+    })
+    .collect();
+
   let expanded = quote! {
     impl ::mws::tdff::FromTdffRow for #name
     {
+      fn tdff_columns() -> &'static [::mws::tdff::TdffColumn] {
+        const COLUMNS: &[::mws::tdff::TdffColumn] = &[#(#columns)*];
+        COLUMNS
+      }
+
       fn from_tdff_row(pairs: &::mws::tdff::TdffRow) -> ::mws::result::MwsResult<Self> {
         use ::mws::tdff::FromTdffField;
         let mut record = #name::default();
@@ -421,6 +441,102 @@ pub fn derive_from_diff_row(input: TokenStream) -> TokenStream {
   expanded.into()
 }
 
+#[proc_macro_derive(ToTdffRow, attributes(to_tdff_row))]
+pub fn derive_to_tdff_row(input: TokenStream) -> TokenStream {
+  let input: DeriveInput = syn::parse(input).unwrap();
+
+  let name = input.ident;
+
+  let struct_config =
+    get_attr_config_items("to_tdff_row", &input.attrs, &["template_type", "version"]);
+  let get_struct_config = |key: &str| {
+    struct_config
+      .iter()
+      .find(|(k, _)| k == key)
+      .and_then(|(_, v)| v.clone())
+  };
+
+  let meta = if let Data::Struct(data) = input.data {
+    get_struct_meta(data, "to_tdff_row", &["key", "label"])
+  } else {
+    panic!("only struct is supported.");
+  };
+
+  // `to_tdff_row(key)`, then the first `from_tdff_row(key)`, then the field name
+  let keys: Vec<String> = meta
+    .fields
+    .iter()
+    .map(|f| match f.config_list.iter().find(|(k, _)| k == "key") {
+      Some(&(_, Some(ref v))) => v.clone(),
+      _ => get_attr_config_items("from_tdff_row", &f.attrs, &["key", "required"])
+        .into_iter()
+        .find(|(k, _)| k == "key")
+        .and_then(|(_, v)| v)
+        .and_then(|v| v.split(',').next().map(|k| k.trim().to_string()))
+        .unwrap_or_else(|| format!("{}", f.ident)),
+    })
+    .collect();
+
+  let values: Vec<_> = meta
+    .fields
+    .iter()
+    .map(|f| {
+      let ident = &f.ident;
+      quote! {
+        ::mws::tdff::ToTdffField::to_tdff_field(&self.#ident),
+      }
+    })
+    .collect();
+
+  let header_rows = match get_struct_config("template_type") {
+    Some(template_type) => {
+      let mut template_row = vec![format!("TemplateType={}", template_type)];
+      if let Some(version) = get_struct_config("version") {
+        template_row.push(format!("Version={}", version));
+      }
+      let labels: Vec<String> = meta
+        .fields
+        .iter()
+        .zip(keys.iter())
+        .map(
+          |(f, key)| match f.config_list.iter().find(|(k, _)| k == "label") {
+            Some(&(_, Some(ref v))) => v.clone(),
+            _ => key.clone(),
+          },
+        )
+        .collect();
+      quote! {
+        fn tdff_header_rows() -> Vec<Vec<String>> {
+          vec![
+            vec![#(#template_row.to_string()),*],
+            vec![#(#labels.to_string()),*],
+            Self::tdff_keys().iter().map(ToString::to_string).collect(),
+          ]
+        }
+      }
+    }
+    None => quote! {},
+  };
+
+  let expanded = quote! {
+    impl ::mws::tdff::ToTdffRow for #name
+    {
+      fn tdff_keys() -> &'static [&'static str] {
+        const KEYS: &[&str] = &[#(#keys),*];
+        KEYS
+      }
+
+      #header_rows
+
+      fn to_tdff_row(&self) -> Vec<String> {
+        vec![#(#values)*]
+      }
+    }
+  };
+
+  expanded.into()
+}
+
 struct StructMeta {
   fields: Vec<StructFieldMeta>,
 }
@@ -428,6 +544,7 @@ struct StructMeta {
 struct StructFieldMeta {
   ident: Ident,
   ty: Type,
+  attrs: Vec<Attribute>,
   config_list: Vec<(String, Option<String>)>,
 }
 
@@ -444,6 +561,7 @@ fn get_struct_meta(data: DataStruct, config_attr_name: &str, config_key_wl: &[&s
       StructFieldMeta {
         ident: ident.clone(),
         ty: field.ty.clone(),
+        attrs: field.attrs.clone(),
         config_list: get_config_items(config_attr_name, field, config_key_wl),
       }
     })
@@ -453,8 +571,15 @@ fn get_struct_meta(data: DataStruct, config_attr_name: &str, config_key_wl: &[&s
 }
 
 fn get_config_items(attr_name: &str, field: &Field, wl: &[&str]) -> Vec<(String, Option<String>)> {
-  field
-    .attrs
+  get_attr_config_items(attr_name, &field.attrs, wl)
+}
+
+fn get_attr_config_items(
+  attr_name: &str,
+  attrs: &[Attribute],
+  wl: &[&str],
+) -> Vec<(String, Option<String>)> {
+  attrs
     .iter()
     .filter_map(|a| {
       a.parse_meta().ok().and_then(|meta| match meta {
@@ -0,0 +1,190 @@
+extern crate mws;
+
+use mws::reports::rows::*;
+use mws::tdff::{FromTdffRow, TdffHeaderMode, TdffParser};
+use std::io::Cursor;
+
+fn parse<T: FromTdffRow>(bytes: &'static [u8]) -> Vec<T> {
+  TdffParser::new(Cursor::new(bytes))
+    .unwrap()
+    .header_mode(TdffHeaderMode::Strict)
+    .parse_all::<T>()
+    .unwrap()
+}
+
+#[test]
+fn merchant_listings_all_data() {
+  let rows: Vec<MerchantListingsAllDataRow> =
+    parse(include_bytes!("./fixtures/merchant_listings_all_data.tdff"));
+  assert_eq!(rows.len(), 2);
+  assert_eq!(
+    rows[0],
+    MerchantListingsAllDataRow {
+      item_name: "Edifier R1280T Powered Bookshelf Speakers".to_string(),
+      listing_id: "0712ZYX1234".to_string(),
+      seller_sku: "edifier-r1280t".to_string(),
+      price: "99.99".to_string(),
+      quantity: 120,
+      open_date: "2018-07-12 05:23:11 PDT".to_string(),
+      item_is_marketplace: "y".to_string(),
+      product_id_type: "1".to_string(),
+      item_condition: "11".to_string(),
+      asin1: "B016P9HJIA".to_string(),
+      product_id: "B016P9HJIA".to_string(),
+      fulfillment_channel: "DEFAULT".to_string(),
+      merchant_shipping_group: "Migrated Template".to_string(),
+      status: "Active".to_string(),
+      ..Default::default()
+    }
+  );
+  assert_eq!(rows[1].quantity, 0);
+  assert_eq!(rows[1].pending_quantity, 3);
+  assert_eq!(rows[1].fulfillment_channel, "AMAZON_NA");
+}
+
+#[test]
+fn fba_myi_unsuppressed_inventory_data() {
+  let rows: Vec<FbaMyiUnsuppressedInventoryDataRow> = parse(include_bytes!(
+    "./fixtures/fba_myi_unsuppressed_inventory_data.tdff"
+  ));
+  assert_eq!(
+    rows[0],
+    FbaMyiUnsuppressedInventoryDataRow {
+      sku: "edifier-r1280t-fba".to_string(),
+      fnsku: "X0014ABCDE".to_string(),
+      asin: "B016P9HJIA".to_string(),
+      product_name: "Edifier R1280T Powered Bookshelf Speakers".to_string(),
+      condition: "New".to_string(),
+      your_price: "99.99".to_string(),
+      mfn_listing_exists: "No".to_string(),
+      mfn_fulfillable_quantity: 0,
+      afn_listing_exists: "Yes".to_string(),
+      afn_warehouse_quantity: 212,
+      afn_fulfillable_quantity: 198,
+      afn_unsellable_quantity: 2,
+      afn_reserved_quantity: 12,
+      afn_total_quantity: 236,
+      per_unit_volume: "0.87".to_string(),
+      afn_inbound_working_quantity: 0,
+      afn_inbound_shipped_quantity: 24,
+      afn_inbound_receiving_quantity: 0,
+    }
+  );
+  assert_eq!(rows[1].afn_inbound_working_quantity, 40);
+}
+
+#[test]
+fn afn_inventory_data() {
+  let rows: Vec<AfnInventoryDataRow> = parse(include_bytes!("./fixtures/afn_inventory_data.tdff"));
+  let tuples: Vec<_> = rows
+    .iter()
+    .map(|r| (r.seller_sku.as_ref(), r.warehouse_condition_code.as_ref(), r.quantity_available))
+    .collect();
+  assert_eq!(
+    tuples,
+    vec![
+      ("edifier-r1280t-fba", "SELLABLE", 198),
+      ("edifier-r1280t-fba", "UNSELLABLE", 2),
+    ]
+  );
+  assert_eq!(rows[0].fulfillment_channel_sku, "X0014ABCDE");
+  assert_eq!(rows[0].condition_type, "NewItem");
+}
+
+#[test]
+fn flat_file_all_orders_data() {
+  let rows: Vec<FlatFileAllOrdersDataRow> = parse(include_bytes!(
+    "./fixtures/flat_file_all_orders_data_by_last_update.tdff"
+  ));
+  assert_eq!(rows.len(), 2);
+  assert_eq!(rows[0].amazon_order_id, "111-3081581-6666666");
+  assert_eq!(rows[0].order_status, "Shipped");
+  assert_eq!(rows[0].quantity, 1);
+  assert_eq!(rows[0].item_price, "99.99");
+  assert_eq!(rows[0].item_tax, "6.50");
+  assert_eq!(rows[0].ship_postal_code, "92683-4617");
+  assert_eq!(rows[1].quantity, 2);
+  assert_eq!(rows[1].item_tax, "");
+  assert_eq!(rows[1].item_promotion_discount, "-50.0");
+  assert_eq!(rows[1].promotion_ids, "PLM-1234");
+  assert_eq!(rows[1].is_business_order, "true");
+  assert_eq!(rows[1].purchase_order_number, "PO-778");
+}
+
+#[test]
+fn fba_fulfillment_customer_returns_data() {
+  let rows: Vec<FbaFulfillmentCustomerReturnsDataRow> = parse(include_bytes!(
+    "./fixtures/fba_fulfillment_customer_returns_data.tdff"
+  ));
+  assert_eq!(
+    rows[1],
+    FbaFulfillmentCustomerReturnsDataRow {
+      return_date: "2019-01-07T09:02:44+00:00".to_string(),
+      order_id: "112-8095165-5463447".to_string(),
+      sku: "edifier-s1000db-fba".to_string(),
+      asin: "B01CDIS6M0".to_string(),
+      fnsku: "X0015FGHIJ".to_string(),
+      product_name: "Edifier S1000DB Audiophile Active Bookshelf Speakers".to_string(),
+      quantity: 1,
+      fulfillment_center_id: "PHX6".to_string(),
+      detailed_disposition: "CUSTOMER_DAMAGED".to_string(),
+      reason: "DEFECTIVE".to_string(),
+      status: "Reimbursed".to_string(),
+      license_plate_number: "LPNRR987654321".to_string(),
+      customer_comments: "Left speaker has no sound".to_string(),
+    }
+  );
+}
+
+#[test]
+fn v2_settlement_report_data_flat_file_v2() {
+  let rows: Vec<V2SettlementReportDataFlatFileV2Row> = parse(include_bytes!(
+    "./fixtures/v2_settlement_report_data_flat_file_v2.tdff"
+  ));
+  assert_eq!(rows.len(), 3);
+  assert_eq!(rows[0].settlement_id, "11720839581");
+  assert_eq!(rows[0].total_amount, "1234.56");
+  assert_eq!(rows[0].currency, "USD");
+  assert_eq!(rows[0].transaction_type, "");
+  let amounts: Vec<_> = rows[1..]
+    .iter()
+    .map(|r| {
+      (
+        r.order_id.as_ref(),
+        r.amount_type.as_ref(),
+        r.amount_description.as_ref(),
+        r.amount.as_ref(),
+        r.quantity_purchased,
+      )
+    })
+    .collect();
+  assert_eq!(
+    amounts,
+    vec![
+      ("111-3081581-6666666", "ItemPrice", "Principal", "99.99", 1),
+      ("111-3081581-6666666", "ItemFees", "Commission", "-15.00", 0),
+    ]
+  );
+  assert_eq!(rows[1].order_item_code, "46510268396154");
+  assert_eq!(rows[1].posted_date_time, "2019-01-02T10:12:45+00:00");
+}
+
+#[test]
+fn reserved_inventory_data() {
+  let rows: Vec<ReservedInventoryDataRow> =
+    parse(include_bytes!("./fixtures/reserved_inventory_data.tdff"));
+  assert_eq!(
+    rows[0],
+    ReservedInventoryDataRow {
+      sku: "edifier-r1280t-fba".to_string(),
+      fnsku: "X0014ABCDE".to_string(),
+      asin: "B016P9HJIA".to_string(),
+      product_name: "Edifier R1280T Powered Bookshelf Speakers".to_string(),
+      reserved_qty: 12,
+      reserved_customerorders: 5,
+      reserved_fc_transfers: 6,
+      reserved_fc_processing: 1,
+    }
+  );
+  assert_eq!(rows[1].reserved_qty, 0);
+}
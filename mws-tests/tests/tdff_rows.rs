@@ -0,0 +1,209 @@
+#[macro_use]
+extern crate mws_derive;
+extern crate mws;
+
+use mws::result::MwsError;
+use mws::tdff::TdffParser;
+use std::io::Cursor;
+
+#[derive(Debug, Default, PartialEq, FromTdffRow)]
+struct S {
+  sku: String,
+  quantity: i32,
+}
+
+fn s(sku: &str, quantity: i32) -> S {
+  S {
+    sku: sku.to_string(),
+    quantity,
+  }
+}
+
+const MALFORMED: &[u8] = b"sku\tquantity
+a\t1
+b\tNaN
+c\t3
+d
+e\t5
+";
+
+#[test]
+fn rows_stream() {
+  let r = Cursor::new(&include_bytes!("./fixtures/report.tdff")[..]);
+  let mut rows = TdffParser::new(r).unwrap().rows::<S>();
+  assert_eq!(rows.next().unwrap().unwrap(), s("OQ-5B1U-8LAN", 57));
+  assert_eq!(rows.next().unwrap().unwrap(), s("edifier-760d", 19));
+  assert_eq!(rows.count(), 241);
+}
+
+#[test]
+fn rows_error_line() {
+  let rows: Vec<_> = TdffParser::new(Cursor::new(MALFORMED))
+    .unwrap()
+    .rows::<S>()
+    .collect();
+  assert_eq!(rows.len(), 5);
+  assert_eq!(rows[0].as_ref().unwrap(), &s("a", 1));
+  match rows[1] {
+    Err(MwsError::TdffRow { line: 3, ref error }) => match **error {
+      MwsError::ParseString { ref what, .. } => assert_eq!(what, "quantity"),
+      ref err => panic!("unexpected error: {:?}", err),
+    },
+    ref other => panic!("unexpected result: {:?}", other),
+  }
+  assert_eq!(rows[2].as_ref().unwrap(), &s("c", 3));
+  match rows[3] {
+    Err(MwsError::TdffRow { line: 5, ref error }) => match **error {
+      MwsError::Csv(_) => {}
+      ref err => panic!("unexpected error: {:?}", err),
+    },
+    ref other => panic!("unexpected result: {:?}", other),
+  }
+  assert_eq!(rows[4].as_ref().unwrap(), &s("e", 5));
+}
+
+#[test]
+fn rows_skip_errors() {
+  let mut lines = vec![];
+  let rows: Vec<_> = TdffParser::new(Cursor::new(MALFORMED))
+    .unwrap()
+    .rows::<S>()
+    .skip_errors(|err| match err {
+      MwsError::TdffRow { line, .. } => lines.push(line),
+      err => panic!("unexpected error: {:?}", err),
+    })
+    .collect();
+  assert_eq!(rows, vec![s("a", 1), s("c", 3), s("e", 5)]);
+  assert_eq!(lines, vec![3, 5]);
+}
+
+#[test]
+fn parse_all_error() {
+  let err = TdffParser::new(Cursor::new(MALFORMED))
+    .unwrap()
+    .parse_all::<S>()
+    .unwrap_err();
+  assert_eq!(
+    err.to_string(),
+    "tdff row error at line 3: parse string error: quantity : invalid digit found in string: 'NaN'"
+  );
+}
+
+#[test]
+fn with_encoding() {
+  use mws::encoding::{SHIFT_JIS, WINDOWS_1252};
+
+  // "sku\tquantity\n日本\t1\n" in Shift_JIS
+  let sjis: &[u8] = b"sku\tquantity\n\x93\xfa\x96\x7b\t1\n";
+  let rows = TdffParser::with_encoding(sjis, SHIFT_JIS)
+    .unwrap()
+    .parse_all::<S>()
+    .unwrap();
+  assert_eq!(rows, vec![s("日本", 1)]);
+
+  // "sku\tquantity\nCafé\t2\n" in Windows-1252
+  let cp1252: &[u8] = b"sku\tquantity\nCaf\xe9\t2\n";
+  let rows = TdffParser::with_encoding(cp1252, WINDOWS_1252)
+    .unwrap()
+    .parse_all::<S>()
+    .unwrap();
+  assert_eq!(rows, vec![s("Café", 2)]);
+}
+
+#[derive(Debug, Default, PartialEq, FromTdffRow)]
+struct Listing {
+  #[from_tdff_row(key = "seller-sku,sku", required)]
+  seller_sku: String,
+  #[from_tdff_row(key = "item-name")]
+  item_name: String,
+  #[from_tdff_row(required)]
+  price: String,
+  quantity: i32,
+}
+
+#[test]
+fn derive_tdff_columns() {
+  use mws::tdff::{FromTdffRow, TdffColumn};
+  assert_eq!(
+    Listing::tdff_columns(),
+    &[
+      TdffColumn {
+        keys: &["seller-sku", "sku"],
+        required: true,
+      },
+      TdffColumn {
+        keys: &["item-name"],
+        required: false,
+      },
+      TdffColumn {
+        keys: &["price"],
+        required: true,
+      },
+      TdffColumn {
+        keys: &["quantity"],
+        required: false,
+      },
+    ][..]
+  );
+}
+
+#[test]
+fn header_drift() {
+  use mws::tdff::TdffHeaderDrift;
+
+  let parser = TdffParser::new(Cursor::new(&b"sku\tItem Name\tcondition\n"[..])).unwrap();
+  assert_eq!(
+    parser.header_drift::<Listing>(),
+    TdffHeaderDrift {
+      missing_required: vec!["price".to_string()],
+      missing_optional: vec!["quantity".to_string()],
+      unexpected: vec!["condition".to_string()],
+      renamed: vec![("item-name".to_string(), "Item Name".to_string())],
+    }
+  );
+
+  let parser =
+    TdffParser::new(Cursor::new(&b"seller-sku\tprice\tquantity\tcondition\n"[..])).unwrap();
+  let drift = parser.header_drift::<Listing>();
+  assert!(!drift.is_breaking());
+  assert_eq!(drift.missing_optional, vec!["item-name".to_string()]);
+  assert_eq!(drift.unexpected, vec!["condition".to_string()]);
+}
+
+#[test]
+fn header_mode() {
+  use mws::tdff::TdffHeaderMode;
+
+  let data: &[u8] = b"sku\tItem Name\tprice\nedifier-r1280t\tSpeakers\t99.99\n";
+  let rows: Vec<_> = TdffParser::new(Cursor::new(data))
+    .unwrap()
+    .header_mode(TdffHeaderMode::Strict)
+    .rows::<Listing>()
+    .collect();
+  assert_eq!(rows.len(), 1);
+  assert_eq!(
+    rows[0].as_ref().unwrap_err().to_string(),
+    "tdff header drift: missing optional: quantity; renamed: 'item-name' -> 'Item Name'"
+  );
+
+  let rows = TdffParser::new(Cursor::new(data))
+    .unwrap()
+    .header_mode(TdffHeaderMode::Lenient)
+    .parse_all::<Listing>()
+    .unwrap();
+  assert_eq!(
+    rows,
+    vec![Listing {
+      seller_sku: "edifier-r1280t".to_string(),
+      price: "99.99".to_string(),
+      ..Default::default()
+    }]
+  );
+
+  let rows = TdffParser::new(Cursor::new(&b"seller-sku\tprice\nedifier-r1280t\t99.99\n"[..]))
+    .unwrap()
+    .header_mode(TdffHeaderMode::Strict)
+    .parse_all::<Listing>()
+    .unwrap();
+  assert_eq!(rows[0].price, "99.99");
+}
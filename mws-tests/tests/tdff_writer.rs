@@ -0,0 +1,76 @@
+#[macro_use]
+extern crate mws_derive;
+extern crate mws;
+
+use mws::tdff::TdffWriter;
+
+#[derive(Debug, Default, PartialEq, FromTdffRow, ToTdffRow)]
+struct Listing {
+  #[from_tdff_row(key = "sku, seller-sku")]
+  sku: String,
+  #[from_tdff_row(key = "item-name")]
+  item_name: String,
+  quantity: i32,
+  #[to_tdff_row(key = "fulfillment-latency")]
+  #[from_tdff_row(key = "fulfillment-latency")]
+  latency: i32,
+}
+
+#[derive(ToTdffRow)]
+#[to_tdff_row(template_type = "PriceInventory", version = "2018.0924")]
+struct PriceInventory {
+  #[to_tdff_row(label = "SKU")]
+  sku: String,
+  #[to_tdff_row(label = "Standard Price")]
+  price: Option<f64>,
+  #[to_tdff_row(label = "Quantity")]
+  quantity: Option<u32>,
+}
+
+fn to_string<T: mws::tdff::ToTdffRow>(rows: &[T]) -> String {
+  let mut w = TdffWriter::new(vec![]);
+  w.write_all(rows).unwrap();
+  String::from_utf8(w.into_inner().unwrap()).unwrap()
+}
+
+#[test]
+fn escape_fields() {
+  let rows = vec![
+    Listing {
+      sku: "edifier-r1280t".to_string(),
+      item_name: "Edifier\tR1280T\r\n\"Brown\"".to_string(),
+      quantity: 3,
+      latency: 0,
+    },
+    Listing {
+      sku: "edifier-s1000db".to_string(),
+      item_name: "Edifier S1000DB".to_string(),
+      quantity: 0,
+      latency: 2,
+    },
+  ];
+  assert_eq!(
+    to_string(&rows),
+    "sku\titem-name\tquantity\tfulfillment-latency
+edifier-r1280t\tEdifier R1280T  \"Brown\"\t3\t0
+edifier-s1000db\tEdifier S1000DB\t0\t2
+"
+  );
+}
+
+#[test]
+fn template_headers() {
+  let body = to_string(&[PriceInventory {
+    sku: "edifier-r1280t".to_string(),
+    price: Some(99.99),
+    quantity: None,
+  }]);
+  assert_eq!(
+    body,
+    "TemplateType=PriceInventory\tVersion=2018.0924
+SKU\tStandard Price\tQuantity
+sku\tprice\tquantity
+edifier-r1280t\t99.99\t
+"
+  );
+}
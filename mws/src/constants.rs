@@ -1,3 +1,5 @@
+use encoding_rs::{Encoding, SHIFT_JIS, UTF_8, WINDOWS_1252};
+
 pub struct AmazonRegion {
   pub id: &'static str,
   pub name: &'static str,
@@ -183,6 +185,15 @@ impl AmazonMarketplace {
       _ => Some(state.to_string()),
     }
   }
+
+  /// Returns the encoding of the flat file reports of this marketplace
+  pub fn report_encoding(&self) -> &'static Encoding {
+    match self.country_id {
+      "JP" => SHIFT_JIS,
+      "DE" | "ES" | "FR" | "GB" | "IT" | "NL" | "PL" | "SE" => WINDOWS_1252,
+      _ => UTF_8,
+    }
+  }
 }
 
 const US_STATES: &'static [(&'static str, &'static str)] = &[
@@ -0,0 +1,174 @@
+//! Character encodings of reports
+//!
+//! MWS returns flat file reports in the marketplace's legacy encoding, e.g. Shift_JIS (Windows-31J)
+//! in Japan and Windows-1252 in Europe. The helpers here transcode them to UTF-8 while reading.
+
+pub use encoding_rs::{Encoding, SHIFT_JIS, UTF_16BE, UTF_16LE, UTF_8, WINDOWS_1252};
+use encoding_rs_io::{DecodeReaderBytes, DecodeReaderBytesBuilder};
+use reports::ReportFormat;
+use std::io::Read;
+
+/// Decides how a downloaded report is transcoded.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ReportEncoding {
+  /// Keeps the bytes as returned by MWS
+  Raw,
+  /// Transcodes to UTF-8 from the `Content-Type` charset of the response.
+  ///
+  /// The given encoding is used when the response has no (known) charset, UTF-8 if it is `None`.
+  Auto(Option<&'static Encoding>),
+  /// Transcodes to UTF-8 from the given encoding, ignoring the `Content-Type` charset
+  Fixed(&'static Encoding),
+}
+
+impl Default for ReportEncoding {
+  fn default() -> Self {
+    ReportEncoding::Auto(None)
+  }
+}
+
+impl ReportEncoding {
+  /// Auto detects the encoding with the marketplace's report encoding as fallback
+  pub fn for_marketplace(marketplace_id: &str) -> Self {
+    ReportEncoding::Auto(encoding_for_marketplace(marketplace_id))
+  }
+
+  /// Turns `Auto` into `Raw` unless the report is a flat file or CSV report.
+  ///
+  /// XML reports declare their encoding, transcoding them would make the declaration wrong.
+  /// Reports of unknown format are kept as is too. `Fixed` is not changed.
+  pub fn for_report_format(self, format: Option<ReportFormat>) -> Self {
+    match (self, format) {
+      (ReportEncoding::Auto(_), Some(ReportFormat::Tdff))
+      | (ReportEncoding::Auto(_), Some(ReportFormat::Csv)) => self,
+      (ReportEncoding::Auto(_), _) => ReportEncoding::Raw,
+      _ => self,
+    }
+  }
+
+  /// Returns the encoding to transcode from, `None` if the bytes should be kept as is.
+  pub fn resolve(&self, content_type: Option<&str>) -> Option<&'static Encoding> {
+    match *self {
+      ReportEncoding::Raw => None,
+      ReportEncoding::Auto(fallback) => Some(
+        content_type
+          .and_then(encoding_from_content_type)
+          .or(fallback)
+          .unwrap_or(UTF_8),
+      ),
+      ReportEncoding::Fixed(encoding) => Some(encoding),
+    }
+  }
+}
+
+/// Returns the report encoding of a marketplace, see `AmazonMarketplace::report_encoding`.
+pub fn encoding_for_marketplace(marketplace_id: &str) -> Option<&'static Encoding> {
+  ::constants::get_marketplace(marketplace_id).map(|m| m.report_encoding())
+}
+
+/// Parses the charset parameter of a `Content-Type` header value,
+/// e.g. `text/plain;charset=Cp1252`.
+pub fn encoding_from_content_type(content_type: &str) -> Option<&'static Encoding> {
+  content_type
+    .split(';')
+    .skip(1)
+    .filter_map(|param| {
+      let mut parts = param.splitn(2, '=');
+      match (parts.next(), parts.next()) {
+        (Some(name), Some(value)) if name.trim().eq_ignore_ascii_case("charset") => {
+          Some(value.trim().trim_matches('"'))
+        }
+        _ => None,
+      }
+    })
+    .next()
+    .and_then(|label| Encoding::for_label(label.as_bytes()))
+}
+
+/// Wraps `source` in a reader that transcodes `encoding` to UTF-8.
+///
+/// A byte order mark takes precedence over `encoding`, so UTF-8 and UTF-16 files with BOM are
+/// always decoded correctly.
+pub fn decode_reader<R: Read>(source: R, encoding: &'static Encoding) -> DecodeReaderBytes<R, Vec<u8>> {
+  DecodeReaderBytesBuilder::new()
+    .encoding(Some(encoding))
+    .bom_override(true)
+    .build(source)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use constants::{MARKETPLACE_ID_DE, MARKETPLACE_ID_JP, MARKETPLACE_ID_PL, MARKETPLACE_ID_US};
+
+  #[test]
+  fn test_encoding_from_content_type() {
+    assert_eq!(
+      encoding_from_content_type("text/plain;charset=Cp1252"),
+      Some(WINDOWS_1252)
+    );
+    assert_eq!(
+      encoding_from_content_type("text/plain; charset=\"Windows-31J\""),
+      Some(SHIFT_JIS)
+    );
+    assert_eq!(
+      encoding_from_content_type("text/plain;charset=UTF-8"),
+      Some(UTF_8)
+    );
+    assert_eq!(encoding_from_content_type("text/plain"), None);
+    assert_eq!(encoding_from_content_type("text/plain;charset=x-unknown"), None);
+  }
+
+  #[test]
+  fn test_report_encoding_resolve() {
+    let jp = ReportEncoding::for_marketplace(MARKETPLACE_ID_JP);
+    assert_eq!(jp.resolve(None), Some(SHIFT_JIS));
+    assert_eq!(jp.resolve(Some("text/plain;charset=UTF-8")), Some(UTF_8));
+    assert_eq!(
+      ReportEncoding::for_marketplace(MARKETPLACE_ID_DE).resolve(None),
+      Some(WINDOWS_1252)
+    );
+    assert_eq!(
+      ReportEncoding::for_marketplace(MARKETPLACE_ID_US).resolve(None),
+      Some(UTF_8)
+    );
+    assert_eq!(
+      ReportEncoding::Fixed(SHIFT_JIS).resolve(Some("text/plain;charset=Cp1252")),
+      Some(SHIFT_JIS)
+    );
+    assert_eq!(ReportEncoding::Raw.resolve(Some("text/plain;charset=Cp1252")), None);
+    assert_eq!(
+      ReportEncoding::for_marketplace(MARKETPLACE_ID_PL).resolve(None),
+      Some(WINDOWS_1252)
+    );
+  }
+
+  #[test]
+  fn test_report_encoding_for_report_format() {
+    let auto = ReportEncoding::Auto(Some(SHIFT_JIS));
+    assert_eq!(auto.for_report_format(Some(ReportFormat::Tdff)), auto);
+    assert_eq!(auto.for_report_format(Some(ReportFormat::Csv)), auto);
+    assert_eq!(
+      auto.for_report_format(Some(ReportFormat::Xml)),
+      ReportEncoding::Raw
+    );
+    assert_eq!(auto.for_report_format(None), ReportEncoding::Raw);
+    let fixed = ReportEncoding::Fixed(SHIFT_JIS);
+    assert_eq!(fixed.for_report_format(Some(ReportFormat::Xml)), fixed);
+  }
+
+  #[test]
+  fn test_decode_reader() {
+    let mut out = String::new();
+    decode_reader(&b"\x93\xfa\x96\x7b"[..], SHIFT_JIS)
+      .read_to_string(&mut out)
+      .unwrap();
+    assert_eq!(out, "日本");
+
+    out.clear();
+    decode_reader(&b"\xff\xfeC\x00a\x00f\x00\xe9\x00"[..], WINDOWS_1252)
+      .read_to_string(&mut out)
+      .unwrap();
+    assert_eq!(out, "Café");
+  }
+}
@@ -0,0 +1,433 @@
+//! FBA inbound carton contents feed message (`_POST_FBA_INBOUND_CARTON_CONTENTS_`)
+//!
+//! Box contents information of an inbound shipment. Shipments without it are charged the manual
+//! processing fee of `InboundShipmentInfo::EstimatedBoxContentsFee`.
+//!
+//! [Documentation](https://images-na.ssl-images-amazon.com/images/G/01/rainier/help/xsd/release_1_9/CartonContentsRequest.xsd)
+
+use super::{write_element, write_message, write_optional_element};
+use chrono::NaiveDate;
+use feeds::validate::{validate_sku, Validate, Violation};
+use feeds::Envelope;
+use feeds::Message;
+use fulfillment_inbound_shipment::InboundShipmentItem;
+use result::{MwsError, MwsResult};
+use std::collections::HashMap;
+use xmlhelper::decode::{characters, fold_elements, FromXmlStream, XmlEventStream};
+use xmlhelper::encode::{self, XmlEventWriter, XmlWrite};
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
+pub struct CartonItem {
+  pub sku: String,
+  /// Units of the SKU in the carton
+  pub quantity_shipped: i32,
+  /// Units per case, `1` if the units are not case-packed
+  pub quantity_in_case: i32,
+  pub expiration_date: Option<NaiveDate>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
+pub struct Carton {
+  /// Unique within the shipment
+  pub carton_id: String,
+  pub items: Vec<CartonItem>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
+pub struct CartonContentsRequestMessage {
+  pub message_id: String,
+  pub shipment_id: String,
+  /// `NumCartons` is written as the number of cartons
+  pub cartons: Vec<Carton>,
+}
+
+/// Units of a SKU packed into a carton
+#[derive(Debug, Clone, PartialEq)]
+pub struct PackedItem {
+  pub sku: String,
+  pub quantity: i32,
+  pub expiration_date: Option<NaiveDate>,
+}
+
+/// A carton of the packing plan passed to `CartonContentsRequestMessage::from_packing_plan`
+#[derive(Debug, Clone, PartialEq)]
+pub struct CartonPacking {
+  pub carton_id: String,
+  pub items: Vec<PackedItem>,
+}
+
+impl CartonContentsRequestMessage {
+  /// Builds the message of shipment `shipment_id` from its items, e.g. the `ItemData` of
+  /// `ListInboundShipmentItems`, and the cartons they are packed into.
+  ///
+  /// Items of other shipments are ignored. Every SKU of the plan must be in the shipment, and the
+  /// packed units of each SKU must add up to its `QuantityShipped`.
+  pub fn from_packing_plan(
+    shipment_id: &str,
+    shipment_items: &[InboundShipmentItem],
+    plan: &[CartonPacking],
+  ) -> MwsResult<Self> {
+    let shipment_items: Vec<_> = shipment_items
+      .iter()
+      .filter(|item| item.ShipmentId == shipment_id)
+      .collect();
+    let mut packed: HashMap<&str, i32> = HashMap::new();
+    let mut cartons = vec![];
+    for carton in plan {
+      let mut items = vec![];
+      for packed_item in &carton.items {
+        let shipment_item = shipment_items
+          .iter()
+          .find(|item| item.SellerSKU == packed_item.sku)
+          .ok_or_else(|| {
+            MwsError::Msg(format!(
+              "SKU '{}' of carton '{}' is not in shipment '{}'",
+              packed_item.sku, carton.carton_id, shipment_id
+            ))
+          })?;
+        *packed.entry(&shipment_item.SellerSKU).or_insert(0) += packed_item.quantity;
+        items.push(CartonItem {
+          sku: packed_item.sku.clone(),
+          quantity_shipped: packed_item.quantity,
+          quantity_in_case: shipment_item.QuantityInCase.unwrap_or(1),
+          expiration_date: packed_item.expiration_date,
+        });
+      }
+      cartons.push(Carton {
+        carton_id: carton.carton_id.clone(),
+        items,
+      });
+    }
+
+    for item in &shipment_items {
+      let quantity = packed.get(item.SellerSKU.as_str()).cloned().unwrap_or(0);
+      if quantity != item.QuantityShipped {
+        return Err(MwsError::Msg(format!(
+          "SKU '{}' of shipment '{}': {} units packed, {} shipped",
+          item.SellerSKU, shipment_id, quantity, item.QuantityShipped
+        )));
+      }
+    }
+
+    Ok(CartonContentsRequestMessage {
+      message_id: String::new(),
+      shipment_id: shipment_id.to_string(),
+      cartons,
+    })
+  }
+}
+
+impl Message for CartonContentsRequestMessage {
+  fn get_message_type() -> &'static str {
+    "CartonContentsRequest"
+  }
+
+  fn message_id(&self) -> &str {
+    &self.message_id
+  }
+
+  fn set_message_id(&mut self, message_id: String) {
+    self.message_id = message_id;
+  }
+}
+
+impl Validate for CartonContentsRequestMessage {
+  fn validate(&self) -> Vec<Violation> {
+    let mut violations = vec![];
+    if self.shipment_id.is_empty() {
+      violations.push(Violation::new("shipment_id", "must not be empty"));
+    }
+    if self.cartons.is_empty() {
+      violations.push(Violation::new("cartons", "must not be empty"));
+    }
+    for carton in &self.cartons {
+      if carton.carton_id.is_empty() {
+        violations.push(Violation::new("cartons.carton_id", "must not be empty"));
+      }
+      for item in &carton.items {
+        validate_sku(&mut violations, "cartons.items.sku", &item.sku);
+        if item.quantity_shipped < 1 {
+          violations.push(Violation::new(
+            "cartons.items.quantity_shipped",
+            format!("must be positive: {}", item.quantity_shipped),
+          ));
+        }
+        if item.quantity_in_case < 1 {
+          violations.push(Violation::new(
+            "cartons.items.quantity_in_case",
+            format!("must be positive: {}", item.quantity_in_case),
+          ));
+        }
+      }
+    }
+    violations
+  }
+}
+
+impl<W: XmlEventWriter> XmlWrite<W> for Carton {
+  fn write_xml(&self, w: &mut W) -> encode::Result<()> {
+    write_xml!(w,
+      Carton[][
+        CartonId[][(&self.carton_id)]
+        [{
+          for item in &self.items {
+            write_xml!(w,
+              Item[][
+                SKU[][(&item.sku)]
+                [{
+                  write_element(w, "QuantityShipped", &item.quantity_shipped.to_string())?;
+                  write_element(w, "QuantityInCase", &item.quantity_in_case.to_string())?;
+                  write_optional_element(
+                    w,
+                    "ExpirationDate",
+                    item.expiration_date.map(|v| v.format("%Y-%m-%d").to_string()),
+                  )
+                }]
+              ]
+            )?;
+          }
+          Ok(())
+        }]
+      ]
+    )
+  }
+}
+
+impl<W: XmlEventWriter> XmlWrite<W> for CartonContentsRequestMessage {
+  fn write_xml(&self, w: &mut W) -> encode::Result<()> {
+    write_xml!(w,
+      CartonContentsRequest[][
+        ShipmentId[][(&self.shipment_id)]
+        [{
+          write_element(w, "NumCartons", &self.cartons.len().to_string())?;
+          for carton in &self.cartons {
+            carton.write_xml(w)?;
+          }
+          Ok(())
+        }]
+      ]
+    )
+  }
+}
+
+impl<W: XmlEventWriter> XmlWrite<W> for Envelope<CartonContentsRequestMessage> {
+  fn write_xml(&self, w: &mut W) -> encode::Result<()> {
+    self.write_envelope_xml(w, |w: &mut W| {
+      for message in self.messages.iter() {
+        write_message(w, message, |w| message.data.write_xml(w))?;
+      }
+      Ok(())
+    })
+  }
+}
+
+impl<S: XmlEventStream> FromXmlStream<S> for CartonItem {
+  fn from_xml(s: &mut S) -> MwsResult<Self> {
+    fold_elements(s, CartonItem::default(), |s, v| {
+      match s.local_name() {
+        "SKU" => v.sku = characters(s)?,
+        "QuantityShipped" => v.quantity_shipped = characters(s)?,
+        "QuantityInCase" => v.quantity_in_case = characters(s)?,
+        "ExpirationDate" => v.expiration_date = Some(characters(s)?),
+        _ => {}
+      }
+      Ok(())
+    })
+  }
+}
+
+impl<S: XmlEventStream> FromXmlStream<S> for Carton {
+  fn from_xml(s: &mut S) -> MwsResult<Self> {
+    fold_elements(s, Carton::default(), |s, v| {
+      match s.local_name() {
+        "CartonId" => v.carton_id = characters(s)?,
+        "Item" => v.items.push(CartonItem::from_xml(s)?),
+        _ => {}
+      }
+      Ok(())
+    })
+  }
+}
+
+/// `NumCartons` is not read, it is the number of `Carton` elements.
+impl<S: XmlEventStream> FromXmlStream<S> for CartonContentsRequestMessage {
+  fn from_xml(s: &mut S) -> MwsResult<Self> {
+    fold_elements(s, CartonContentsRequestMessage::default(), |s, v| {
+      match s.local_name() {
+        "ShipmentId" => v.shipment_id = characters(s)?,
+        "Carton" => v.cartons.push(Carton::from_xml(s)?),
+        _ => {}
+      }
+      Ok(())
+    })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use feeds::OperationType;
+  use xmlhelper::encode::{EmitterConfig, EventWriter};
+
+  fn shipment_item(shipment_id: &str, sku: &str, quantity: i32) -> InboundShipmentItem {
+    InboundShipmentItem {
+      ShipmentId: shipment_id.to_string(),
+      SellerSKU: sku.to_string(),
+      QuantityShipped: quantity,
+      ..Default::default()
+    }
+  }
+
+  fn packed(sku: &str, quantity: i32) -> PackedItem {
+    PackedItem {
+      sku: sku.to_string(),
+      quantity,
+      expiration_date: None,
+    }
+  }
+
+  #[test]
+  fn test_from_packing_plan() {
+    let mut case_packed = shipment_item("FBA15C2BBHFB", "sku-2", 24);
+    case_packed.QuantityInCase = Some(12);
+    let items = vec![
+      shipment_item("FBA15C2BBHFB", "sku-1", 10),
+      case_packed,
+      shipment_item("FBA15C2CDKLM", "sku-3", 5),
+    ];
+    let plan = vec![
+      CartonPacking {
+        carton_id: "1".to_string(),
+        items: vec![packed("sku-1", 6), packed("sku-2", 12)],
+      },
+      CartonPacking {
+        carton_id: "2".to_string(),
+        items: vec![packed("sku-1", 4), packed("sku-2", 12)],
+      },
+    ];
+    let message =
+      CartonContentsRequestMessage::from_packing_plan("FBA15C2BBHFB", &items, &plan).unwrap();
+    assert_eq!(message.shipment_id, "FBA15C2BBHFB");
+    assert_eq!(message.cartons.len(), 2);
+    assert_eq!(
+      message.cartons[1].items[1],
+      CartonItem {
+        sku: "sku-2".to_string(),
+        quantity_shipped: 12,
+        quantity_in_case: 12,
+        expiration_date: None,
+      }
+    );
+    assert_eq!(message.cartons[0].items[0].quantity_in_case, 1);
+    assert_eq!(message.validate(), vec![]);
+
+    let err = CartonContentsRequestMessage::from_packing_plan("FBA15C2BBHFB", &items, &plan[..1])
+      .unwrap_err();
+    assert_eq!(
+      err.to_string(),
+      "SKU 'sku-1' of shipment 'FBA15C2BBHFB': 6 units packed, 10 shipped"
+    );
+
+    let plan = vec![CartonPacking {
+      carton_id: "1".to_string(),
+      items: vec![packed("sku-3", 5)],
+    }];
+    let err =
+      CartonContentsRequestMessage::from_packing_plan("FBA15C2BBHFB", &items, &plan).unwrap_err();
+    assert_eq!(
+      err.to_string(),
+      "SKU 'sku-3' of carton '1' is not in shipment 'FBA15C2BBHFB'"
+    );
+  }
+
+  #[test]
+  fn test_carton_contents_feed() {
+    let mut writer =
+      EventWriter::new_with_config(vec![], EmitterConfig::new().perform_indent(true));
+
+    let mut e = Envelope::<CartonContentsRequestMessage>::new("M_EXAMPLE_123456".to_string());
+    e.add_message(
+      CartonContentsRequestMessage {
+        message_id: "1".to_string(),
+        shipment_id: "FBA15C2BBHFB".to_string(),
+        cartons: vec![
+          Carton {
+            carton_id: "1".to_string(),
+            items: vec![
+              CartonItem {
+                sku: "edifier-r1280t".to_string(),
+                quantity_shipped: 2,
+                quantity_in_case: 1,
+                expiration_date: None,
+              },
+              CartonItem {
+                sku: "coffee-beans-1kg".to_string(),
+                quantity_shipped: 12,
+                quantity_in_case: 12,
+                expiration_date: Some(NaiveDate::from_ymd_opt(2020, 3, 31).unwrap()),
+              },
+            ],
+          },
+          Carton {
+            carton_id: "2".to_string(),
+            items: vec![CartonItem {
+              sku: "edifier-r1280t".to_string(),
+              quantity_shipped: 1,
+              quantity_in_case: 1,
+              expiration_date: None,
+            }],
+          },
+        ],
+      },
+      Some(OperationType::Update),
+    );
+    e.write_xml(&mut writer).unwrap();
+
+    let xml = String::from_utf8(writer.into_inner()).unwrap();
+    assert_eq!(
+      xml,
+      r#"<?xml version="1.0" encoding="utf-8"?>
+<AmazonEnvelope xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance" xsi:noNamespaceSchemaLocation="amznenvelope.xsd">
+  <Header>
+    <DocumentVersion>1.01</DocumentVersion>
+    <MerchantIdentifier>M_EXAMPLE_123456</MerchantIdentifier>
+  </Header>
+  <MessageType>CartonContentsRequest</MessageType>
+  <Message>
+    <MessageID>1</MessageID>
+    <OperationType>Update</OperationType>
+    <CartonContentsRequest>
+      <ShipmentId>FBA15C2BBHFB</ShipmentId>
+      <NumCartons>2</NumCartons>
+      <Carton>
+        <CartonId>1</CartonId>
+        <Item>
+          <SKU>edifier-r1280t</SKU>
+          <QuantityShipped>2</QuantityShipped>
+          <QuantityInCase>1</QuantityInCase>
+        </Item>
+        <Item>
+          <SKU>coffee-beans-1kg</SKU>
+          <QuantityShipped>12</QuantityShipped>
+          <QuantityInCase>12</QuantityInCase>
+          <ExpirationDate>2020-03-31</ExpirationDate>
+        </Item>
+      </Carton>
+      <Carton>
+        <CartonId>2</CartonId>
+        <Item>
+          <SKU>edifier-r1280t</SKU>
+          <QuantityShipped>1</QuantityShipped>
+          <QuantityInCase>1</QuantityInCase>
+        </Item>
+      </Carton>
+    </CartonContentsRequest>
+  </Message>
+</AmazonEnvelope>"#
+    );
+    assert_eq!(
+      Envelope::<CartonContentsRequestMessage>::from_reader(xml.as_bytes()).unwrap(),
+      e
+    );
+  }
+}
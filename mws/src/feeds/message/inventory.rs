@@ -1,9 +1,12 @@
+use feeds::validate::{validate_sku, Validate, Violation};
 use feeds::Envelope;
 use feeds::Message;
+use result::MwsResult;
+use xmlhelper::decode::{characters, fold_elements, FromXmlStream, XmlEventStream};
 use xmlhelper::encode;
 
 #[allow(non_snake_case)]
-#[derive(Debug, PartialEq, Serialize)]
+#[derive(Debug, Default, PartialEq, Serialize)]
 pub struct InventoryMessage {
   pub message_id: String,
   /// Used to identify an individual product. Each product must have a SKU, and each SKU must
@@ -26,6 +29,34 @@ impl Message for InventoryMessage {
   fn get_message_type() -> &'static str {
     "Inventory"
   }
+
+  fn message_id(&self) -> &str {
+    &self.message_id
+  }
+
+  fn set_message_id(&mut self, message_id: String) {
+    self.message_id = message_id;
+  }
+}
+
+impl Validate for InventoryMessage {
+  fn validate(&self) -> Vec<Violation> {
+    let mut violations = vec![];
+    validate_sku(&mut violations, "sku", &self.sku);
+    if self.quantity < 0 {
+      violations.push(Violation::new(
+        "quantity",
+        format!("must not be negative: {}", self.quantity),
+      ));
+    }
+    if self.fulfillment_latency < 1 || self.fulfillment_latency > 30 {
+      violations.push(Violation::new(
+        "fulfillment_latency",
+        format!("must be between 1 and 30: {}", self.fulfillment_latency),
+      ));
+    }
+    violations
+  }
 }
 
 impl<W: encode::XmlEventWriter> encode::XmlWrite<W> for Envelope<InventoryMessage> {
@@ -64,6 +95,21 @@ impl<W: encode::XmlEventWriter> encode::XmlWrite<W> for Envelope<InventoryMessag
   }
 }
 
+impl<S: XmlEventStream> FromXmlStream<S> for InventoryMessage {
+  fn from_xml(s: &mut S) -> MwsResult<Self> {
+    fold_elements(s, InventoryMessage::default(), |s, v| {
+      match s.local_name() {
+        "SKU" => v.sku = characters(s)?,
+        "Quantity" => v.quantity = characters(s)?,
+        "FulfillmentLatency" => v.fulfillment_latency = characters(s)?,
+        "SwitchFulfillmentTo" => v.switch_fulfillment_to = Some(characters(s)?),
+        _ => {}
+      }
+      Ok(())
+    })
+  }
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
@@ -134,4 +180,54 @@ mod tests {
 </AmazonEnvelope>"#
     );
   }
+
+  #[test]
+  fn test_inventory_feed_round_trip() {
+    let mut e = Envelope::<InventoryMessage>::new("1234567890".to_owned());
+    e.add_message(
+      InventoryMessage {
+        message_id: "".to_owned(),
+        sku: "p1".to_owned(),
+        quantity: 100,
+        fulfillment_latency: 2,
+        switch_fulfillment_to: Some("MFN".to_owned()),
+      },
+      None,
+    )
+    .add_message(
+      InventoryMessage {
+        message_id: "".to_owned(),
+        sku: "p2".to_owned(),
+        quantity: 0,
+        fulfillment_latency: 1,
+        switch_fulfillment_to: None,
+      },
+      None,
+    );
+    let xml = e.to_xml_string().unwrap();
+    let parsed = Envelope::<InventoryMessage>::from_reader(xml.as_bytes()).unwrap();
+    assert_eq!(parsed.MerchantIdentifier, "1234567890");
+    assert_eq!(parsed.messages().len(), 2);
+    for (parsed, message) in parsed.messages().iter().zip(e.messages()) {
+      assert_eq!(parsed.data, message.data);
+    }
+    assert_eq!(parsed.to_xml_string().unwrap(), xml);
+  }
+
+  #[test]
+  fn test_parse_envelope_with_wrong_message_type() {
+    let xml = r#"<?xml version="1.0" encoding="utf-8"?>
+<AmazonEnvelope>
+  <Header>
+    <DocumentVersion>1.01</DocumentVersion>
+    <MerchantIdentifier>1234567890</MerchantIdentifier>
+  </Header>
+  <MessageType>Price</MessageType>
+</AmazonEnvelope>"#;
+    let err = Envelope::<InventoryMessage>::from_reader(xml.as_bytes()).unwrap_err();
+    assert_eq!(
+      err.to_string(),
+      "unexpected xml event: expected 'Inventory', found 'Price'"
+    );
+  }
 }
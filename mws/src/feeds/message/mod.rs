@@ -1,2 +1,175 @@
+pub mod carton_contents;
 pub mod inventory;
+pub mod order_acknowledgement;
+pub mod order_adjustment;
 pub mod order_fulfillment_data;
+pub mod overrides;
+pub mod price;
+pub mod product;
+pub mod product_image;
+pub mod relationship;
+
+use feeds::{Envelope, EnvelopeMessage, Message, OperationType};
+use result::{MwsError, MwsResult};
+use xmlhelper::decode::{
+  characters, fold_elements, ElementScopedStream, FromXmlStream, XmlEventStream,
+};
+use xmlhelper::encode::{self, XmlEvent, XmlEventWriter};
+
+/// Writes `<Message>` with its `MessageID` and `OperationType`, `f` writes the message body.
+pub(crate) fn write_message<W, M, F>(
+  w: &mut W,
+  message: &EnvelopeMessage<M>,
+  f: F,
+) -> encode::Result<()>
+where
+  W: XmlEventWriter,
+  M: Message,
+  F: FnOnce(&mut W) -> encode::Result<()>,
+{
+  let operation_type = message
+    .operation_type
+    .as_ref()
+    .unwrap_or(&OperationType::Update)
+    .as_str();
+  write_xml!(w,
+    Message[][
+      MessageID[][(message.data.message_id())]
+      OperationType[][(operation_type)]
+      [{ f(w) }]
+    ]
+  )
+}
+
+/// Writes `<name>value</name>`
+pub(crate) fn write_element<W: XmlEventWriter>(
+  w: &mut W,
+  name: &str,
+  value: &str,
+) -> encode::Result<()> {
+  w.write(XmlEvent::start_element(name).into())?;
+  w.write(XmlEvent::characters(value))?;
+  w.write(XmlEvent::end_element().into())
+}
+
+/// Writes `<name attr_name="attr_value">value</name>`
+pub(crate) fn write_element_with_attr<W: XmlEventWriter>(
+  w: &mut W,
+  name: &str,
+  attr_name: &str,
+  attr_value: &str,
+  value: &str,
+) -> encode::Result<()> {
+  w.write(
+    XmlEvent::start_element(name)
+      .attr(attr_name, attr_value)
+      .into(),
+  )?;
+  w.write(XmlEvent::characters(value))?;
+  w.write(XmlEvent::end_element().into())
+}
+
+/// Writes `<name>value</name>` if `value` is `Some`
+pub(crate) fn write_optional_element<W: XmlEventWriter, V: AsRef<str>>(
+  w: &mut W,
+  name: &str,
+  value: Option<V>,
+) -> encode::Result<()> {
+  match value {
+    Some(value) => write_element(w, name, value.as_ref()),
+    None => Ok(()),
+  }
+}
+
+/// Reads the children of `<AmazonEnvelope>`, the message bodies, e.g. `<Inventory>`, are read with
+/// `M::from_xml`.
+impl<S, M> FromXmlStream<S> for Envelope<M>
+where
+  S: XmlEventStream,
+  M: Message + for<'a, 'b> FromXmlStream<ElementScopedStream<'a, ElementScopedStream<'b, S>>>,
+{
+  fn from_xml(s: &mut S) -> MwsResult<Self> {
+    read_envelope(s, |s| M::from_xml(s))
+  }
+}
+
+/// Reads the children of `<AmazonEnvelope>`, `f` reads the message body.
+///
+/// `MessageID` is assigned to the message with `Message::set_message_id`.
+fn read_envelope<S, M, F>(s: &mut S, mut f: F) -> MwsResult<Envelope<M>>
+where
+  S: XmlEventStream,
+  M: Message,
+  F: FnMut(&mut ElementScopedStream<ElementScopedStream<S>>) -> MwsResult<M>,
+{
+  let message_type = M::get_message_type();
+  let mut envelope = Envelope::<M>::new(String::new());
+  fold_elements(s, (), |s, _| {
+    match s.local_name() {
+      "Header" => {
+        envelope.MerchantIdentifier = fold_elements(s, String::new(), |s, v| {
+          if s.local_name() == "MerchantIdentifier" {
+            *v = characters(s)?;
+          }
+          Ok(())
+        })?;
+      }
+      "MessageType" => {
+        let v: String = characters(s)?;
+        if v != message_type {
+          return Err(MwsError::UnexpectedXmlEvent {
+            expected: message_type.to_string(),
+            found: v,
+          });
+        }
+      }
+      "Message" => {
+        let mut message_id = String::new();
+        let mut operation_type = None;
+        let mut data = None;
+        fold_elements(s, (), |s, _| {
+          match s.local_name() {
+            "MessageID" => message_id = characters(s)?,
+            "OperationType" => operation_type = Some(read_operation_type(s)?),
+            name if name == message_type => data = Some(f(s)?),
+            _ => {}
+          }
+          Ok(())
+        })?;
+        let mut data = required(data, message_type)?;
+        data.set_message_id(message_id);
+        envelope.push_message(EnvelopeMessage {
+          data,
+          operation_type,
+        });
+      }
+      _ => {}
+    }
+    Ok(())
+  })?;
+  Ok(envelope)
+}
+
+fn read_operation_type<S: XmlEventStream>(s: &mut S) -> MwsResult<OperationType> {
+  let v: String = characters(s)?;
+  match v.as_ref() {
+    "Update" => Ok(OperationType::Update),
+    "Delete" => Ok(OperationType::Delete),
+    "PartialUpdate" => Ok(OperationType::PartialUpdate),
+    _ => Err(MwsError::ParseString {
+      what: "OperationType".to_string(),
+      message: format!("unknown value: '{}'", v),
+    }),
+  }
+}
+
+/// Returns the value of a required element, or an error if the element was missing
+pub(crate) fn required<T>(value: Option<T>, name: &str) -> MwsResult<T> {
+  value.ok_or_else(|| MwsError::UnexpectedEndOfXml(format!("expected element: {}", name)))
+}
+
+/// Reads an attribute of the current element
+pub(crate) fn read_attr<S: XmlEventStream>(s: &S, name: &str) -> Option<String> {
+  s.container_elem()
+    .and_then(|elem| elem.attributes.value(name))
+}
@@ -0,0 +1,221 @@
+//! Order acknowledgement feed message (`_POST_ORDER_ACKNOWLEDGEMENT_DATA_`)
+//!
+//! Confirms or cancels merchant fulfilled orders.
+//!
+//! [Documentation](https://images-na.ssl-images-amazon.com/images/G/01/rainier/help/xsd/release_1_9/OrderAcknowledgement.xsd)
+
+use super::{write_element, write_message, write_optional_element};
+use feeds::validate::{Validate, Violation};
+use feeds::Envelope;
+use feeds::Message;
+use result::MwsResult;
+use xmlhelper::decode::{characters, fold_elements, FromXmlStream, XmlEventStream};
+use xmlhelper::encode::{self, XmlEventWriter, XmlWrite};
+
+str_enum! {
+  pub enum AcknowledgementStatusCode {
+    Success,
+    Failure,
+  }
+}
+
+str_enum! {
+  pub enum CancelReason {
+    NoInventory,
+    ShippingAddressUndeliverable,
+    CustomerExchange,
+    BuyerCanceled,
+    GeneralAdjustment,
+    CarrierCreditDecision,
+    RiskAssessmentInformationNotValid,
+    CarrierCoverageFailure,
+    CustomerReturn,
+    MerchandiseNotReceived,
+  }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
+pub struct OrderAcknowledgementMessage {
+  pub message_id: String,
+  pub amazon_order_id: String,
+  pub merchant_order_id: Option<String>,
+  /// `Success` confirms the order, `Failure` cancels it
+  pub status_code: AcknowledgementStatusCode,
+  /// Items to cancel, leave empty to acknowledge the whole order
+  pub items: Vec<OrderAcknowledgementItem>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
+pub struct OrderAcknowledgementItem {
+  pub amazon_order_item_code: String,
+  pub merchant_order_item_id: Option<String>,
+  pub cancel_reason: Option<CancelReason>,
+}
+
+impl Message for OrderAcknowledgementMessage {
+  fn get_message_type() -> &'static str {
+    "OrderAcknowledgement"
+  }
+
+  fn message_id(&self) -> &str {
+    &self.message_id
+  }
+
+  fn set_message_id(&mut self, message_id: String) {
+    self.message_id = message_id;
+  }
+}
+
+impl Validate for OrderAcknowledgementMessage {
+  fn validate(&self) -> Vec<Violation> {
+    let mut violations = vec![];
+    if self.amazon_order_id.is_empty() {
+      violations.push(Violation::new("amazon_order_id", "must not be empty"));
+    }
+    violations
+  }
+}
+
+impl<W: XmlEventWriter> XmlWrite<W> for OrderAcknowledgementMessage {
+  fn write_xml(&self, w: &mut W) -> encode::Result<()> {
+    write_xml!(w,
+      OrderAcknowledgement[][
+        AmazonOrderID[][(&self.amazon_order_id)]
+        [{
+          write_optional_element(w, "MerchantOrderID", self.merchant_order_id.as_ref())?;
+          write_element(w, "StatusCode", self.status_code.as_ref())?;
+          for item in &self.items {
+            write_xml!(w,
+              Item[][
+                AmazonOrderItemCode[][(&item.amazon_order_item_code)]
+                [{
+                  write_optional_element(w, "MerchantOrderItemID", item.merchant_order_item_id.as_ref())?;
+                  write_optional_element(w, "CancelReason", item.cancel_reason.as_ref())
+                }]
+              ]
+            )?;
+          }
+          Ok(())
+        }]
+      ]
+    )
+  }
+}
+
+impl<W: XmlEventWriter> XmlWrite<W> for Envelope<OrderAcknowledgementMessage> {
+  fn write_xml(&self, w: &mut W) -> encode::Result<()> {
+    self.write_envelope_xml(w, |w: &mut W| {
+      for message in self.messages.iter() {
+        write_message(w, message, |w| message.data.write_xml(w))?;
+      }
+      Ok(())
+    })
+  }
+}
+
+impl<S: XmlEventStream> FromXmlStream<S> for OrderAcknowledgementItem {
+  fn from_xml(s: &mut S) -> MwsResult<Self> {
+    fold_elements(s, OrderAcknowledgementItem::default(), |s, v| {
+      match s.local_name() {
+        "AmazonOrderItemCode" => v.amazon_order_item_code = characters(s)?,
+        "MerchantOrderItemID" => v.merchant_order_item_id = Some(characters(s)?),
+        "CancelReason" => v.cancel_reason = Some(characters(s)?),
+        _ => {}
+      }
+      Ok(())
+    })
+  }
+}
+
+impl<S: XmlEventStream> FromXmlStream<S> for OrderAcknowledgementMessage {
+  fn from_xml(s: &mut S) -> MwsResult<Self> {
+    fold_elements(s, OrderAcknowledgementMessage::default(), |s, v| {
+      match s.local_name() {
+        "AmazonOrderID" => v.amazon_order_id = characters(s)?,
+        "MerchantOrderID" => v.merchant_order_id = Some(characters(s)?),
+        "StatusCode" => v.status_code = characters(s)?,
+        "Item" => v.items.push(OrderAcknowledgementItem::from_xml(s)?),
+        _ => {}
+      }
+      Ok(())
+    })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use feeds::OperationType;
+  use xmlhelper::encode::{EmitterConfig, EventWriter};
+
+  #[test]
+  fn test_order_acknowledgement_feed() {
+    let mut writer =
+      EventWriter::new_with_config(vec![], EmitterConfig::new().perform_indent(true));
+
+    let mut e = Envelope::<OrderAcknowledgementMessage>::new("M_EXAMPLE_123456".to_string());
+    e.add_message(
+      OrderAcknowledgementMessage {
+        message_id: "1".to_string(),
+        amazon_order_id: "112-3739032-8075461".to_string(),
+        merchant_order_id: Some("1001".to_string()),
+        status_code: AcknowledgementStatusCode::Success,
+        items: vec![],
+      },
+      Some(OperationType::Update),
+    )
+    .add_message(
+      OrderAcknowledgementMessage {
+        message_id: "2".to_string(),
+        amazon_order_id: "112-8170461-3093032".to_string(),
+        merchant_order_id: None,
+        status_code: AcknowledgementStatusCode::Failure,
+        items: vec![OrderAcknowledgementItem {
+          amazon_order_item_code: "56323517235162".to_string(),
+          merchant_order_item_id: None,
+          cancel_reason: Some(CancelReason::NoInventory),
+        }],
+      },
+      Some(OperationType::Update),
+    );
+    e.write_xml(&mut writer).unwrap();
+
+    let xml = String::from_utf8(writer.into_inner()).unwrap();
+    assert_eq!(
+      xml,
+      r#"<?xml version="1.0" encoding="utf-8"?>
+<AmazonEnvelope xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance" xsi:noNamespaceSchemaLocation="amznenvelope.xsd">
+  <Header>
+    <DocumentVersion>1.01</DocumentVersion>
+    <MerchantIdentifier>M_EXAMPLE_123456</MerchantIdentifier>
+  </Header>
+  <MessageType>OrderAcknowledgement</MessageType>
+  <Message>
+    <MessageID>1</MessageID>
+    <OperationType>Update</OperationType>
+    <OrderAcknowledgement>
+      <AmazonOrderID>112-3739032-8075461</AmazonOrderID>
+      <MerchantOrderID>1001</MerchantOrderID>
+      <StatusCode>Success</StatusCode>
+    </OrderAcknowledgement>
+  </Message>
+  <Message>
+    <MessageID>2</MessageID>
+    <OperationType>Update</OperationType>
+    <OrderAcknowledgement>
+      <AmazonOrderID>112-8170461-3093032</AmazonOrderID>
+      <StatusCode>Failure</StatusCode>
+      <Item>
+        <AmazonOrderItemCode>56323517235162</AmazonOrderItemCode>
+        <CancelReason>NoInventory</CancelReason>
+      </Item>
+    </OrderAcknowledgement>
+  </Message>
+</AmazonEnvelope>"#
+    );
+    assert_eq!(
+      Envelope::<OrderAcknowledgementMessage>::from_reader(xml.as_bytes()).unwrap(),
+      e
+    );
+  }
+}
@@ -0,0 +1,400 @@
+//! Order adjustment feed message (`_POST_PAYMENT_ADJUSTMENT_DATA_`)
+//!
+//! Refunds or partially refunds order items.
+//!
+//! [Documentation](https://images-na.ssl-images-amazon.com/images/G/01/rainier/help/xsd/release_1_9/OrderAdjustment.xsd)
+
+use super::price::{read_currency_amount, write_currency_amount, CurrencyAmount};
+use super::{required, write_element, write_message, write_optional_element};
+use feeds::validate::{Validate, Violation};
+use feeds::Envelope;
+use feeds::Message;
+use result::MwsResult;
+use xmlhelper::decode::{characters, fold_elements, FromXmlStream, XmlEventStream};
+use xmlhelper::encode::{self, XmlEventWriter, XmlWrite};
+
+str_enum! {
+  pub enum AdjustmentReason {
+    NoInventory,
+    CustomerReturn,
+    GeneralAdjustment,
+    CouldNotShip,
+    DifferentItem,
+    Abandoned,
+    CustomerCancel,
+    PriceError,
+    ProductOutofStock,
+    CustomerAddressIncorrect,
+    Exchange,
+    Other,
+    CarrierCreditDecision,
+    RiskAssessmentInformationNotValid,
+    CarrierCoverageFailure,
+    TransactionRecord,
+    Undeliverable,
+    RefusedDelivery,
+  }
+}
+
+str_enum! {
+  pub enum PriceAdjustmentType {
+    Principal,
+    Shipping,
+    Tax,
+    ShippingTax,
+    RestockingFee,
+    RestockingFeeTax,
+    GiftWrap,
+    GiftWrapTax,
+    Surcharge,
+    ReturnShipping,
+    Goodwill,
+    ExportCharge,
+    COD,
+    CODTax,
+    Other,
+    FreeReplacementReturnShipping,
+  }
+}
+
+str_enum! {
+  pub enum PromotionAdjustmentType {
+    Principal,
+    Shipping,
+  }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PriceAdjustmentComponent {
+  pub component_type: PriceAdjustmentType,
+  /// The amount to refund
+  pub amount: CurrencyAmount,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PromotionAdjustmentComponent {
+  pub component_type: PromotionAdjustmentType,
+  pub amount: CurrencyAmount,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
+pub struct PromotionAdjustment {
+  pub promotion_claim_code: Option<String>,
+  pub merchant_promotion_id: Option<String>,
+  pub components: Vec<PromotionAdjustmentComponent>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
+pub struct AdjustedItem {
+  pub amazon_order_item_code: String,
+  pub merchant_adjustment_item_id: Option<String>,
+  pub adjustment_reason: AdjustmentReason,
+  pub item_price_adjustments: Vec<PriceAdjustmentComponent>,
+  pub promotion_adjustments: Vec<PromotionAdjustment>,
+  pub quantity_cancelled: Option<i32>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
+pub struct OrderAdjustmentMessage {
+  pub message_id: String,
+  pub amazon_order_id: String,
+  pub merchant_order_id: Option<String>,
+  pub items: Vec<AdjustedItem>,
+}
+
+impl Message for OrderAdjustmentMessage {
+  fn get_message_type() -> &'static str {
+    "OrderAdjustment"
+  }
+
+  fn message_id(&self) -> &str {
+    &self.message_id
+  }
+
+  fn set_message_id(&mut self, message_id: String) {
+    self.message_id = message_id;
+  }
+}
+
+impl Validate for OrderAdjustmentMessage {
+  fn validate(&self) -> Vec<Violation> {
+    let mut violations = vec![];
+    if self.amazon_order_id.is_empty() {
+      violations.push(Violation::new("amazon_order_id", "must not be empty"));
+    }
+    if self.items.is_empty() {
+      violations.push(Violation::new("items", "must not be empty"));
+    }
+    for item in &self.items {
+      if let Some(quantity) = item.quantity_cancelled {
+        if quantity < 0 {
+          violations.push(Violation::new(
+            "items.quantity_cancelled",
+            format!("must not be negative: {}", quantity),
+          ));
+        }
+      }
+    }
+    violations
+  }
+}
+
+fn write_component<W: XmlEventWriter>(
+  w: &mut W,
+  component_type: &str,
+  amount: &CurrencyAmount,
+) -> encode::Result<()> {
+  write_xml!(w,
+    Component[][
+      Type[][(component_type)]
+      [{ write_currency_amount(w, "Amount", Some(amount)) }]
+    ]
+  )
+}
+
+impl<W: XmlEventWriter> XmlWrite<W> for AdjustedItem {
+  fn write_xml(&self, w: &mut W) -> encode::Result<()> {
+    write_xml!(w,
+      AdjustedItem[][
+        AmazonOrderItemCode[][(&self.amazon_order_item_code)]
+        [{
+          write_optional_element(w, "MerchantAdjustmentItemID", self.merchant_adjustment_item_id.as_ref())?;
+          write_element(w, "AdjustmentReason", self.adjustment_reason.as_ref())?;
+          if !self.item_price_adjustments.is_empty() {
+            write_xml!(w,
+              ItemPriceAdjustments[][
+                [{
+                  for c in &self.item_price_adjustments {
+                    write_component(w, c.component_type.as_ref(), &c.amount)?;
+                  }
+                  Ok(())
+                }]
+              ]
+            )?;
+          }
+          for promotion in &self.promotion_adjustments {
+            write_xml!(w,
+              PromotionAdjustments[][
+                [{
+                  write_optional_element(w, "PromotionClaimCode", promotion.promotion_claim_code.as_ref())?;
+                  write_optional_element(w, "MerchantPromotionID", promotion.merchant_promotion_id.as_ref())?;
+                  for c in &promotion.components {
+                    write_component(w, c.component_type.as_ref(), &c.amount)?;
+                  }
+                  Ok(())
+                }]
+              ]
+            )?;
+          }
+          write_optional_element(w, "QuantityCancelled", self.quantity_cancelled.map(|v| v.to_string()))
+        }]
+      ]
+    )
+  }
+}
+
+impl<W: XmlEventWriter> XmlWrite<W> for OrderAdjustmentMessage {
+  fn write_xml(&self, w: &mut W) -> encode::Result<()> {
+    write_xml!(w,
+      OrderAdjustment[][
+        AmazonOrderID[][(&self.amazon_order_id)]
+        [{
+          write_optional_element(w, "MerchantOrderID", self.merchant_order_id.as_ref())?;
+          for item in &self.items {
+            item.write_xml(w)?;
+          }
+          Ok(())
+        }]
+      ]
+    )
+  }
+}
+
+impl<W: XmlEventWriter> XmlWrite<W> for Envelope<OrderAdjustmentMessage> {
+  fn write_xml(&self, w: &mut W) -> encode::Result<()> {
+    self.write_envelope_xml(w, |w: &mut W| {
+      for message in self.messages.iter() {
+        write_message(w, message, |w| message.data.write_xml(w))?;
+      }
+      Ok(())
+    })
+  }
+}
+
+/// Reads `<Component>`, returns its `Type` and `Amount`
+fn read_component<S: XmlEventStream>(s: &mut S) -> MwsResult<(String, CurrencyAmount)> {
+  let mut component_type = None;
+  let mut amount = None;
+  fold_elements(s, (), |s, _| {
+    match s.local_name() {
+      "Type" => component_type = Some(characters(s)?),
+      "Amount" => amount = Some(read_currency_amount(s)?),
+      _ => {}
+    }
+    Ok(())
+  })?;
+  Ok((
+    required(component_type, "Type")?,
+    required(amount, "Amount")?,
+  ))
+}
+
+impl<S: XmlEventStream> FromXmlStream<S> for PromotionAdjustment {
+  fn from_xml(s: &mut S) -> MwsResult<Self> {
+    fold_elements(s, PromotionAdjustment::default(), |s, v| {
+      match s.local_name() {
+        "PromotionClaimCode" => v.promotion_claim_code = Some(characters(s)?),
+        "MerchantPromotionID" => v.merchant_promotion_id = Some(characters(s)?),
+        "Component" => {
+          let (component_type, amount) = read_component(s)?;
+          v.components.push(PromotionAdjustmentComponent {
+            component_type: PromotionAdjustmentType::from(component_type.as_ref()),
+            amount,
+          });
+        }
+        _ => {}
+      }
+      Ok(())
+    })
+  }
+}
+
+impl<S: XmlEventStream> FromXmlStream<S> for AdjustedItem {
+  fn from_xml(s: &mut S) -> MwsResult<Self> {
+    fold_elements(s, AdjustedItem::default(), |s, v| {
+      match s.local_name() {
+        "AmazonOrderItemCode" => v.amazon_order_item_code = characters(s)?,
+        "MerchantAdjustmentItemID" => v.merchant_adjustment_item_id = Some(characters(s)?),
+        "AdjustmentReason" => v.adjustment_reason = characters(s)?,
+        "ItemPriceAdjustments" => {
+          fold_elements(s, (), |s, _| {
+            if s.local_name() == "Component" {
+              let (component_type, amount) = read_component(s)?;
+              v.item_price_adjustments.push(PriceAdjustmentComponent {
+                component_type: PriceAdjustmentType::from(component_type.as_ref()),
+                amount,
+              });
+            }
+            Ok(())
+          })?;
+        }
+        "PromotionAdjustments" => v
+          .promotion_adjustments
+          .push(PromotionAdjustment::from_xml(s)?),
+        "QuantityCancelled" => v.quantity_cancelled = Some(characters(s)?),
+        _ => {}
+      }
+      Ok(())
+    })
+  }
+}
+
+impl<S: XmlEventStream> FromXmlStream<S> for OrderAdjustmentMessage {
+  fn from_xml(s: &mut S) -> MwsResult<Self> {
+    fold_elements(s, OrderAdjustmentMessage::default(), |s, v| {
+      match s.local_name() {
+        "AmazonOrderID" => v.amazon_order_id = characters(s)?,
+        "MerchantOrderID" => v.merchant_order_id = Some(characters(s)?),
+        "AdjustedItem" => v.items.push(AdjustedItem::from_xml(s)?),
+        _ => {}
+      }
+      Ok(())
+    })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use feeds::message::price::{CurrencyCode, Decimal};
+  use feeds::OperationType;
+  use xmlhelper::encode::{EmitterConfig, EventWriter};
+
+  #[test]
+  fn test_order_adjustment_feed() {
+    let mut writer =
+      EventWriter::new_with_config(vec![], EmitterConfig::new().perform_indent(true));
+
+    let usd = |cents| CurrencyAmount::new(CurrencyCode::USD, Decimal::new(cents, 2));
+    let mut e = Envelope::<OrderAdjustmentMessage>::new("M_EXAMPLE_123456".to_string());
+    e.add_message(
+      OrderAdjustmentMessage {
+        message_id: "1".to_string(),
+        amazon_order_id: "112-3739032-8075461".to_string(),
+        merchant_order_id: None,
+        items: vec![AdjustedItem {
+          amazon_order_item_code: "56323517235162".to_string(),
+          merchant_adjustment_item_id: None,
+          adjustment_reason: AdjustmentReason::CustomerReturn,
+          item_price_adjustments: vec![
+            PriceAdjustmentComponent {
+              component_type: PriceAdjustmentType::Principal,
+              amount: usd(9999),
+            },
+            PriceAdjustmentComponent {
+              component_type: PriceAdjustmentType::Tax,
+              amount: usd(650),
+            },
+          ],
+          promotion_adjustments: vec![PromotionAdjustment {
+            promotion_claim_code: None,
+            merchant_promotion_id: Some("FREESHIP".to_string()),
+            components: vec![PromotionAdjustmentComponent {
+              component_type: PromotionAdjustmentType::Shipping,
+              amount: usd(-499),
+            }],
+          }],
+          quantity_cancelled: Some(1),
+        }],
+      },
+      Some(OperationType::Update),
+    );
+    e.write_xml(&mut writer).unwrap();
+
+    let xml = String::from_utf8(writer.into_inner()).unwrap();
+    assert_eq!(
+      xml,
+      r#"<?xml version="1.0" encoding="utf-8"?>
+<AmazonEnvelope xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance" xsi:noNamespaceSchemaLocation="amznenvelope.xsd">
+  <Header>
+    <DocumentVersion>1.01</DocumentVersion>
+    <MerchantIdentifier>M_EXAMPLE_123456</MerchantIdentifier>
+  </Header>
+  <MessageType>OrderAdjustment</MessageType>
+  <Message>
+    <MessageID>1</MessageID>
+    <OperationType>Update</OperationType>
+    <OrderAdjustment>
+      <AmazonOrderID>112-3739032-8075461</AmazonOrderID>
+      <AdjustedItem>
+        <AmazonOrderItemCode>56323517235162</AmazonOrderItemCode>
+        <AdjustmentReason>CustomerReturn</AdjustmentReason>
+        <ItemPriceAdjustments>
+          <Component>
+            <Type>Principal</Type>
+            <Amount currency="USD">99.99</Amount>
+          </Component>
+          <Component>
+            <Type>Tax</Type>
+            <Amount currency="USD">6.50</Amount>
+          </Component>
+        </ItemPriceAdjustments>
+        <PromotionAdjustments>
+          <MerchantPromotionID>FREESHIP</MerchantPromotionID>
+          <Component>
+            <Type>Shipping</Type>
+            <Amount currency="USD">-4.99</Amount>
+          </Component>
+        </PromotionAdjustments>
+        <QuantityCancelled>1</QuantityCancelled>
+      </AdjustedItem>
+    </OrderAdjustment>
+  </Message>
+</AmazonEnvelope>"#
+    );
+    assert_eq!(
+      Envelope::<OrderAdjustmentMessage>::from_reader(xml.as_bytes()).unwrap(),
+      e
+    );
+  }
+}
@@ -1,31 +1,168 @@
-use chrono::{DateTime, Utc};
+use super::write_element;
+use chrono::{DateTime, TimeZone, Utc};
+use feeds::validate::{Validate, Violation};
 use feeds::Envelope;
 use feeds::Message;
+use result::MwsResult;
 use types::ToIso8601;
+use xmlhelper::decode::{characters, fold_elements, FromXmlStream, XmlEventStream};
 use xmlhelper::encode;
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct OrderFulfillmentMessage {
   pub message_id: String,
   pub amazon_order_id: String,
   pub fulfillment_date: DateTime<Utc>,
+  /// One of `CARRIER_CODES`, written as `<CarrierCode>`
+  pub carrier_code: Option<String>,
+  /// Free text carrier name, written as `<CarrierName>` if `carrier_code` is `None`
   pub carrier_name: String,
   pub shipping_method: String,
   pub shipper_tracking_number: String,
   pub items: Vec<OrderFulfillmentItem>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
 pub struct OrderFulfillmentItem {
   pub amazon_order_item_code: String,
   pub quantity: i32,
   pub transparency_code: Option<String>,
 }
 
+impl Default for OrderFulfillmentMessage {
+  fn default() -> Self {
+    OrderFulfillmentMessage {
+      message_id: String::new(),
+      amazon_order_id: String::new(),
+      fulfillment_date: Utc.timestamp_opt(0, 0).unwrap(),
+      carrier_code: None,
+      carrier_name: String::new(),
+      shipping_method: String::new(),
+      shipper_tracking_number: String::new(),
+      items: vec![],
+    }
+  }
+}
+
 impl Message for OrderFulfillmentMessage {
   fn get_message_type() -> &'static str {
     "OrderFulfillment"
   }
+
+  fn message_id(&self) -> &str {
+    &self.message_id
+  }
+
+  fn set_message_id(&mut self, message_id: String) {
+    self.message_id = message_id;
+  }
+}
+
+/// Carrier codes accepted by Amazon, see `CarrierCode` in
+/// [amzn-base.xsd](https://images-na.ssl-images-amazon.com/images/G/01/rainier/help/xsd/release_1_9/amzn-base.xsd)
+pub const CARRIER_CODES: &[&str] = &[
+  "USPS",
+  "UPS",
+  "UPSMI",
+  "FedEx",
+  "DHL",
+  "Fastway",
+  "GLS",
+  "GO!",
+  "Hermes Logistik Gruppe",
+  "Royal Mail",
+  "Parcelforce",
+  "City Link",
+  "TNT",
+  "Target",
+  "SagawaExpress",
+  "NipponExpress",
+  "YamatoTransport",
+  "DHL Global Mail",
+  "UPS Mail Innovations",
+  "FedEx SmartPost",
+  "OSM",
+  "OnTrac",
+  "Streamlite",
+  "Newgistics",
+  "Canada Post",
+  "Blue Package",
+  "Chronopost",
+  "Deutsche Post",
+  "DPD",
+  "La Poste",
+  "Parcelnet",
+  "Poste Italiane",
+  "SDA",
+  "Smartmail",
+  "FEDEX_JP",
+  "JP_EXPRESS",
+  "NITTSU",
+  "SAGAWA",
+  "YAMATO",
+  "BlueDart",
+  "AFL/Fedex",
+  "Aramex",
+  "India Post",
+  "Professional",
+  "DTDC",
+  "Overnite Express",
+  "First Flight",
+  "Delhivery",
+  "Lasership",
+  "Yodel",
+  "Other",
+];
+
+impl Validate for OrderFulfillmentMessage {
+  fn validate(&self) -> Vec<Violation> {
+    let mut violations = vec![];
+    if self.amazon_order_id.is_empty() {
+      violations.push(Violation::new("amazon_order_id", "must not be empty"));
+    }
+    if self.fulfillment_date > Utc::now() {
+      violations.push(Violation::new(
+        "fulfillment_date",
+        format!(
+          "must not be in the future: {}",
+          self.fulfillment_date.to_iso8601()
+        ),
+      ));
+    }
+    match self.carrier_code {
+      Some(ref carrier_code) => {
+        if !CARRIER_CODES.contains(&carrier_code.as_str()) {
+          violations.push(Violation::new(
+            "carrier_code",
+            format!("unknown carrier code: '{}'", carrier_code),
+          ));
+        }
+        if !self.carrier_name.is_empty() {
+          violations.push(Violation::new(
+            "carrier_name",
+            "must be empty if carrier_code is set",
+          ));
+        }
+      }
+      None => {
+        if self.carrier_name.is_empty() {
+          violations.push(Violation::new(
+            "carrier_name",
+            "must not be empty if carrier_code is not set",
+          ));
+        }
+      }
+    }
+    for item in &self.items {
+      if item.quantity < 1 {
+        violations.push(Violation::new(
+          "items.quantity",
+          format!("must be positive: {}", item.quantity),
+        ));
+      }
+    }
+    violations
+  }
 }
 
 impl<W: encode::XmlEventWriter> encode::XmlWrite<W> for Envelope<OrderFulfillmentMessage> {
@@ -49,9 +186,12 @@ impl<W: encode::XmlEventWriter> encode::XmlWrite<W> for Envelope<OrderFulfillmen
                 (&fulfillment_date)
               ]
               FulfillmentData[][
-                CarrierName[][
-                  (&message.data.carrier_name)
-                ]
+                [{
+                  match message.data.carrier_code {
+                    Some(ref carrier_code) => write_element(w, "CarrierCode", carrier_code),
+                    None => write_element(w, "CarrierName", &message.data.carrier_name),
+                  }
+                }]
                 ShippingMethod[][
                   (&message.data.shipping_method)
                 ]
@@ -100,6 +240,46 @@ impl<W: encode::XmlEventWriter> encode::XmlWrite<W> for Envelope<OrderFulfillmen
   }
 }
 
+impl<S: XmlEventStream> FromXmlStream<S> for OrderFulfillmentItem {
+  fn from_xml(s: &mut S) -> MwsResult<Self> {
+    fold_elements(s, OrderFulfillmentItem::default(), |s, v| {
+      match s.local_name() {
+        "AmazonOrderItemCode" => v.amazon_order_item_code = characters(s)?,
+        "Quantity" => v.quantity = characters(s)?,
+        "TransparencyCode" => v.transparency_code = Some(characters(s)?),
+        _ => {}
+      }
+      Ok(())
+    })
+  }
+}
+
+impl<S: XmlEventStream> FromXmlStream<S> for OrderFulfillmentMessage {
+  fn from_xml(s: &mut S) -> MwsResult<Self> {
+    fold_elements(s, OrderFulfillmentMessage::default(), |s, v| {
+      match s.local_name() {
+        "AmazonOrderID" => v.amazon_order_id = characters(s)?,
+        "FulfillmentDate" => v.fulfillment_date = characters(s)?,
+        "FulfillmentData" => {
+          fold_elements(s, (), |s, _| {
+            match s.local_name() {
+              "CarrierCode" => v.carrier_code = Some(characters(s)?),
+              "CarrierName" => v.carrier_name = characters(s)?,
+              "ShippingMethod" => v.shipping_method = characters(s)?,
+              "ShipperTrackingNumber" => v.shipper_tracking_number = characters(s)?,
+              _ => {}
+            }
+            Ok(())
+          })?;
+        }
+        "Item" => v.items.push(OrderFulfillmentItem::from_xml(s)?),
+        _ => {}
+      }
+      Ok(())
+    })
+  }
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
@@ -125,6 +305,7 @@ mod tests {
           )
           .unwrap()
           .with_timezone(&Utc),
+          carrier_code: None,
           carrier_name: "UPS".to_string(),
           shipping_method: "Standard".to_string(),
           shipper_tracking_number: "1Z71178X0261236762".to_string(),
@@ -169,4 +350,58 @@ mod tests {
 </AmazonEnvelope>"#
     );
   }
+
+  #[test]
+  fn test_order_fulfillment_feed_carrier_code() {
+    let mut e = Envelope::<OrderFulfillmentMessage>::new("1234567890".to_owned());
+    e.add_message(
+      OrderFulfillmentMessage {
+        message_id: "1".to_string(),
+        amazon_order_id: "112-3739032-8075461".to_string(),
+        fulfillment_date: Utc.timestamp_opt(1544116111, 0).unwrap(),
+        carrier_code: Some("UPS".to_string()),
+        carrier_name: String::new(),
+        shipping_method: "Standard".to_string(),
+        shipper_tracking_number: "1Z71178X0261236762".to_string(),
+        items: vec![],
+      },
+      None,
+    );
+    let xml = e.to_xml_string().unwrap();
+    assert!(xml.contains("<CarrierCode>UPS</CarrierCode>"));
+    assert!(!xml.contains("CarrierName"));
+  }
+
+  #[test]
+  fn test_order_fulfillment_feed_round_trip() {
+    let mut e = Envelope::<OrderFulfillmentMessage>::new("1234567890".to_owned());
+    e.add_message(
+      OrderFulfillmentMessage {
+        message_id: "".to_string(),
+        amazon_order_id: "112-3739032-8075461".to_string(),
+        fulfillment_date: Utc.timestamp_opt(1544116111, 0).unwrap(),
+        carrier_code: None,
+        carrier_name: "UPS".to_string(),
+        shipping_method: "Standard".to_string(),
+        shipper_tracking_number: "1Z71178X0261236762".to_string(),
+        items: vec![
+          OrderFulfillmentItem {
+            amazon_order_item_code: "56323517235162".to_string(),
+            quantity: 1,
+            transparency_code: None,
+          },
+          OrderFulfillmentItem {
+            amazon_order_item_code: "56323517235163".to_string(),
+            quantity: 2,
+            transparency_code: Some("AZ:9CNJKDYB8Q4X".to_string()),
+          },
+        ],
+      },
+      None,
+    );
+    let xml = e.to_xml_string().unwrap();
+    let parsed = Envelope::<OrderFulfillmentMessage>::from_reader(xml.as_bytes()).unwrap();
+    assert_eq!(parsed.messages()[0].data, e.messages()[0].data);
+    assert_eq!(parsed.to_xml_string().unwrap(), xml);
+  }
 }
@@ -0,0 +1,246 @@
+//! Override feed message (`_POST_PRODUCT_OVERRIDES_DATA_`)
+//!
+//! Overrides the account level shipping settings of a SKU.
+//!
+//! [Documentation](https://images-na.ssl-images-amazon.com/images/G/01/rainier/help/xsd/release_1_9/Override.xsd)
+
+use super::price::{read_currency_amount, write_currency_amount, CurrencyAmount};
+use super::{required, write_element, write_message};
+use feeds::validate::{validate_sku, Validate, Violation};
+use feeds::Envelope;
+use feeds::Message;
+use result::{MwsError, MwsResult};
+use xmlhelper::decode::{characters, fold_elements, FromXmlStream, XmlEventStream};
+use xmlhelper::encode::{self, XmlEventWriter, XmlWrite};
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub enum ShippingOverrideKind {
+  /// `true` disables the ship option for the SKU, `false` enables it again
+  Restricted(bool),
+  /// `ShipAmount` is added to the account level shipping charge
+  Additive(CurrencyAmount),
+  /// `ShipAmount` replaces the account level shipping charge
+  Exclusive(CurrencyAmount),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ShippingOverride {
+  /// e.g. `Std Cont US Street Addr`
+  pub ship_option: String,
+  pub kind: ShippingOverrideKind,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
+pub struct OverrideMessage {
+  pub message_id: String,
+  pub sku: String,
+  pub shipping_overrides: Vec<ShippingOverride>,
+}
+
+impl Message for OverrideMessage {
+  fn get_message_type() -> &'static str {
+    "Override"
+  }
+
+  fn message_id(&self) -> &str {
+    &self.message_id
+  }
+
+  fn set_message_id(&mut self, message_id: String) {
+    self.message_id = message_id;
+  }
+}
+
+impl Validate for OverrideMessage {
+  fn validate(&self) -> Vec<Violation> {
+    let mut violations = vec![];
+    validate_sku(&mut violations, "sku", &self.sku);
+    violations
+  }
+}
+
+impl<W: XmlEventWriter> XmlWrite<W> for ShippingOverride {
+  fn write_xml(&self, w: &mut W) -> encode::Result<()> {
+    write_xml!(w,
+      ShippingOverride[][
+        ShipOption[][(&self.ship_option)]
+        [{
+          match self.kind {
+            ShippingOverrideKind::Restricted(restricted) => {
+              write_element(w, "IsShippingRestricted", if restricted { "true" } else { "false" })
+            }
+            ShippingOverrideKind::Additive(ref amount) => {
+              write_element(w, "Type", "Additive")?;
+              write_currency_amount(w, "ShipAmount", Some(amount))
+            }
+            ShippingOverrideKind::Exclusive(ref amount) => {
+              write_element(w, "Type", "Exclusive")?;
+              write_currency_amount(w, "ShipAmount", Some(amount))
+            }
+          }
+        }]
+      ]
+    )
+  }
+}
+
+impl<W: XmlEventWriter> XmlWrite<W> for OverrideMessage {
+  fn write_xml(&self, w: &mut W) -> encode::Result<()> {
+    write_xml!(w,
+      Override[][
+        SKU[][(&self.sku)]
+        [{
+          for shipping_override in &self.shipping_overrides {
+            shipping_override.write_xml(w)?;
+          }
+          Ok(())
+        }]
+      ]
+    )
+  }
+}
+
+impl<W: XmlEventWriter> XmlWrite<W> for Envelope<OverrideMessage> {
+  fn write_xml(&self, w: &mut W) -> encode::Result<()> {
+    self.write_envelope_xml(w, |w: &mut W| {
+      for message in self.messages.iter() {
+        write_message(w, message, |w| message.data.write_xml(w))?;
+      }
+      Ok(())
+    })
+  }
+}
+
+fn read_shipping_override<S: XmlEventStream>(s: &mut S) -> MwsResult<ShippingOverride> {
+  let mut ship_option = None;
+  let mut restricted = None;
+  let mut override_type: Option<String> = None;
+  let mut amount = None;
+  fold_elements(s, (), |s, _| {
+    match s.local_name() {
+      "ShipOption" => ship_option = Some(characters(s)?),
+      "IsShippingRestricted" => restricted = Some(characters(s)?),
+      "Type" => override_type = Some(characters(s)?),
+      "ShipAmount" => amount = Some(read_currency_amount(s)?),
+      _ => {}
+    }
+    Ok(())
+  })?;
+  let kind = match (restricted, override_type) {
+    (Some(restricted), _) => ShippingOverrideKind::Restricted(restricted),
+    (None, Some(override_type)) => match override_type.as_ref() {
+      "Additive" => ShippingOverrideKind::Additive(required(amount, "ShipAmount")?),
+      "Exclusive" => ShippingOverrideKind::Exclusive(required(amount, "ShipAmount")?),
+      _ => {
+        return Err(MwsError::ParseString {
+          what: "Type".to_string(),
+          message: format!("unknown value: '{}'", override_type),
+        })
+      }
+    },
+    (None, None) => {
+      return Err(MwsError::UnexpectedEndOfXml(
+        "expected element: IsShippingRestricted or Type".to_string(),
+      ))
+    }
+  };
+  Ok(ShippingOverride {
+    ship_option: required(ship_option, "ShipOption")?,
+    kind,
+  })
+}
+
+impl<S: XmlEventStream> FromXmlStream<S> for OverrideMessage {
+  fn from_xml(s: &mut S) -> MwsResult<Self> {
+    fold_elements(s, OverrideMessage::default(), |s, v| {
+      match s.local_name() {
+        "SKU" => v.sku = characters(s)?,
+        "ShippingOverride" => v.shipping_overrides.push(read_shipping_override(s)?),
+        _ => {}
+      }
+      Ok(())
+    })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use feeds::message::price::{CurrencyCode, Decimal};
+  use feeds::OperationType;
+  use xmlhelper::encode::{EmitterConfig, EventWriter};
+
+  #[test]
+  fn test_override_feed() {
+    let mut writer =
+      EventWriter::new_with_config(vec![], EmitterConfig::new().perform_indent(true));
+
+    let mut e = Envelope::<OverrideMessage>::new("M_EXAMPLE_123456".to_string());
+    e.add_message(
+      OverrideMessage {
+        message_id: "1".to_string(),
+        sku: "edifier-r1280t".to_string(),
+        shipping_overrides: vec![
+          ShippingOverride {
+            ship_option: "Exp AK HI Street Addr".to_string(),
+            kind: ShippingOverrideKind::Restricted(true),
+          },
+          ShippingOverride {
+            ship_option: "Std Cont US Street Addr".to_string(),
+            kind: ShippingOverrideKind::Exclusive(CurrencyAmount::new(
+              CurrencyCode::USD,
+              Decimal::new(0, 0),
+            )),
+          },
+          ShippingOverride {
+            ship_option: "Second".to_string(),
+            kind: ShippingOverrideKind::Additive(CurrencyAmount::new(
+              CurrencyCode::USD,
+              Decimal::new(599, 2),
+            )),
+          },
+        ],
+      },
+      Some(OperationType::Update),
+    );
+    e.write_xml(&mut writer).unwrap();
+
+    let xml = String::from_utf8(writer.into_inner()).unwrap();
+    assert_eq!(
+      xml,
+      r#"<?xml version="1.0" encoding="utf-8"?>
+<AmazonEnvelope xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance" xsi:noNamespaceSchemaLocation="amznenvelope.xsd">
+  <Header>
+    <DocumentVersion>1.01</DocumentVersion>
+    <MerchantIdentifier>M_EXAMPLE_123456</MerchantIdentifier>
+  </Header>
+  <MessageType>Override</MessageType>
+  <Message>
+    <MessageID>1</MessageID>
+    <OperationType>Update</OperationType>
+    <Override>
+      <SKU>edifier-r1280t</SKU>
+      <ShippingOverride>
+        <ShipOption>Exp AK HI Street Addr</ShipOption>
+        <IsShippingRestricted>true</IsShippingRestricted>
+      </ShippingOverride>
+      <ShippingOverride>
+        <ShipOption>Std Cont US Street Addr</ShipOption>
+        <Type>Exclusive</Type>
+        <ShipAmount currency="USD">0.00</ShipAmount>
+      </ShippingOverride>
+      <ShippingOverride>
+        <ShipOption>Second</ShipOption>
+        <Type>Additive</Type>
+        <ShipAmount currency="USD">5.99</ShipAmount>
+      </ShippingOverride>
+    </Override>
+  </Message>
+</AmazonEnvelope>"#
+    );
+    assert_eq!(
+      Envelope::<OverrideMessage>::from_reader(xml.as_bytes()).unwrap(),
+      e
+    );
+  }
+}
@@ -0,0 +1,539 @@
+//! Price feed message (`_POST_PRODUCT_PRICING_DATA_`)
+//!
+//! [Documentation](https://images-na.ssl-images-amazon.com/images/G/01/rainier/help/xsd/release_1_9/Price.xsd)
+
+use super::{read_attr, required, write_element, write_element_with_attr, write_message};
+use chrono::{DateTime, Utc};
+use feeds::validate::{validate_sku, Validate, Violation};
+use feeds::Envelope;
+use feeds::Message;
+use result::MwsResult;
+pub use rust_decimal::Decimal;
+use types::ToIso8601;
+use xmlhelper::decode::{characters, fold_elements, FromXmlStream, XmlEventStream};
+use xmlhelper::encode::{self, XmlEventWriter, XmlWrite};
+
+str_enum! {
+  pub enum CurrencyCode {
+    USD,
+    GBP,
+    EUR,
+    JPY,
+    CAD,
+    CNY,
+    INR,
+    AUD,
+    MXN,
+    BRL,
+    DEFAULT,
+  }
+}
+
+impl CurrencyCode {
+  /// Number of decimal places of the currency, `2` if unknown
+  pub fn minor_units(&self) -> u32 {
+    match *self {
+      CurrencyCode::JPY => 0,
+      _ => 2,
+    }
+  }
+}
+
+/// An amount with a `currency` attribute, e.g. `<StandardPrice currency="USD">19.99</StandardPrice>`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CurrencyAmount {
+  pub currency: CurrencyCode,
+  pub amount: Decimal,
+}
+
+impl CurrencyAmount {
+  pub fn new(currency: CurrencyCode, amount: Decimal) -> Self {
+    CurrencyAmount { currency, amount }
+  }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Sale {
+  pub start_date: DateTime<Utc>,
+  pub end_date: DateTime<Utc>,
+  pub sale_price: CurrencyAmount,
+}
+
+string_map_enum! {
+  /// How the `QuantityPrice` tiers are interpreted, `Fixed` amounts or `Percent` off the business
+  /// price
+  pub enum QuantityPriceType {
+    Fixed = "fixed",
+    Percent = "percent",
+  }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
+pub struct QuantityPriceTier {
+  /// Minimum order quantity of this tier
+  pub lower_bound: u32,
+  /// An amount or a percentage, depending on `QuantityPriceType`
+  pub price: Decimal,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct QuantityPrice {
+  pub price_type: QuantityPriceType,
+  /// Up to 5 tiers
+  pub tiers: Vec<QuantityPriceTier>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
+pub struct PriceMessage {
+  pub message_id: String,
+  pub sku: String,
+  pub standard_price: Option<CurrencyAmount>,
+  pub minimum_seller_allowed_price: Option<CurrencyAmount>,
+  pub maximum_seller_allowed_price: Option<CurrencyAmount>,
+  pub sale: Option<Sale>,
+  /// Amazon Business price, in the marketplace currency. Written with the decimal places of the
+  /// `standard_price` currency, like the `quantity_price` tiers.
+  pub business_price: Option<Decimal>,
+  pub quantity_price: Option<QuantityPrice>,
+}
+
+impl Message for PriceMessage {
+  fn get_message_type() -> &'static str {
+    "Price"
+  }
+
+  fn message_id(&self) -> &str {
+    &self.message_id
+  }
+
+  fn set_message_id(&mut self, message_id: String) {
+    self.message_id = message_id;
+  }
+}
+
+impl PriceMessage {
+  /// Decimal places of `business_price` and `quantity_price`
+  fn minor_units(&self) -> u32 {
+    self
+      .standard_price
+      .as_ref()
+      .map(|v| v.currency.minor_units())
+      .unwrap_or(2)
+  }
+}
+
+impl Validate for PriceMessage {
+  fn validate(&self) -> Vec<Violation> {
+    let mut violations = vec![];
+    validate_sku(&mut violations, "sku", &self.sku);
+    let amounts = vec![
+      ("standard_price", self.standard_price.as_ref()),
+      (
+        "minimum_seller_allowed_price",
+        self.minimum_seller_allowed_price.as_ref(),
+      ),
+      (
+        "maximum_seller_allowed_price",
+        self.maximum_seller_allowed_price.as_ref(),
+      ),
+      (
+        "sale.sale_price",
+        self.sale.as_ref().map(|sale| &sale.sale_price),
+      ),
+    ];
+    for (field, amount) in amounts {
+      if let Some(amount) = amount {
+        validate_amount(
+          &mut violations,
+          field,
+          &amount.amount,
+          amount.currency.minor_units(),
+        );
+      }
+    }
+    if let Some(ref business_price) = self.business_price {
+      validate_amount(
+        &mut violations,
+        "business_price",
+        business_price,
+        self.minor_units(),
+      );
+    }
+    if let Some(ref quantity_price) = self.quantity_price {
+      if let QuantityPriceType::UnknownValue(ref v) = quantity_price.price_type {
+        violations.push(Violation::new(
+          "quantity_price.price_type",
+          format!("unknown value: '{}'", v),
+        ));
+      }
+      for tier in &quantity_price.tiers {
+        validate_amount(
+          &mut violations,
+          "quantity_price.tiers.price",
+          &tier.price,
+          self.minor_units(),
+        );
+      }
+    }
+    violations
+  }
+}
+
+fn validate_amount(
+  violations: &mut Vec<Violation>,
+  field: &str,
+  amount: &Decimal,
+  minor_units: u32,
+) {
+  if amount.normalize().scale() > minor_units {
+    violations.push(Violation::new(
+      field,
+      format!("more than {} decimal places: {}", minor_units, amount),
+    ));
+  }
+}
+
+/// Formats `amount` with `minor_units` decimal places. Amounts with more decimal places are
+/// written as is instead of being rounded, `Validate` reports them.
+fn format_amount(amount: &Decimal, minor_units: u32) -> String {
+  let normalized = amount.normalize();
+  if normalized.scale() > minor_units {
+    normalized.to_string()
+  } else {
+    format!("{:.*}", minor_units as usize, amount)
+  }
+}
+
+pub(crate) fn write_currency_amount<W: XmlEventWriter>(
+  w: &mut W,
+  name: &str,
+  value: Option<&CurrencyAmount>,
+) -> encode::Result<()> {
+  match value {
+    Some(v) => write_element_with_attr(
+      w,
+      name,
+      "currency",
+      v.currency.as_ref(),
+      &format_amount(&v.amount, v.currency.minor_units()),
+    ),
+    None => Ok(()),
+  }
+}
+
+/// Reads an amount with a `currency` attribute, e.g. `<StandardPrice currency="USD">`
+pub(crate) fn read_currency_amount<S: XmlEventStream>(s: &mut S) -> MwsResult<CurrencyAmount> {
+  let currency = read_attr(s, "currency").unwrap_or_default();
+  Ok(CurrencyAmount {
+    currency: CurrencyCode::from(currency.as_ref()),
+    amount: characters(s)?,
+  })
+}
+
+impl<W: XmlEventWriter> XmlWrite<W> for PriceMessage {
+  fn write_xml(&self, w: &mut W) -> encode::Result<()> {
+    write_xml!(w,
+      Price[][
+        SKU[][(&self.sku)]
+        [{
+          write_currency_amount(w, "StandardPrice", self.standard_price.as_ref())?;
+          write_currency_amount(w, "MinimumSellerAllowedPrice", self.minimum_seller_allowed_price.as_ref())?;
+          write_currency_amount(w, "MaximumSellerAllowedPrice", self.maximum_seller_allowed_price.as_ref())?;
+          if let Some(ref sale) = self.sale {
+            let start_date = sale.start_date.to_iso8601();
+            let end_date = sale.end_date.to_iso8601();
+            write_xml!(w,
+              Sale[][
+                StartDate[][(&start_date)]
+                EndDate[][(&end_date)]
+                [{ write_currency_amount(w, "SalePrice", Some(&sale.sale_price)) }]
+              ]
+            )?;
+          }
+          if let Some(ref business_price) = self.business_price {
+            write_element(w, "BusinessPrice", &format_amount(business_price, self.minor_units()))?;
+          }
+          if let Some(ref quantity_price) = self.quantity_price {
+            write_element(w, "QuantityPriceType", quantity_price.price_type.as_ref())?;
+            write_xml!(w,
+              QuantityPrice[][
+                [{
+                  for (i, tier) in quantity_price.tiers.iter().enumerate() {
+                    let n = i + 1;
+                    write_element(w, &format!("QuantityPrice{}", n), &format_amount(&tier.price, self.minor_units()))?;
+                    write_element(w, &format!("QuantityLowerBound{}", n), &tier.lower_bound.to_string())?;
+                  }
+                  Ok(())
+                }]
+              ]
+            )?;
+          }
+          Ok(())
+        }]
+      ]
+    )
+  }
+}
+
+impl<W: XmlEventWriter> XmlWrite<W> for Envelope<PriceMessage> {
+  fn write_xml(&self, w: &mut W) -> encode::Result<()> {
+    self.write_envelope_xml(w, |w: &mut W| {
+      for message in self.messages.iter() {
+        write_message(w, message, |w| message.data.write_xml(w))?;
+      }
+      Ok(())
+    })
+  }
+}
+
+fn read_sale<S: XmlEventStream>(s: &mut S) -> MwsResult<Sale> {
+  let mut start_date = None;
+  let mut end_date = None;
+  let mut sale_price = None;
+  fold_elements(s, (), |s, _| {
+    match s.local_name() {
+      "StartDate" => start_date = Some(characters(s)?),
+      "EndDate" => end_date = Some(characters(s)?),
+      "SalePrice" => sale_price = Some(read_currency_amount(s)?),
+      _ => {}
+    }
+    Ok(())
+  })?;
+  Ok(Sale {
+    start_date: required(start_date, "StartDate")?,
+    end_date: required(end_date, "EndDate")?,
+    sale_price: required(sale_price, "SalePrice")?,
+  })
+}
+
+fn read_quantity_price_tiers<S: XmlEventStream>(
+  s: &mut S,
+  tiers: &mut Vec<QuantityPriceTier>,
+) -> MwsResult<()> {
+  fold_elements(s, (), |s, _| {
+    let (is_price, n) = {
+      let name = s.local_name();
+      if let Some(n) = name.strip_prefix("QuantityPrice") {
+        (true, n.parse::<usize>().ok())
+      } else if let Some(n) = name.strip_prefix("QuantityLowerBound") {
+        (false, n.parse::<usize>().ok())
+      } else {
+        (false, None)
+      }
+    };
+    if let Some(n) = n.filter(|n| *n > 0) {
+      if tiers.len() < n {
+        tiers.resize(n, QuantityPriceTier::default());
+      }
+      if is_price {
+        tiers[n - 1].price = characters(s)?;
+      } else {
+        tiers[n - 1].lower_bound = characters(s)?;
+      }
+    }
+    Ok(())
+  })
+}
+
+impl<S: XmlEventStream> FromXmlStream<S> for PriceMessage {
+  fn from_xml(s: &mut S) -> MwsResult<Self> {
+    fold_elements(s, PriceMessage::default(), |s, v| {
+      match s.local_name() {
+        "SKU" => v.sku = characters(s)?,
+        "StandardPrice" => v.standard_price = Some(read_currency_amount(s)?),
+        "MinimumSellerAllowedPrice" => {
+          v.minimum_seller_allowed_price = Some(read_currency_amount(s)?)
+        }
+        "MaximumSellerAllowedPrice" => {
+          v.maximum_seller_allowed_price = Some(read_currency_amount(s)?)
+        }
+        "Sale" => v.sale = Some(read_sale(s)?),
+        "BusinessPrice" => v.business_price = Some(characters(s)?),
+        "QuantityPriceType" => {
+          let price_type = characters(s)?;
+          match v.quantity_price {
+            Some(ref mut quantity_price) => quantity_price.price_type = price_type,
+            None => {
+              v.quantity_price = Some(QuantityPrice {
+                price_type,
+                tiers: vec![],
+              })
+            }
+          }
+        }
+        "QuantityPrice" => {
+          let quantity_price = v.quantity_price.get_or_insert(QuantityPrice {
+            price_type: QuantityPriceType::Fixed,
+            tiers: vec![],
+          });
+          read_quantity_price_tiers(s, &mut quantity_price.tiers)?;
+        }
+        _ => {}
+      }
+      Ok(())
+    })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use feeds::OperationType;
+  use xmlhelper::encode::{EmitterConfig, EventWriter};
+
+  #[test]
+  fn test_price_feed() {
+    let mut writer =
+      EventWriter::new_with_config(vec![], EmitterConfig::new().perform_indent(true));
+
+    let mut e = Envelope::<PriceMessage>::new("M_EXAMPLE_123456".to_string());
+    e.add_message(
+      PriceMessage {
+        message_id: "1".to_string(),
+        sku: "edifier-r1280t".to_string(),
+        standard_price: Some(CurrencyAmount::new(
+          CurrencyCode::USD,
+          Decimal::new(9999, 2),
+        )),
+        minimum_seller_allowed_price: Some(CurrencyAmount::new(
+          CurrencyCode::USD,
+          Decimal::new(80, 0),
+        )),
+        maximum_seller_allowed_price: Some(CurrencyAmount::new(
+          CurrencyCode::USD,
+          Decimal::new(120, 0),
+        )),
+        sale: Some(Sale {
+          start_date: "2019-11-29T00:00:00Z".parse().unwrap(),
+          end_date: "2019-12-02T23:59:59Z".parse().unwrap(),
+          sale_price: CurrencyAmount::new(CurrencyCode::USD, Decimal::new(8499, 2)),
+        }),
+        business_price: Some(Decimal::new(95, 0)),
+        quantity_price: Some(QuantityPrice {
+          price_type: QuantityPriceType::Percent,
+          tiers: vec![
+            QuantityPriceTier {
+              lower_bound: 5,
+              price: Decimal::new(5, 0),
+            },
+            QuantityPriceTier {
+              lower_bound: 10,
+              price: Decimal::new(75, 1),
+            },
+          ],
+        }),
+      },
+      Some(OperationType::Update),
+    )
+    .add_message(
+      PriceMessage {
+        message_id: "2".to_string(),
+        sku: "edifier-s1000db".to_string(),
+        standard_price: Some(CurrencyAmount::new(
+          CurrencyCode::CAD,
+          Decimal::new(44999, 2),
+        )),
+        minimum_seller_allowed_price: None,
+        maximum_seller_allowed_price: None,
+        sale: None,
+        business_price: None,
+        quantity_price: None,
+      },
+      Some(OperationType::Update),
+    );
+    e.write_xml(&mut writer).unwrap();
+
+    let xml = String::from_utf8(writer.into_inner()).unwrap();
+    assert_eq!(
+      xml,
+      r#"<?xml version="1.0" encoding="utf-8"?>
+<AmazonEnvelope xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance" xsi:noNamespaceSchemaLocation="amznenvelope.xsd">
+  <Header>
+    <DocumentVersion>1.01</DocumentVersion>
+    <MerchantIdentifier>M_EXAMPLE_123456</MerchantIdentifier>
+  </Header>
+  <MessageType>Price</MessageType>
+  <Message>
+    <MessageID>1</MessageID>
+    <OperationType>Update</OperationType>
+    <Price>
+      <SKU>edifier-r1280t</SKU>
+      <StandardPrice currency="USD">99.99</StandardPrice>
+      <MinimumSellerAllowedPrice currency="USD">80.00</MinimumSellerAllowedPrice>
+      <MaximumSellerAllowedPrice currency="USD">120.00</MaximumSellerAllowedPrice>
+      <Sale>
+        <StartDate>2019-11-29T00:00:00Z</StartDate>
+        <EndDate>2019-12-02T23:59:59Z</EndDate>
+        <SalePrice currency="USD">84.99</SalePrice>
+      </Sale>
+      <BusinessPrice>95.00</BusinessPrice>
+      <QuantityPriceType>percent</QuantityPriceType>
+      <QuantityPrice>
+        <QuantityPrice1>5.00</QuantityPrice1>
+        <QuantityLowerBound1>5</QuantityLowerBound1>
+        <QuantityPrice2>7.50</QuantityPrice2>
+        <QuantityLowerBound2>10</QuantityLowerBound2>
+      </QuantityPrice>
+    </Price>
+  </Message>
+  <Message>
+    <MessageID>2</MessageID>
+    <OperationType>Update</OperationType>
+    <Price>
+      <SKU>edifier-s1000db</SKU>
+      <StandardPrice currency="CAD">449.99</StandardPrice>
+    </Price>
+  </Message>
+</AmazonEnvelope>"#
+    );
+    assert_eq!(
+      Envelope::<PriceMessage>::from_reader(xml.as_bytes()).unwrap(),
+      e
+    );
+  }
+
+  #[test]
+  fn test_price_feed_minor_units() {
+    let mut message = PriceMessage {
+      message_id: "1".to_string(),
+      sku: "edifier-r1280t".to_string(),
+      standard_price: Some(CurrencyAmount::new(
+        CurrencyCode::JPY,
+        Decimal::new(10800, 0),
+      )),
+      business_price: Some(Decimal::new(980000, 2)),
+      ..Default::default()
+    };
+    assert_eq!(message.validate(), vec![]);
+    let mut envelope = Envelope::<PriceMessage>::new("M_EXAMPLE_123456".to_string());
+    envelope.add_message(message.clone(), None);
+    let xml = envelope.to_xml_string().unwrap();
+    assert!(xml.contains(r#"<StandardPrice currency="JPY">10800</StandardPrice>"#));
+    assert!(xml.contains("<BusinessPrice>9800</BusinessPrice>"));
+
+    message.standard_price = Some(CurrencyAmount::new(
+      CurrencyCode::JPY,
+      Decimal::new(108005, 1),
+    ));
+    message.minimum_seller_allowed_price = Some(CurrencyAmount::new(
+      CurrencyCode::USD,
+      Decimal::new(19999, 3),
+    ));
+    assert_eq!(
+      message.validate(),
+      vec![
+        Violation::new("standard_price", "more than 0 decimal places: 10800.5"),
+        Violation::new(
+          "minimum_seller_allowed_price",
+          "more than 2 decimal places: 19.999"
+        ),
+      ]
+    );
+    let mut envelope = Envelope::<PriceMessage>::new("M_EXAMPLE_123456".to_string());
+    envelope.add_message(message, None);
+    let xml = envelope.to_xml_string().unwrap();
+    assert!(xml.contains(r#"<StandardPrice currency="JPY">10800.5</StandardPrice>"#));
+    assert!(xml
+      .contains(r#"<MinimumSellerAllowedPrice currency="USD">19.999</MinimumSellerAllowedPrice>"#));
+  }
+}
@@ -0,0 +1,599 @@
+//! Product feed message (`_POST_PRODUCT_DATA_`)
+//!
+//! [Documentation](https://images-na.ssl-images-amazon.com/images/G/01/rainier/help/xsd/release_1_9/Product.xsd)
+
+use super::{
+  read_attr, required, write_element, write_element_with_attr, write_message,
+  write_optional_element,
+};
+use chrono::{DateTime, Utc};
+use feeds::validate::{validate_sku, Validate, Violation};
+use feeds::Envelope;
+use feeds::Message;
+use result::MwsResult;
+use types::ToIso8601;
+use xml::reader;
+use xmlhelper::decode::{characters, fold_elements, FromXmlStream, XmlEventStream};
+use xmlhelper::encode::{self, XmlEvent, XmlEventWriter, XmlWrite};
+
+str_enum! {
+  pub enum StandardProductIdType {
+    ASIN,
+    ISBN,
+    UPC,
+    EAN,
+    GTIN,
+  }
+}
+
+str_enum! {
+  pub enum ConditionType {
+    New,
+    UsedLikeNew,
+    UsedVeryGood,
+    UsedGood,
+    UsedAcceptable,
+    CollectibleLikeNew,
+    CollectibleVeryGood,
+    CollectibleGood,
+    CollectibleAcceptable,
+    Refurbished,
+    Club,
+  }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct StandardProductId {
+  pub id_type: StandardProductIdType,
+  pub value: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ProductCondition {
+  pub condition_type: ConditionType,
+  pub condition_note: Option<String>,
+}
+
+/// A value with a `unitOfMeasure` attribute, e.g. `<Length unitOfMeasure="IN">7.9</Length>`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MeasuredValue {
+  pub value: String,
+  /// e.g. `IN`, `CM`, `LB` or `KG`
+  pub unit_of_measure: String,
+}
+
+impl MeasuredValue {
+  pub fn new<V: Into<String>, U: Into<String>>(value: V, unit_of_measure: U) -> Self {
+    MeasuredValue {
+      value: value.into(),
+      unit_of_measure: unit_of_measure.into(),
+    }
+  }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
+pub struct Dimensions {
+  pub length: Option<MeasuredValue>,
+  pub width: Option<MeasuredValue>,
+  pub height: Option<MeasuredValue>,
+  pub weight: Option<MeasuredValue>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
+pub struct DescriptionData {
+  pub title: String,
+  pub brand: Option<String>,
+  pub description: Option<String>,
+  /// Up to 5 bullet points
+  pub bullet_points: Vec<String>,
+  pub item_dimensions: Option<Dimensions>,
+  pub package_dimensions: Option<Dimensions>,
+  pub package_weight: Option<MeasuredValue>,
+  pub shipping_weight: Option<MeasuredValue>,
+  pub manufacturer: Option<String>,
+  pub mfr_part_number: Option<String>,
+  /// Up to 5 search terms
+  pub search_terms: Vec<String>,
+  pub item_type: Option<String>,
+}
+
+/// The category specific `ProductData` payload of a `ProductMessage`
+///
+/// Implement this trait to write a typed category payload, or use `GenericProductData`.
+pub trait ProductData {
+  /// Writes the category element, e.g. `<Home>...</Home>`, `<ProductData>` is written by the
+  /// caller.
+  fn write_product_data<W: XmlEventWriter>(&self, w: &mut W) -> encode::Result<()>;
+}
+
+/// An untyped `ProductData` payload
+///
+/// ```
+/// # use mws::feeds::message::product::{GenericProductData, ProductDataField};
+/// let data = GenericProductData {
+///   category: "Home".to_string(),
+///   fields: vec![
+///     ProductDataField::nested("ProductType", vec![ProductDataField::nested("Home", vec![])]),
+///     ProductDataField::text("Material", "Wood"),
+///   ],
+/// };
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
+pub struct GenericProductData {
+  pub category: String,
+  pub fields: Vec<ProductDataField>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ProductDataField {
+  pub name: String,
+  pub value: ProductDataValue,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub enum ProductDataValue {
+  Text(String),
+  Nested(Vec<ProductDataField>),
+}
+
+impl ProductDataField {
+  pub fn text<N: Into<String>, V: Into<String>>(name: N, value: V) -> Self {
+    ProductDataField {
+      name: name.into(),
+      value: ProductDataValue::Text(value.into()),
+    }
+  }
+
+  pub fn nested<N: Into<String>>(name: N, fields: Vec<ProductDataField>) -> Self {
+    ProductDataField {
+      name: name.into(),
+      value: ProductDataValue::Nested(fields),
+    }
+  }
+
+  fn write<W: XmlEventWriter>(&self, w: &mut W) -> encode::Result<()> {
+    match self.value {
+      ProductDataValue::Text(ref value) => write_element(w, &self.name, value),
+      ProductDataValue::Nested(ref fields) => write_fields(w, &self.name, fields),
+    }
+  }
+}
+
+fn write_fields<W: XmlEventWriter>(
+  w: &mut W,
+  name: &str,
+  fields: &[ProductDataField],
+) -> encode::Result<()> {
+  w.write(XmlEvent::start_element(name).into())?;
+  for field in fields {
+    field.write(w)?;
+  }
+  w.write(XmlEvent::end_element().into())
+}
+
+impl ProductData for GenericProductData {
+  fn write_product_data<W: XmlEventWriter>(&self, w: &mut W) -> encode::Result<()> {
+    write_fields(w, &self.category, &self.fields)
+  }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
+pub struct ProductMessage<D: ProductData = GenericProductData> {
+  pub message_id: String,
+  pub sku: String,
+  pub standard_product_id: Option<StandardProductId>,
+  /// e.g. `A_GEN_NOTAX`
+  pub product_tax_code: Option<String>,
+  pub launch_date: Option<DateTime<Utc>>,
+  pub condition: Option<ProductCondition>,
+  pub description_data: Option<DescriptionData>,
+  pub product_data: Option<D>,
+}
+
+impl<D: ProductData> Message for ProductMessage<D> {
+  fn get_message_type() -> &'static str {
+    "Product"
+  }
+
+  fn message_id(&self) -> &str {
+    &self.message_id
+  }
+
+  fn set_message_id(&mut self, message_id: String) {
+    self.message_id = message_id;
+  }
+}
+
+impl<D: ProductData> Validate for ProductMessage<D> {
+  fn validate(&self) -> Vec<Violation> {
+    let mut violations = vec![];
+    validate_sku(&mut violations, "sku", &self.sku);
+    violations
+  }
+}
+
+fn write_measured_value<W: XmlEventWriter>(
+  w: &mut W,
+  name: &str,
+  value: &Option<MeasuredValue>,
+) -> encode::Result<()> {
+  match *value {
+    Some(ref v) => write_element_with_attr(w, name, "unitOfMeasure", &v.unit_of_measure, &v.value),
+    None => Ok(()),
+  }
+}
+
+fn write_dimensions<W: XmlEventWriter>(
+  w: &mut W,
+  name: &str,
+  dimensions: &Option<Dimensions>,
+) -> encode::Result<()> {
+  let dimensions = match *dimensions {
+    Some(ref v) => v,
+    None => return Ok(()),
+  };
+  w.write(XmlEvent::start_element(name).into())?;
+  write_measured_value(w, "Length", &dimensions.length)?;
+  write_measured_value(w, "Width", &dimensions.width)?;
+  write_measured_value(w, "Height", &dimensions.height)?;
+  write_measured_value(w, "Weight", &dimensions.weight)?;
+  w.write(XmlEvent::end_element().into())
+}
+
+impl<W: XmlEventWriter> XmlWrite<W> for DescriptionData {
+  fn write_xml(&self, w: &mut W) -> encode::Result<()> {
+    write_xml!(w,
+      DescriptionData[][
+        Title[][(&self.title)]
+        [{
+          write_optional_element(w, "Brand", self.brand.as_ref())?;
+          write_optional_element(w, "Description", self.description.as_ref())?;
+          for v in &self.bullet_points {
+            write_element(w, "BulletPoint", v)?;
+          }
+          write_dimensions(w, "ItemDimensions", &self.item_dimensions)?;
+          write_dimensions(w, "PackageDimensions", &self.package_dimensions)?;
+          write_measured_value(w, "PackageWeight", &self.package_weight)?;
+          write_measured_value(w, "ShippingWeight", &self.shipping_weight)?;
+          write_optional_element(w, "Manufacturer", self.manufacturer.as_ref())?;
+          write_optional_element(w, "MfrPartNumber", self.mfr_part_number.as_ref())?;
+          for v in &self.search_terms {
+            write_element(w, "SearchTerms", v)?;
+          }
+          write_optional_element(w, "ItemType", self.item_type.as_ref())
+        }]
+      ]
+    )
+  }
+}
+
+impl<W: XmlEventWriter, D: ProductData> XmlWrite<W> for ProductMessage<D> {
+  fn write_xml(&self, w: &mut W) -> encode::Result<()> {
+    write_xml!(w,
+      Product[][
+        SKU[][(&self.sku)]
+        [{
+          if let Some(ref id) = self.standard_product_id {
+            write_xml!(w,
+              StandardProductID[][
+                Type[][(id.id_type.as_ref())]
+                Value[][(&id.value)]
+              ]
+            )?;
+          }
+          write_optional_element(w, "ProductTaxCode", self.product_tax_code.as_ref())?;
+          write_optional_element(w, "LaunchDate", self.launch_date.map(|v| v.to_iso8601()))?;
+          if let Some(ref condition) = self.condition {
+            write_xml!(w,
+              Condition[][
+                ConditionType[][(condition.condition_type.as_ref())]
+                [{ write_optional_element(w, "ConditionNote", condition.condition_note.as_ref()) }]
+              ]
+            )?;
+          }
+          if let Some(ref description_data) = self.description_data {
+            description_data.write_xml(w)?;
+          }
+          if let Some(ref product_data) = self.product_data {
+            write_xml!(w,
+              ProductData[][
+                [{ product_data.write_product_data(w) }]
+              ]
+            )?;
+          }
+          Ok(())
+        }]
+      ]
+    )
+  }
+}
+
+impl<W: XmlEventWriter, D: ProductData> XmlWrite<W> for Envelope<ProductMessage<D>> {
+  fn write_xml(&self, w: &mut W) -> encode::Result<()> {
+    self.write_envelope_xml(w, |w: &mut W| {
+      for message in self.messages.iter() {
+        write_message(w, message, |w| message.data.write_xml(w))?;
+      }
+      Ok(())
+    })
+  }
+}
+
+fn read_measured_value<S: XmlEventStream>(s: &mut S) -> MwsResult<MeasuredValue> {
+  let unit_of_measure = read_attr(s, "unitOfMeasure").unwrap_or_default();
+  Ok(MeasuredValue {
+    value: characters(s)?,
+    unit_of_measure,
+  })
+}
+
+impl<S: XmlEventStream> FromXmlStream<S> for Dimensions {
+  fn from_xml(s: &mut S) -> MwsResult<Self> {
+    fold_elements(s, Dimensions::default(), |s, v| {
+      match s.local_name() {
+        "Length" => v.length = Some(read_measured_value(s)?),
+        "Width" => v.width = Some(read_measured_value(s)?),
+        "Height" => v.height = Some(read_measured_value(s)?),
+        "Weight" => v.weight = Some(read_measured_value(s)?),
+        _ => {}
+      }
+      Ok(())
+    })
+  }
+}
+
+impl<S: XmlEventStream> FromXmlStream<S> for DescriptionData {
+  fn from_xml(s: &mut S) -> MwsResult<Self> {
+    fold_elements(s, DescriptionData::default(), |s, v| {
+      match s.local_name() {
+        "Title" => v.title = characters(s)?,
+        "Brand" => v.brand = Some(characters(s)?),
+        "Description" => v.description = Some(characters(s)?),
+        "BulletPoint" => v.bullet_points.push(characters(s)?),
+        "ItemDimensions" => v.item_dimensions = Some(Dimensions::from_xml(s)?),
+        "PackageDimensions" => v.package_dimensions = Some(Dimensions::from_xml(s)?),
+        "PackageWeight" => v.package_weight = Some(read_measured_value(s)?),
+        "ShippingWeight" => v.shipping_weight = Some(read_measured_value(s)?),
+        "Manufacturer" => v.manufacturer = Some(characters(s)?),
+        "MfrPartNumber" => v.mfr_part_number = Some(characters(s)?),
+        "SearchTerms" => v.search_terms.push(characters(s)?),
+        "ItemType" => v.item_type = Some(characters(s)?),
+        _ => {}
+      }
+      Ok(())
+    })
+  }
+}
+
+/// Reads the category element inside `<ProductData>`.
+///
+/// Elements with children become `ProductDataValue::Nested` fields, other elements become
+/// `ProductDataValue::Text` fields.
+impl<S: XmlEventStream> FromXmlStream<S> for GenericProductData {
+  fn from_xml(s: &mut S) -> MwsResult<Self> {
+    // (name, text, children) of the open elements
+    let mut stack: Vec<(String, String, Vec<ProductDataField>)> = vec![];
+    let mut data = GenericProductData::default();
+    // stop before the end of the scope, `fold_elements` consumes it
+    while s.peek().is_some() {
+      let event = match s.next() {
+        Some(event) => event?,
+        None => break,
+      };
+      match event {
+        reader::XmlEvent::StartElement { name, .. } => {
+          stack.push((name.local_name, String::new(), vec![]))
+        }
+        reader::XmlEvent::Characters(text) => {
+          if let Some(top) = stack.last_mut() {
+            top.1.push_str(&text);
+          }
+        }
+        reader::XmlEvent::EndElement { .. } => {
+          let (name, text, children) = match stack.pop() {
+            Some(v) => v,
+            None => break,
+          };
+          match stack.last_mut() {
+            Some(parent) => parent.2.push(if children.is_empty() {
+              ProductDataField::text(name, text)
+            } else {
+              ProductDataField::nested(name, children)
+            }),
+            None => {
+              data.category = name;
+              data.fields = children;
+            }
+          }
+        }
+        _ => {}
+      }
+    }
+    Ok(data)
+  }
+}
+
+impl<S: XmlEventStream> FromXmlStream<S> for ProductMessage {
+  fn from_xml(s: &mut S) -> MwsResult<Self> {
+    fold_elements(s, ProductMessage::default(), |s, v| {
+      match s.local_name() {
+        "SKU" => v.sku = characters(s)?,
+        "StandardProductID" => {
+          let (id_type, value) = fold_elements(s, (None, None), |s, id| {
+            match s.local_name() {
+              "Type" => id.0 = Some(characters(s)?),
+              "Value" => id.1 = Some(characters(s)?),
+              _ => {}
+            }
+            Ok(())
+          })?;
+          v.standard_product_id = Some(StandardProductId {
+            id_type: required(id_type, "Type")?,
+            value: required(value, "Value")?,
+          });
+        }
+        "ProductTaxCode" => v.product_tax_code = Some(characters(s)?),
+        "LaunchDate" => v.launch_date = Some(characters(s)?),
+        "Condition" => {
+          let (condition_type, condition_note) = fold_elements(s, (None, None), |s, c| {
+            match s.local_name() {
+              "ConditionType" => c.0 = Some(characters(s)?),
+              "ConditionNote" => c.1 = Some(characters(s)?),
+              _ => {}
+            }
+            Ok(())
+          })?;
+          v.condition = Some(ProductCondition {
+            condition_type: required(condition_type, "ConditionType")?,
+            condition_note,
+          });
+        }
+        "DescriptionData" => v.description_data = Some(DescriptionData::from_xml(s)?),
+        "ProductData" => v.product_data = Some(GenericProductData::from_xml(s)?),
+        _ => {}
+      }
+      Ok(())
+    })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use feeds::OperationType;
+  use xmlhelper::encode::{EmitterConfig, EventWriter};
+
+  #[test]
+  fn test_product_feed() {
+    let mut writer =
+      EventWriter::new_with_config(vec![], EmitterConfig::new().perform_indent(true));
+
+    let mut e = Envelope::<ProductMessage>::new("M_EXAMPLE_123456".to_string());
+    e.add_message(
+      ProductMessage {
+        message_id: "1".to_string(),
+        sku: "edifier-r1280t".to_string(),
+        standard_product_id: Some(StandardProductId {
+          id_type: StandardProductIdType::UPC,
+          value: "875228002429".to_string(),
+        }),
+        product_tax_code: Some("A_GEN_NOTAX".to_string()),
+        launch_date: Some("2019-01-02T00:00:00Z".parse().unwrap()),
+        condition: Some(ProductCondition {
+          condition_type: ConditionType::New,
+          condition_note: None,
+        }),
+        description_data: Some(DescriptionData {
+          title: "Edifier R1280T Powered Bookshelf Speakers".to_string(),
+          brand: Some("Edifier".to_string()),
+          description: Some("Active 2.0 bookshelf speakers & remote".to_string()),
+          bullet_points: vec!["Two AUX inputs".to_string(), "Wireless remote".to_string()],
+          item_dimensions: Some(Dimensions {
+            length: Some(MeasuredValue::new("9.6", "IN")),
+            width: Some(MeasuredValue::new("5.7", "IN")),
+            height: Some(MeasuredValue::new("7.9", "IN")),
+            weight: None,
+          }),
+          package_dimensions: None,
+          package_weight: Some(MeasuredValue::new("12.3", "LB")),
+          shipping_weight: None,
+          manufacturer: Some("Edifier".to_string()),
+          mfr_part_number: Some("R1280T".to_string()),
+          search_terms: vec!["bookshelf speakers".to_string()],
+          item_type: Some("bookshelf-speakers".to_string()),
+        }),
+        product_data: Some(GenericProductData {
+          category: "CE".to_string(),
+          fields: vec![ProductDataField::nested(
+            "ProductType",
+            vec![ProductDataField::nested(
+              "ConsumerElectronics",
+              vec![ProductDataField::text("Color", "Brown")],
+            )],
+          )],
+        }),
+      },
+      Some(OperationType::Update),
+    )
+    .add_message(
+      ProductMessage {
+        message_id: "2".to_string(),
+        sku: "edifier-s1000db".to_string(),
+        standard_product_id: None,
+        product_tax_code: None,
+        launch_date: None,
+        condition: None,
+        description_data: None,
+        product_data: None,
+      },
+      Some(OperationType::Delete),
+    );
+    e.write_xml(&mut writer).unwrap();
+
+    let xml = String::from_utf8(writer.into_inner()).unwrap();
+    assert_eq!(
+      xml,
+      r#"<?xml version="1.0" encoding="utf-8"?>
+<AmazonEnvelope xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance" xsi:noNamespaceSchemaLocation="amznenvelope.xsd">
+  <Header>
+    <DocumentVersion>1.01</DocumentVersion>
+    <MerchantIdentifier>M_EXAMPLE_123456</MerchantIdentifier>
+  </Header>
+  <MessageType>Product</MessageType>
+  <Message>
+    <MessageID>1</MessageID>
+    <OperationType>Update</OperationType>
+    <Product>
+      <SKU>edifier-r1280t</SKU>
+      <StandardProductID>
+        <Type>UPC</Type>
+        <Value>875228002429</Value>
+      </StandardProductID>
+      <ProductTaxCode>A_GEN_NOTAX</ProductTaxCode>
+      <LaunchDate>2019-01-02T00:00:00Z</LaunchDate>
+      <Condition>
+        <ConditionType>New</ConditionType>
+      </Condition>
+      <DescriptionData>
+        <Title>Edifier R1280T Powered Bookshelf Speakers</Title>
+        <Brand>Edifier</Brand>
+        <Description>Active 2.0 bookshelf speakers &amp; remote</Description>
+        <BulletPoint>Two AUX inputs</BulletPoint>
+        <BulletPoint>Wireless remote</BulletPoint>
+        <ItemDimensions>
+          <Length unitOfMeasure="IN">9.6</Length>
+          <Width unitOfMeasure="IN">5.7</Width>
+          <Height unitOfMeasure="IN">7.9</Height>
+        </ItemDimensions>
+        <PackageWeight unitOfMeasure="LB">12.3</PackageWeight>
+        <Manufacturer>Edifier</Manufacturer>
+        <MfrPartNumber>R1280T</MfrPartNumber>
+        <SearchTerms>bookshelf speakers</SearchTerms>
+        <ItemType>bookshelf-speakers</ItemType>
+      </DescriptionData>
+      <ProductData>
+        <CE>
+          <ProductType>
+            <ConsumerElectronics>
+              <Color>Brown</Color>
+            </ConsumerElectronics>
+          </ProductType>
+        </CE>
+      </ProductData>
+    </Product>
+  </Message>
+  <Message>
+    <MessageID>2</MessageID>
+    <OperationType>Delete</OperationType>
+    <Product>
+      <SKU>edifier-s1000db</SKU>
+    </Product>
+  </Message>
+</AmazonEnvelope>"#
+    );
+    assert_eq!(
+      Envelope::<ProductMessage>::from_reader(xml.as_bytes()).unwrap(),
+      e
+    );
+  }
+}
@@ -0,0 +1,163 @@
+//! Product image feed message (`_POST_PRODUCT_IMAGE_DATA_`)
+//!
+//! [Documentation](https://images-na.ssl-images-amazon.com/images/G/01/rainier/help/xsd/release_1_9/ProductImage.xsd)
+
+use super::{write_message, write_optional_element};
+use feeds::validate::{validate_sku, Validate, Violation};
+use feeds::Envelope;
+use feeds::Message;
+use result::MwsResult;
+use xmlhelper::decode::{characters, fold_elements, FromXmlStream, XmlEventStream};
+use xmlhelper::encode::{self, XmlEventWriter, XmlWrite};
+
+str_enum! {
+  pub enum ImageType {
+    Main,
+    Alternate,
+    PT1,
+    PT2,
+    PT3,
+    PT4,
+    PT5,
+    PT6,
+    PT7,
+    PT8,
+    Swatch,
+  }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
+pub struct ProductImageMessage {
+  pub message_id: String,
+  pub sku: String,
+  pub image_type: ImageType,
+  /// Public URL of the image, omit it with `OperationType::Delete`
+  pub image_location: Option<String>,
+}
+
+impl Message for ProductImageMessage {
+  fn get_message_type() -> &'static str {
+    "ProductImage"
+  }
+
+  fn message_id(&self) -> &str {
+    &self.message_id
+  }
+
+  fn set_message_id(&mut self, message_id: String) {
+    self.message_id = message_id;
+  }
+}
+
+impl Validate for ProductImageMessage {
+  fn validate(&self) -> Vec<Violation> {
+    let mut violations = vec![];
+    validate_sku(&mut violations, "sku", &self.sku);
+    violations
+  }
+}
+
+impl<W: XmlEventWriter> XmlWrite<W> for ProductImageMessage {
+  fn write_xml(&self, w: &mut W) -> encode::Result<()> {
+    write_xml!(w,
+      ProductImage[][
+        SKU[][(&self.sku)]
+        ImageType[][(self.image_type.as_ref())]
+        [{ write_optional_element(w, "ImageLocation", self.image_location.as_ref()) }]
+      ]
+    )
+  }
+}
+
+impl<W: XmlEventWriter> XmlWrite<W> for Envelope<ProductImageMessage> {
+  fn write_xml(&self, w: &mut W) -> encode::Result<()> {
+    self.write_envelope_xml(w, |w: &mut W| {
+      for message in self.messages.iter() {
+        write_message(w, message, |w| message.data.write_xml(w))?;
+      }
+      Ok(())
+    })
+  }
+}
+
+impl<S: XmlEventStream> FromXmlStream<S> for ProductImageMessage {
+  fn from_xml(s: &mut S) -> MwsResult<Self> {
+    fold_elements(s, ProductImageMessage::default(), |s, v| {
+      match s.local_name() {
+        "SKU" => v.sku = characters(s)?,
+        "ImageType" => v.image_type = characters(s)?,
+        "ImageLocation" => v.image_location = Some(characters(s)?),
+        _ => {}
+      }
+      Ok(())
+    })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use feeds::OperationType;
+  use xmlhelper::encode::{EmitterConfig, EventWriter};
+
+  #[test]
+  fn test_product_image_feed() {
+    let mut writer =
+      EventWriter::new_with_config(vec![], EmitterConfig::new().perform_indent(true));
+
+    let mut e = Envelope::<ProductImageMessage>::new("M_EXAMPLE_123456".to_string());
+    e.add_message(
+      ProductImageMessage {
+        message_id: "1".to_string(),
+        sku: "edifier-r1280t".to_string(),
+        image_type: ImageType::Main,
+        image_location: Some("https://example.com/images/r1280t-main.jpg".to_string()),
+      },
+      Some(OperationType::Update),
+    )
+    .add_message(
+      ProductImageMessage {
+        message_id: "2".to_string(),
+        sku: "edifier-r1280t".to_string(),
+        image_type: ImageType::PT1,
+        image_location: None,
+      },
+      Some(OperationType::Delete),
+    );
+    e.write_xml(&mut writer).unwrap();
+
+    let xml = String::from_utf8(writer.into_inner()).unwrap();
+    assert_eq!(
+      xml,
+      r#"<?xml version="1.0" encoding="utf-8"?>
+<AmazonEnvelope xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance" xsi:noNamespaceSchemaLocation="amznenvelope.xsd">
+  <Header>
+    <DocumentVersion>1.01</DocumentVersion>
+    <MerchantIdentifier>M_EXAMPLE_123456</MerchantIdentifier>
+  </Header>
+  <MessageType>ProductImage</MessageType>
+  <Message>
+    <MessageID>1</MessageID>
+    <OperationType>Update</OperationType>
+    <ProductImage>
+      <SKU>edifier-r1280t</SKU>
+      <ImageType>Main</ImageType>
+      <ImageLocation>https://example.com/images/r1280t-main.jpg</ImageLocation>
+    </ProductImage>
+  </Message>
+  <Message>
+    <MessageID>2</MessageID>
+    <OperationType>Delete</OperationType>
+    <ProductImage>
+      <SKU>edifier-r1280t</SKU>
+      <ImageType>PT1</ImageType>
+    </ProductImage>
+  </Message>
+</AmazonEnvelope>"#
+    );
+    assert_eq!(
+      Envelope::<ProductImageMessage>::from_reader(xml.as_bytes()).unwrap(),
+      e
+    );
+  }
+}
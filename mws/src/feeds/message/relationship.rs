@@ -0,0 +1,192 @@
+//! Relationship feed message (`_POST_PRODUCT_RELATIONSHIP_DATA_`)
+//!
+//! [Documentation](https://images-na.ssl-images-amazon.com/images/G/01/rainier/help/xsd/release_1_9/Relationship.xsd)
+
+use super::{write_message, write_optional_element};
+use feeds::validate::{validate_sku, Validate, Violation};
+use feeds::Envelope;
+use feeds::Message;
+use result::MwsResult;
+use xmlhelper::decode::{characters, fold_elements, FromXmlStream, XmlEventStream};
+use xmlhelper::encode::{self, XmlEventWriter, XmlWrite};
+
+str_enum! {
+  pub enum RelationType {
+    Variation,
+    Accessory,
+  }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
+pub struct Relation {
+  pub sku: String,
+  /// e.g. `Display` or `NotDisplay`
+  pub child_detail_page_display: Option<String>,
+  pub relation_type: RelationType,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
+pub struct RelationshipMessage {
+  pub message_id: String,
+  pub parent_sku: String,
+  pub relations: Vec<Relation>,
+}
+
+impl Message for RelationshipMessage {
+  fn get_message_type() -> &'static str {
+    "Relationship"
+  }
+
+  fn message_id(&self) -> &str {
+    &self.message_id
+  }
+
+  fn set_message_id(&mut self, message_id: String) {
+    self.message_id = message_id;
+  }
+}
+
+impl Validate for RelationshipMessage {
+  fn validate(&self) -> Vec<Violation> {
+    let mut violations = vec![];
+    validate_sku(&mut violations, "parent_sku", &self.parent_sku);
+    for relation in &self.relations {
+      validate_sku(&mut violations, "relations.sku", &relation.sku);
+    }
+    violations
+  }
+}
+
+impl<W: XmlEventWriter> XmlWrite<W> for RelationshipMessage {
+  fn write_xml(&self, w: &mut W) -> encode::Result<()> {
+    write_xml!(w,
+      Relationship[][
+        ParentSKU[][(&self.parent_sku)]
+        [{
+          for relation in &self.relations {
+            write_xml!(w,
+              Relation[][
+                SKU[][(&relation.sku)]
+                [{
+                  write_optional_element(
+                    w,
+                    "ChildDetailPageDisplay",
+                    relation.child_detail_page_display.as_ref(),
+                  )
+                }]
+                Type[][(relation.relation_type.as_ref())]
+              ]
+            )?;
+          }
+          Ok(())
+        }]
+      ]
+    )
+  }
+}
+
+impl<W: XmlEventWriter> XmlWrite<W> for Envelope<RelationshipMessage> {
+  fn write_xml(&self, w: &mut W) -> encode::Result<()> {
+    self.write_envelope_xml(w, |w: &mut W| {
+      for message in self.messages.iter() {
+        write_message(w, message, |w| message.data.write_xml(w))?;
+      }
+      Ok(())
+    })
+  }
+}
+
+impl<S: XmlEventStream> FromXmlStream<S> for Relation {
+  fn from_xml(s: &mut S) -> MwsResult<Self> {
+    fold_elements(s, Relation::default(), |s, v| {
+      match s.local_name() {
+        "SKU" => v.sku = characters(s)?,
+        "ChildDetailPageDisplay" => v.child_detail_page_display = Some(characters(s)?),
+        "Type" => v.relation_type = characters(s)?,
+        _ => {}
+      }
+      Ok(())
+    })
+  }
+}
+
+impl<S: XmlEventStream> FromXmlStream<S> for RelationshipMessage {
+  fn from_xml(s: &mut S) -> MwsResult<Self> {
+    fold_elements(s, RelationshipMessage::default(), |s, v| {
+      match s.local_name() {
+        "ParentSKU" => v.parent_sku = characters(s)?,
+        "Relation" => v.relations.push(Relation::from_xml(s)?),
+        _ => {}
+      }
+      Ok(())
+    })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use feeds::OperationType;
+  use xmlhelper::encode::{EmitterConfig, EventWriter};
+
+  #[test]
+  fn test_relationship_feed() {
+    let mut writer =
+      EventWriter::new_with_config(vec![], EmitterConfig::new().perform_indent(true));
+
+    let mut e = Envelope::<RelationshipMessage>::new("M_EXAMPLE_123456".to_string());
+    e.add_message(
+      RelationshipMessage {
+        message_id: "1".to_string(),
+        parent_sku: "edifier-r1280t-parent".to_string(),
+        relations: vec![
+          Relation {
+            sku: "edifier-r1280t-brown".to_string(),
+            child_detail_page_display: None,
+            relation_type: RelationType::Variation,
+          },
+          Relation {
+            sku: "speaker-stand".to_string(),
+            child_detail_page_display: Some("Display".to_string()),
+            relation_type: RelationType::Accessory,
+          },
+        ],
+      },
+      Some(OperationType::Update),
+    );
+    e.write_xml(&mut writer).unwrap();
+
+    let xml = String::from_utf8(writer.into_inner()).unwrap();
+    assert_eq!(
+      xml,
+      r#"<?xml version="1.0" encoding="utf-8"?>
+<AmazonEnvelope xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance" xsi:noNamespaceSchemaLocation="amznenvelope.xsd">
+  <Header>
+    <DocumentVersion>1.01</DocumentVersion>
+    <MerchantIdentifier>M_EXAMPLE_123456</MerchantIdentifier>
+  </Header>
+  <MessageType>Relationship</MessageType>
+  <Message>
+    <MessageID>1</MessageID>
+    <OperationType>Update</OperationType>
+    <Relationship>
+      <ParentSKU>edifier-r1280t-parent</ParentSKU>
+      <Relation>
+        <SKU>edifier-r1280t-brown</SKU>
+        <Type>Variation</Type>
+      </Relation>
+      <Relation>
+        <SKU>speaker-stand</SKU>
+        <ChildDetailPageDisplay>Display</ChildDetailPageDisplay>
+        <Type>Accessory</Type>
+      </Relation>
+    </Relationship>
+  </Message>
+</AmazonEnvelope>"#
+    );
+    assert_eq!(
+      Envelope::<RelationshipMessage>::from_reader(xml.as_bytes()).unwrap(),
+      e
+    );
+  }
+}
@@ -4,11 +4,20 @@
 
 use chrono::{DateTime, Utc};
 use client::{Client, Method};
+use reports::GetCountResponse;
 use result::MwsResult;
+use std::collections::HashMap;
 use std::io::{Read, Write};
+use xmlhelper::decode::{element, start_document, ElementScopedStream, FromXmlStream, Stream};
 use xmlhelper::encode;
 
 pub mod message;
+pub mod processing_report;
+pub use self::processing_report::{get_feed_processing_report, FeedProcessingReport};
+pub mod submit;
+pub use self::submit::{submit_and_wait, SubmitAndWaitOutcome, SubmitFeedOptions};
+pub mod validate;
+pub use self::validate::{FeedValidationError, Validate};
 
 static PATH: &'static str = "/";
 static VERSION: &'static str = "2009-01-01";
@@ -30,6 +39,10 @@ string_map_enum! {
     FlatFilePriceAndQuantityUpdate = "_POST_FLAT_FILE_PRICEANDQUANTITYONLY_UPDATE_DATA_",
     UIEEInventory = "_POST_UIEE_BOOKLOADER_DATA_",
     AutomotivePartFinder = "_POST_STD_ACES_DATA_",
+    OrderAcknowledgement = "_POST_ORDER_ACKNOWLEDGEMENT_DATA_",
+    OrderAdjustment = "_POST_PAYMENT_ADJUSTMENT_DATA_",
+    OrderFulfillment = "_POST_ORDER_FULFILLMENT_DATA_",
+    FbaInboundCartonContents = "_POST_FBA_INBOUND_CARTON_CONTENTS_",
   }
 }
 
@@ -53,8 +66,29 @@ pub enum OperationType {
   PartialUpdate,
 }
 
+impl OperationType {
+  pub fn as_str(&self) -> &'static str {
+    match *self {
+      OperationType::Update => "Update",
+      OperationType::Delete => "Delete",
+      OperationType::PartialUpdate => "PartialUpdate",
+    }
+  }
+}
+
 pub trait Message {
   fn get_message_type() -> &'static str;
+
+  /// The `MessageID` written to the envelope, used to match processing report results.
+  ///
+  /// Defaults to an empty id for messages that do not keep their `MessageID`, they are not
+  /// assigned ids and can not be looked up with `Envelope::message_map`.
+  fn message_id(&self) -> &str {
+    ""
+  }
+
+  /// Called by `Envelope::add_message` to assign a `MessageID` to a message without one
+  fn set_message_id(&mut self, _message_id: String) {}
 }
 
 #[allow(non_snake_case)]
@@ -69,6 +103,8 @@ pub struct EnvelopeMessage<M: Message> {
 pub struct Envelope<M: Message> {
   pub MerchantIdentifier: String,
   messages: Vec<EnvelopeMessage<M>>,
+  #[serde(skip)]
+  next_message_id: u64,
 }
 
 impl<M: Message> Envelope<M> {
@@ -76,17 +112,102 @@ impl<M: Message> Envelope<M> {
     Envelope::<M> {
       MerchantIdentifier: merchant_identifier,
       messages: vec![],
+      next_message_id: 1,
     }
   }
 
-  pub fn add_message(&mut self, m: M, operation_type: Option<OperationType>) -> &mut Self {
-    self.messages.push(EnvelopeMessage::<M> {
+  /// Adds a message to the envelope.
+  ///
+  /// A message with an empty `message_id` is assigned the next sequential `MessageID`, starting
+  /// at `1` and skipping past numeric ids already added. Duplicate `MessageID`s are not checked
+  /// here, they are reported by `Envelope::validate`.
+  pub fn add_message(&mut self, mut m: M, operation_type: Option<OperationType>) -> &mut Self {
+    if m.message_id().is_empty() {
+      m.set_message_id(self.next_message_id.to_string());
+    }
+    self.push_message(EnvelopeMessage::<M> {
       data: m,
-      operation_type: operation_type,
+      operation_type,
     });
     self
   }
 
+  pub fn messages(&self) -> &[EnvelopeMessage<M>] {
+    &self.messages
+  }
+
+  /// Returns the messages by `MessageID`, to correlate processing report results.
+  ///
+  /// If `MessageID`s are duplicated, the last message with the id is returned.
+  pub fn message_map(&self) -> HashMap<&str, &EnvelopeMessage<M>> {
+    self
+      .messages
+      .iter()
+      .map(|m| (m.data.message_id(), m))
+      .collect()
+  }
+
+  /// Splits the envelope into envelopes of at most `max_messages` messages each.
+  ///
+  /// `MessageID`s are kept, so results of each submission can be matched with `message_map`.
+  pub fn split_by_count(self, max_messages: usize) -> Vec<Envelope<M>> {
+    let max_messages = ::std::cmp::max(max_messages, 1);
+    let merchant_identifier = self.MerchantIdentifier;
+    let mut envelopes: Vec<Envelope<M>> = vec![];
+    for message in self.messages {
+      let full = envelopes
+        .last()
+        .map(|e| e.messages.len() >= max_messages)
+        .unwrap_or(true);
+      if full {
+        envelopes.push(Envelope::new(merchant_identifier.clone()));
+      }
+      envelopes.last_mut().unwrap().push_message(message);
+    }
+    envelopes
+  }
+
+  /// Splits the envelope into envelopes whose serialized size (see `to_xml_string`) is at most
+  /// `max_bytes`.
+  ///
+  /// A message that is larger than `max_bytes` on its own is put into an envelope by itself.
+  pub fn split_by_size(self, max_bytes: usize) -> MwsResult<Vec<Envelope<M>>>
+  where
+    Self: encode::XmlWrite<encode::EventWriter<Vec<u8>>>,
+  {
+    let merchant_identifier = self.MerchantIdentifier;
+    let mut probe = Envelope::<M>::new(merchant_identifier.clone());
+    let empty_size = probe.to_xml_string()?.len();
+
+    let mut envelopes: Vec<(Envelope<M>, usize)> = vec![];
+    for message in self.messages {
+      probe.push_message(message);
+      let message_size = probe.to_xml_string()?.len() - empty_size;
+      let message = probe.messages.pop().unwrap();
+
+      let fits = envelopes
+        .last()
+        .map(|&(_, size)| size + message_size <= max_bytes)
+        .unwrap_or(false);
+      if !fits {
+        envelopes.push((Envelope::new(merchant_identifier.clone()), empty_size));
+      }
+      let last = envelopes.last_mut().unwrap();
+      last.0.push_message(message);
+      last.1 += message_size;
+    }
+    Ok(envelopes.into_iter().map(|(e, _)| e).collect())
+  }
+
+  fn push_message(&mut self, message: EnvelopeMessage<M>) {
+    if let Ok(id) = message.data.message_id().parse::<u64>() {
+      if id >= self.next_message_id {
+        self.next_message_id = id + 1;
+      }
+    }
+    self.messages.push(message);
+  }
+
   pub fn write_envelope_xml<W: encode::XmlEventWriter, F: FnMut(&mut W) -> encode::Result<()>>(
     &self,
     w: &mut W,
@@ -114,6 +235,16 @@ impl<M: Message> Envelope<M> {
     w.write(encode::XmlEvent::end_element().into())
   }
 
+  /// Parses an `AmazonEnvelope` document, e.g. a feed written by `to_xml_string`
+  pub fn from_reader<R: Read>(source: R) -> MwsResult<Self>
+  where
+    Self: for<'a> FromXmlStream<ElementScopedStream<'a, Stream<R>>>,
+  {
+    let mut stream = Stream::new(source);
+    start_document(&mut stream)?;
+    element(&mut stream, "AmazonEnvelope", |s| Self::from_xml(s))
+  }
+
   pub fn to_xml_string(&self) -> MwsResult<String>
   where
     Self: encode::XmlWrite<encode::EventWriter<Vec<u8>>>,
@@ -126,6 +257,12 @@ impl<M: Message> Envelope<M> {
   }
 }
 
+impl<M: Message> Default for Envelope<M> {
+  fn default() -> Self {
+    Envelope::new(String::new())
+  }
+}
+
 #[allow(non_snake_case)]
 #[derive(Debug, Default, SerializeMwsParams)]
 pub struct SubmitFeedParameters {
@@ -191,22 +328,37 @@ pub fn GetFeedSubmissionResult<W: Write>(
   Ok(size)
 }
 
+str_enum! {
+  pub enum FeedProcessingStatus {
+    _AWAITING_ASYNCHRONOUS_REPLY_,
+    _CANCELLED_,
+    _DONE_,
+    _IN_PROGRESS_,
+    _IN_SAFETY_NET_,
+    _SUBMITTED_,
+    _UNCONFIRMED_,
+  }
+}
+
 /// Parameters for `GetFeedSubmissionList`
 #[allow(non_snake_case)]
 #[derive(Debug, Default, Serialize, SerializeMwsParams)]
 pub struct GetFeedSubmissionListParameters {
+  #[mws_param(list_item_type_name = "Id")]
   pub FeedSubmissionIdList: Option<Vec<String>>,
   pub MaxCount: Option<i32>,
+  #[mws_param(list_item_type_name = "Type")]
   pub FeedTypeList: Option<Vec<String>>,
-  pub FeedProcessingStatusList: Option<Vec<String>>,
+  #[mws_param(list_item_type_name = "Status")]
+  pub FeedProcessingStatusList: Option<Vec<FeedProcessingStatus>>,
   pub SubmittedFromDate: Option<DateTime<Utc>>,
   pub SubmittedToDate: Option<DateTime<Utc>>,
 }
 
 #[allow(non_snake_case)]
-#[derive(Debug, Default, Serialize, FromXmlStream)]
+#[derive(Debug, Default, PartialEq, Serialize, FromXmlStream)]
 pub struct FeedSubmissionInfo {
-  pub FeedProcessingStatus: String,
+  pub FeedProcessingStatus: FeedProcessingStatus,
   pub FeedType: String,
   pub FeedSubmissionId: String,
   pub StartedProcessingDate: Option<DateTime<Utc>>,
@@ -269,3 +421,212 @@ pub fn GetFeedSubmissionListByNextToken(
     .map(|e: GetFeedSubmissionListByNextTokenEnvelope| e.into_inner())
     .map_err(|err| err.into())
 }
+
+/// Parameters for `CancelFeedSubmissions`
+#[allow(non_snake_case)]
+#[derive(Debug, Default, Serialize, SerializeMwsParams)]
+pub struct CancelFeedSubmissionsParameters {
+  #[mws_param(list_item_type_name = "Id")]
+  pub FeedSubmissionIdList: Option<Vec<String>>,
+  #[mws_param(list_item_type_name = "Type")]
+  pub FeedTypeList: Option<Vec<String>>,
+  pub SubmittedFromDate: Option<DateTime<Utc>>,
+  pub SubmittedToDate: Option<DateTime<Utc>>,
+}
+
+#[allow(non_snake_case)]
+#[derive(Debug, Default, PartialEq, Serialize, FromXmlStream)]
+pub struct CancelFeedSubmissionsResponse {
+  pub Count: i32,
+  #[from_xml_stream(no_list_wrapper)]
+  pub FeedSubmissionInfo: Vec<FeedSubmissionInfo>,
+}
+
+response_envelope_type!(
+  CancelFeedSubmissionsEnvelope<CancelFeedSubmissionsResponse>,
+  "CancelFeedSubmissionsResponse",
+  "CancelFeedSubmissionsResult"
+);
+
+/// Cancels one or more feed submissions.
+///
+/// If no parameters are specified, all feed submissions that have not started processing are
+/// cancelled.
+#[allow(non_snake_case)]
+pub fn CancelFeedSubmissions(
+  client: &Client,
+  params: CancelFeedSubmissionsParameters,
+) -> MwsResult<CancelFeedSubmissionsResponse> {
+  client
+    .request_xml(Method::POST, PATH, VERSION, "CancelFeedSubmissions", params)
+    .map(|e: CancelFeedSubmissionsEnvelope| e.into_inner())
+    .map_err(|err| err.into())
+}
+
+/// Parameters for `GetFeedSubmissionCount`
+#[allow(non_snake_case)]
+#[derive(Debug, Default, Serialize, SerializeMwsParams)]
+pub struct GetFeedSubmissionCountParameters {
+  #[mws_param(list_item_type_name = "Type")]
+  pub FeedTypeList: Option<Vec<String>>,
+  #[mws_param(list_item_type_name = "Status")]
+  pub FeedProcessingStatusList: Option<Vec<FeedProcessingStatus>>,
+  pub SubmittedFromDate: Option<DateTime<Utc>>,
+  pub SubmittedToDate: Option<DateTime<Utc>>,
+}
+
+response_envelope_type!(
+  GetFeedSubmissionCountEnvelope<GetCountResponse>,
+  "GetFeedSubmissionCountResponse",
+  "GetFeedSubmissionCountResult"
+);
+
+/// Returns a count of the feeds submitted in the previous 90 days.
+#[allow(non_snake_case)]
+pub fn GetFeedSubmissionCount(
+  client: &Client,
+  params: GetFeedSubmissionCountParameters,
+) -> MwsResult<GetCountResponse> {
+  client
+    .request_xml(Method::POST, PATH, VERSION, "GetFeedSubmissionCount", params)
+    .map(|e: GetFeedSubmissionCountEnvelope| e.into_inner())
+    .map_err(|err| err.into())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::message::inventory::InventoryMessage;
+  use super::*;
+  use types::SerializeMwsParams;
+
+  fn inventory(message_id: &str, sku: &str) -> InventoryMessage {
+    InventoryMessage {
+      message_id: message_id.to_string(),
+      sku: sku.to_string(),
+      quantity: 1,
+      fulfillment_latency: 1,
+      switch_fulfillment_to: None,
+    }
+  }
+
+  fn message_ids(envelope: &Envelope<InventoryMessage>) -> Vec<&str> {
+    envelope
+      .messages()
+      .iter()
+      .map(|m| m.data.message_id.as_ref())
+      .collect()
+  }
+
+  fn envelope(len: usize) -> Envelope<InventoryMessage> {
+    let mut envelope = Envelope::<InventoryMessage>::new("M_EXAMPLE_123456".to_string());
+    for i in 0..len {
+      envelope.add_message(inventory("", &format!("sku-{}", i)), None);
+    }
+    envelope
+  }
+
+  #[test]
+  fn test_envelope_message_id() {
+    let mut envelope = Envelope::<InventoryMessage>::new("M_EXAMPLE_123456".to_string());
+    envelope
+      .add_message(inventory("", "a"), None)
+      .add_message(inventory("5", "b"), None)
+      .add_message(inventory("", "c"), None)
+      .add_message(inventory("x", "d"), None)
+      .add_message(inventory("", "e"), None);
+    assert_eq!(message_ids(&envelope), vec!["1", "5", "6", "x", "7"]);
+
+    let map = envelope.message_map();
+    assert_eq!(map.len(), 5);
+    assert_eq!(map["6"].data.sku, "c");
+    assert_eq!(map["x"].data.sku, "d");
+  }
+
+  #[test]
+  fn test_envelope_split_by_count() {
+    let envelopes = envelope(5).split_by_count(2);
+    let ids: Vec<_> = envelopes.iter().map(message_ids).collect();
+    assert_eq!(ids, vec![vec!["1", "2"], vec!["3", "4"], vec!["5"]]);
+    assert!(envelopes
+      .iter()
+      .all(|e| e.MerchantIdentifier == "M_EXAMPLE_123456"));
+  }
+
+  #[test]
+  fn test_envelope_split_by_size() {
+    let whole = envelope(10);
+    let max_bytes = whole.to_xml_string().unwrap().len() / 3;
+    let envelopes = whole.split_by_size(max_bytes).unwrap();
+    assert!(envelopes.len() > 3);
+    for e in &envelopes {
+      assert!(e.to_xml_string().unwrap().len() <= max_bytes);
+    }
+    let ids: Vec<_> = envelopes.iter().flat_map(message_ids).collect();
+    assert_eq!(ids, vec!["1", "2", "3", "4", "5", "6", "7", "8", "9", "10"]);
+
+    // a message larger than `max_bytes` gets an envelope by itself
+    let envelopes = envelope(2).split_by_size(1).unwrap();
+    assert_eq!(envelopes.len(), 2);
+  }
+
+  #[test]
+  fn test_encode_get_feed_submission_count_params() {
+    let params = GetFeedSubmissionCountParameters {
+      FeedTypeList: Some(vec![FeedType::Inventory.as_ref().to_string()]),
+      FeedProcessingStatusList: Some(vec![
+        FeedProcessingStatus::_SUBMITTED_,
+        FeedProcessingStatus::_IN_PROGRESS_,
+      ]),
+      ..Default::default()
+    };
+    assert_eq!(
+      params.into_mws_params(),
+      vec![
+        (
+          "FeedTypeList.Type.1".to_owned(),
+          "_POST_INVENTORY_AVAILABILITY_DATA_".to_owned()
+        ),
+        (
+          "FeedProcessingStatusList.Status.1".to_owned(),
+          "_SUBMITTED_".to_owned()
+        ),
+        (
+          "FeedProcessingStatusList.Status.2".to_owned(),
+          "_IN_PROGRESS_".to_owned()
+        ),
+      ]
+    );
+  }
+
+  #[test]
+  fn test_decode_cancel_feed_submissions_response() {
+    test_decode_envelope!(
+      CancelFeedSubmissionsEnvelope,
+      r#"<?xml version="1.0"?>
+        <CancelFeedSubmissionsResponse xmlns="http://mws.amazonaws.com/doc/2009-01-01/">
+          <CancelFeedSubmissionsResult>
+            <Count>1</Count>
+            <FeedSubmissionInfo>
+              <FeedSubmissionId>2291326430</FeedSubmissionId>
+              <FeedType>_POST_PRODUCT_DATA_</FeedType>
+              <SubmittedDate>2009-02-20T02:10:35+00:00</SubmittedDate>
+              <FeedProcessingStatus>_CANCELLED_</FeedProcessingStatus>
+            </FeedSubmissionInfo>
+          </CancelFeedSubmissionsResult>
+          <ResponseMetadata>
+            <RequestId>18e78983-bbf9-43aa-a661-ae7696cb49d4</RequestId>
+          </ResponseMetadata>
+        </CancelFeedSubmissionsResponse>"#,
+      CancelFeedSubmissionsResponse {
+        Count: 1,
+        FeedSubmissionInfo: vec![FeedSubmissionInfo {
+          FeedProcessingStatus: FeedProcessingStatus::_CANCELLED_,
+          FeedType: "_POST_PRODUCT_DATA_".to_string(),
+          FeedSubmissionId: "2291326430".to_string(),
+          SubmittedDate: Some("2009-02-20T02:10:35+00:00".parse().unwrap()),
+          ..Default::default()
+        }],
+      }
+    );
+  }
+}
@@ -0,0 +1,360 @@
+//! Processing reports returned by `GetFeedSubmissionResult`
+//!
+//! XML feeds return an `AmazonEnvelope` containing a `ProcessingReport` message, flat file feeds
+//! return a tab-delimited report with a summary section. `FeedProcessingReport::from_reader`
+//! detects the format:
+//!
+//! ```no_run
+//! # use mws::feeds::processing_report::FeedProcessingReport;
+//! let f = std::fs::File::open("result.xml").unwrap();
+//! match FeedProcessingReport::from_reader(f).unwrap() {
+//!   FeedProcessingReport::Xml(report) => println!("{:?}", report.errors()),
+//!   FeedProcessingReport::FlatFile(report) => println!("{:?}", report.results),
+//! }
+//! ```
+
+use client::Client;
+use result::MwsResult;
+use std::io::{BufRead, BufReader, Cursor, Read};
+use tdff::TdffParser;
+use xmlhelper::decode::{
+  element, seek_element, start_document, start_element, FromXmlStream, Stream,
+};
+
+#[allow(non_snake_case)]
+#[derive(Debug, Default, PartialEq, Serialize, FromXmlStream)]
+pub struct ProcessingSummary {
+  pub MessagesProcessed: i32,
+  pub MessagesSuccessful: i32,
+  pub MessagesWithError: i32,
+  pub MessagesWithWarning: i32,
+}
+
+/// Identifies the item a result applies to, which elements are present depends on the feed type
+#[allow(non_snake_case)]
+#[derive(Debug, Default, PartialEq, Serialize, FromXmlStream)]
+pub struct AdditionalInfo {
+  pub SKU: Option<String>,
+  pub FulfillmentCenterID: Option<String>,
+  pub AmazonOrderID: Option<String>,
+  pub AmazonOrderItemCode: Option<String>,
+}
+
+/// The result of one feed message, only messages with errors or warnings are reported
+#[allow(non_snake_case)]
+#[derive(Debug, Default, PartialEq, Serialize, FromXmlStream)]
+pub struct ProcessingResult {
+  /// `MessageID` of the message in the submitted feed, `0` for feed level errors
+  pub MessageID: String,
+  /// `Error` or `Warning`
+  pub ResultCode: String,
+  pub ResultMessageCode: String,
+  pub ResultDescription: String,
+  pub AdditionalInfo: Option<AdditionalInfo>,
+}
+
+impl ProcessingResult {
+  pub fn is_error(&self) -> bool {
+    self.ResultCode == "Error"
+  }
+
+  pub fn is_warning(&self) -> bool {
+    self.ResultCode == "Warning"
+  }
+}
+
+/// Processing report of an XML feed
+///
+/// [Documentation](https://images-na.ssl-images-amazon.com/images/G/01/rainier/help/xsd/release_1_9/ProcessingReport.xsd)
+#[allow(non_snake_case)]
+#[derive(Debug, Default, PartialEq, Serialize, FromXmlStream)]
+pub struct ProcessingReport {
+  pub DocumentTransactionID: String,
+  /// `Complete` or `Processing`
+  pub StatusCode: String,
+  pub ProcessingSummary: Option<ProcessingSummary>,
+  #[from_xml_stream(no_list_wrapper)]
+  pub Result: Vec<ProcessingResult>,
+}
+
+impl ProcessingReport {
+  /// Parses the `ProcessingReport` message of an `AmazonEnvelope`
+  pub fn from_reader<R: Read>(source: R) -> MwsResult<Self> {
+    let mut stream = Stream::new(source);
+    start_document(&mut stream)?;
+    start_element(&mut stream, "AmazonEnvelope")?;
+    seek_element(&mut stream, "Message")?;
+    start_element(&mut stream, "Message")?;
+    seek_element(&mut stream, "ProcessingReport")?;
+    element(&mut stream, "ProcessingReport", |s| ProcessingReport::from_xml(s))
+  }
+
+  pub fn errors(&self) -> Vec<&ProcessingResult> {
+    self.Result.iter().filter(|r| r.is_error()).collect()
+  }
+
+  pub fn warnings(&self) -> Vec<&ProcessingResult> {
+    self.Result.iter().filter(|r| r.is_warning()).collect()
+  }
+}
+
+/// A row of a flat file processing report
+#[derive(Debug, Default, PartialEq, Serialize, FromTdffRow)]
+pub struct FlatFileProcessingResult {
+  /// Line number of the record in the submitted feed, `0` for feed level errors
+  #[from_tdff_row(key = "original-record-number", required)]
+  pub original_record_number: String,
+  pub sku: String,
+  #[from_tdff_row(key = "error-code", required)]
+  pub error_code: String,
+  /// `Error` or `Warning`
+  #[from_tdff_row(key = "error-type", required)]
+  pub error_type: String,
+  #[from_tdff_row(key = "error-message")]
+  pub error_message: String,
+}
+
+impl FlatFileProcessingResult {
+  pub fn is_error(&self) -> bool {
+    self.error_type == "Error"
+  }
+
+  pub fn is_warning(&self) -> bool {
+    self.error_type == "Warning"
+  }
+}
+
+/// Processing report of a flat file feed
+///
+/// ```text
+/// Feed Processing Summary:
+///   Number of records processed    2
+///   Number of records successful   1
+///
+/// original-record-number  sku  error-code  error-type  error-message
+/// 2                       ...
+/// ```
+#[derive(Debug, Default, PartialEq, Serialize)]
+pub struct FlatFileProcessingReport {
+  pub records_processed: i32,
+  pub records_successful: i32,
+  pub results: Vec<FlatFileProcessingResult>,
+}
+
+impl FlatFileProcessingReport {
+  pub fn from_reader<R: Read>(source: R) -> MwsResult<Self> {
+    let mut report = FlatFileProcessingReport::default();
+    let mut reader = BufReader::new(source);
+    let mut line = String::new();
+    loop {
+      line.clear();
+      if reader.read_line(&mut line)? == 0 {
+        return Ok(report);
+      }
+
+      if line.starts_with("original-record-number") {
+        break;
+      }
+
+      let mut parts = line.split('\t').map(str::trim).filter(|v| !v.is_empty());
+      let (label, value) = match (parts.next(), parts.next()) {
+        (Some(label), Some(value)) => (label, value),
+        _ => continue,
+      };
+      match label {
+        "Number of records processed" => {
+          report.records_processed = ::xmlhelper::decode::parse_str(value)?
+        }
+        "Number of records successful" => {
+          report.records_successful = ::xmlhelper::decode::parse_str(value)?
+        }
+        _ => {}
+      }
+    }
+
+    report.results = TdffParser::new(Cursor::new(line).chain(reader))?.parse_all()?;
+    Ok(report)
+  }
+
+  pub fn errors(&self) -> Vec<&FlatFileProcessingResult> {
+    self.results.iter().filter(|r| r.is_error()).collect()
+  }
+
+  pub fn warnings(&self) -> Vec<&FlatFileProcessingResult> {
+    self.results.iter().filter(|r| r.is_warning()).collect()
+  }
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub enum FeedProcessingReport {
+  Xml(ProcessingReport),
+  FlatFile(FlatFileProcessingReport),
+}
+
+impl FeedProcessingReport {
+  /// Parses an XML or a flat file processing report, depending on the first non-whitespace byte
+  pub fn from_reader<R: Read>(source: R) -> MwsResult<Self> {
+    let mut reader = BufReader::new(source);
+    let is_xml = loop {
+      let (skip, first) = {
+        let buf = reader.fill_buf()?;
+        if buf.is_empty() {
+          break false;
+        }
+        match buf.iter().position(|b| !b.is_ascii_whitespace()) {
+          Some(pos) => (pos, Some(buf[pos])),
+          None => (buf.len(), None),
+        }
+      };
+      reader.consume(skip);
+      if let Some(first) = first {
+        break first == b'<';
+      }
+    };
+
+    if is_xml {
+      ProcessingReport::from_reader(reader).map(FeedProcessingReport::Xml)
+    } else {
+      FlatFileProcessingReport::from_reader(reader).map(FeedProcessingReport::FlatFile)
+    }
+  }
+}
+
+/// Downloads the processing report of a feed submission with `GetFeedSubmissionResult` and
+/// parses it.
+pub fn get_feed_processing_report(
+  client: &Client,
+  feed_submission_id: String,
+) -> MwsResult<FeedProcessingReport> {
+  let mut body = vec![];
+  super::GetFeedSubmissionResult(client, feed_submission_id, &mut body)?;
+  FeedProcessingReport::from_reader(Cursor::new(body))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const XML: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<AmazonEnvelope xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance" xsi:noNamespaceSchemaLocation="amzn-envelope.xsd">
+  <Header>
+    <DocumentVersion>1.02</DocumentVersion>
+    <MerchantIdentifier>M_EXAMPLE_123456</MerchantIdentifier>
+  </Header>
+  <MessageType>ProcessingReport</MessageType>
+  <Message>
+    <MessageID>1</MessageID>
+    <ProcessingReport>
+      <DocumentTransactionID>50047018011</DocumentTransactionID>
+      <StatusCode>Complete</StatusCode>
+      <ProcessingSummary>
+        <MessagesProcessed>3</MessagesProcessed>
+        <MessagesSuccessful>1</MessagesSuccessful>
+        <MessagesWithError>1</MessagesWithError>
+        <MessagesWithWarning>1</MessagesWithWarning>
+      </ProcessingSummary>
+      <Result>
+        <MessageID>2</MessageID>
+        <ResultCode>Error</ResultCode>
+        <ResultMessageCode>8560</ResultMessageCode>
+        <ResultDescription>SKU edifier-r1280t, Missing Attributes standard_product_id.</ResultDescription>
+        <AdditionalInfo>
+          <SKU>edifier-r1280t</SKU>
+        </AdditionalInfo>
+      </Result>
+      <Result>
+        <MessageID>3</MessageID>
+        <ResultCode>Warning</ResultCode>
+        <ResultMessageCode>99001</ResultMessageCode>
+        <ResultDescription>A value was not provided for "item_type".</ResultDescription>
+      </Result>
+    </ProcessingReport>
+  </Message>
+</AmazonEnvelope>"#;
+
+  const FLAT_FILE: &str = "Feed Processing Summary:\n\
+                           \tNumber of records processed\t\t3\n\
+                           \tNumber of records successful\t\t1\n\
+                           \n\
+                           original-record-number\tsku\terror-code\terror-type\terror-message\n\
+                           2\tedifier-r1280t\t8560\tError\tMissing Attributes standard_product_id.\n\
+                           3\tedifier-s1000db\t99001\tWarning\tA value was not provided for item_type.\n";
+
+  #[test]
+  fn test_processing_report() {
+    let report = ProcessingReport::from_reader(Cursor::new(XML)).unwrap();
+    assert_eq!(
+      report,
+      ProcessingReport {
+        DocumentTransactionID: "50047018011".to_string(),
+        StatusCode: "Complete".to_string(),
+        ProcessingSummary: Some(ProcessingSummary {
+          MessagesProcessed: 3,
+          MessagesSuccessful: 1,
+          MessagesWithError: 1,
+          MessagesWithWarning: 1,
+        }),
+        Result: vec![
+          ProcessingResult {
+            MessageID: "2".to_string(),
+            ResultCode: "Error".to_string(),
+            ResultMessageCode: "8560".to_string(),
+            ResultDescription: "SKU edifier-r1280t, Missing Attributes standard_product_id."
+              .to_string(),
+            AdditionalInfo: Some(AdditionalInfo {
+              SKU: Some("edifier-r1280t".to_string()),
+              ..Default::default()
+            }),
+          },
+          ProcessingResult {
+            MessageID: "3".to_string(),
+            ResultCode: "Warning".to_string(),
+            ResultMessageCode: "99001".to_string(),
+            ResultDescription: "A value was not provided for \"item_type\".".to_string(),
+            AdditionalInfo: None,
+          },
+        ],
+      }
+    );
+    assert_eq!(report.errors().len(), 1);
+    assert_eq!(report.warnings()[0].MessageID, "3");
+  }
+
+  #[test]
+  fn test_flat_file_processing_report() {
+    let report = FlatFileProcessingReport::from_reader(Cursor::new(FLAT_FILE)).unwrap();
+    assert_eq!(report.records_processed, 3);
+    assert_eq!(report.records_successful, 1);
+    assert_eq!(
+      report.results[0],
+      FlatFileProcessingResult {
+        original_record_number: "2".to_string(),
+        sku: "edifier-r1280t".to_string(),
+        error_code: "8560".to_string(),
+        error_type: "Error".to_string(),
+        error_message: "Missing Attributes standard_product_id.".to_string(),
+      }
+    );
+    assert_eq!(report.errors().len(), 1);
+    assert_eq!(report.warnings()[0].sku, "edifier-s1000db");
+
+    let report = FlatFileProcessingReport::from_reader(Cursor::new(
+      "Feed Processing Summary:\n\tNumber of records processed\t\t1\n\tNumber of records successful\t\t1\n\n",
+    ))
+    .unwrap();
+    assert_eq!(report.records_successful, 1);
+    assert_eq!(report.results, vec![]);
+  }
+
+  #[test]
+  fn test_detect_format() {
+    match FeedProcessingReport::from_reader(Cursor::new(format!("\n  {}", XML))).unwrap() {
+      FeedProcessingReport::Xml(report) => assert_eq!(report.Result.len(), 2),
+      other => panic!("unexpected report: {:?}", other),
+    }
+    match FeedProcessingReport::from_reader(Cursor::new(FLAT_FILE)).unwrap() {
+      FeedProcessingReport::FlatFile(report) => assert_eq!(report.results.len(), 2),
+      other => panic!("unexpected report: {:?}", other),
+    }
+  }
+}
@@ -0,0 +1,263 @@
+//! Submit → poll → processing report workflow
+
+use super::processing_report::{ProcessingReport, ProcessingResult};
+use super::validate::Validate;
+use super::{
+  Envelope, EnvelopeMessage, FeedProcessingStatus, FeedType, GetFeedSubmissionList,
+  GetFeedSubmissionListParameters, GetFeedSubmissionResult, Message, SubmitFeed,
+  SubmitFeedParameters,
+};
+use client::Client;
+use reports::PollPolicy;
+use result::{MwsError, MwsResult};
+use std::io::Cursor;
+use std::thread::sleep;
+use std::time::Instant;
+use xmlhelper::encode::{EventWriter, XmlWrite};
+
+/// Options of `submit_and_wait`
+#[derive(Debug, Default)]
+pub struct SubmitFeedOptions {
+  pub marketplace_id_list: Option<Vec<String>>,
+  pub purge_and_replace: Option<bool>,
+  pub poll_policy: PollPolicy,
+}
+
+/// The processing results of one message of the submitted envelope
+#[derive(Debug)]
+pub struct MessageOutcome<'a, M: Message + 'a> {
+  pub message: &'a EnvelopeMessage<M>,
+  /// Errors and warnings reported for this message, empty if it was processed without issues
+  pub results: Vec<ProcessingResult>,
+}
+
+impl<'a, M: Message + 'a> MessageOutcome<'a, M> {
+  /// Returns `true` if no error was reported for this message, warnings are ignored.
+  pub fn is_success(&self) -> bool {
+    !self.results.iter().any(ProcessingResult::is_error)
+  }
+}
+
+#[derive(Debug)]
+pub struct FeedSubmissionOutcome<'a, M: Message + 'a> {
+  pub feed_submission_id: String,
+  pub document_transaction_id: String,
+  /// One entry per envelope message, in envelope order
+  pub messages: Vec<MessageOutcome<'a, M>>,
+  /// Results without a matching message, e.g. feed level errors reported with `MessageID` `0`
+  pub unmatched_results: Vec<ProcessingResult>,
+}
+
+impl<'a, M: Message + 'a> FeedSubmissionOutcome<'a, M> {
+  /// Matches the results of `report` to the messages of `envelope` by `MessageID`
+  pub fn new(
+    feed_submission_id: String,
+    envelope: &'a Envelope<M>,
+    report: ProcessingReport,
+  ) -> Self {
+    let mut messages: Vec<_> = envelope
+      .messages
+      .iter()
+      .map(|message| MessageOutcome {
+        message,
+        results: vec![],
+      })
+      .collect();
+    let mut unmatched_results = vec![];
+    for result in report.Result {
+      match messages
+        .iter_mut()
+        .find(|outcome| outcome.message.data.message_id() == result.MessageID)
+      {
+        Some(outcome) => outcome.results.push(result),
+        None => unmatched_results.push(result),
+      }
+    }
+    FeedSubmissionOutcome {
+      feed_submission_id,
+      document_transaction_id: report.DocumentTransactionID,
+      messages,
+      unmatched_results,
+    }
+  }
+
+  /// Returns `true` if every message succeeded and there is no feed level error
+  pub fn is_success(&self) -> bool {
+    self.messages.iter().all(MessageOutcome::is_success)
+      && !self
+        .unmatched_results
+        .iter()
+        .any(ProcessingResult::is_error)
+  }
+
+  pub fn failed_messages(&self) -> Vec<&MessageOutcome<'a, M>> {
+    self.messages.iter().filter(|m| !m.is_success()).collect()
+  }
+}
+
+#[derive(Debug)]
+pub enum SubmitAndWaitOutcome<'a, M: Message + 'a> {
+  /// The feed was processed and its processing report was matched to the envelope messages.
+  Done(FeedSubmissionOutcome<'a, M>),
+  /// The feed submission was cancelled.
+  Cancelled { feed_submission_id: String },
+  /// The feed was still being processed when `PollPolicy::timeout` elapsed.
+  TimedOut { feed_submission_id: String },
+}
+
+/// Serializes `envelope`, submits it with its Content-MD5, polls `GetFeedSubmissionList` until
+/// the submission is done, then downloads and parses the processing report.
+///
+/// Use `submit_and_wait_validated` to validate the envelope first.
+///
+/// Throttling and other temporary errors (see `MwsError::should_try_again`) during polling are
+/// retried with the poll interval.
+pub fn submit_and_wait<'a, M>(
+  client: &Client,
+  feed_type: FeedType,
+  envelope: &'a Envelope<M>,
+  options: SubmitFeedOptions,
+) -> MwsResult<SubmitAndWaitOutcome<'a, M>>
+where
+  M: Message + 'a,
+  Envelope<M>: XmlWrite<EventWriter<Vec<u8>>>,
+{
+  let content = envelope.to_xml_string()?.into_bytes();
+  let content_md5 = ::base64::encode(&*::md5::compute(&content));
+
+  let started = Instant::now();
+  let feed_submission_id = SubmitFeed(
+    client,
+    SubmitFeedParameters {
+      FeedType: feed_type.as_ref().to_string(),
+      MarketplaceIdList: options.marketplace_id_list,
+      PurgeAndReplace: options.purge_and_replace,
+    },
+    Cursor::new(content),
+    content_md5,
+    "text/xml".to_string(),
+  )?
+  .FeedSubmissionInfo
+  .FeedSubmissionId;
+
+  let poll_policy = options.poll_policy;
+  let mut attempt = 0;
+  loop {
+    let interval = poll_policy.interval(attempt);
+    attempt += 1;
+    if started.elapsed() + interval > poll_policy.timeout {
+      return Ok(SubmitAndWaitOutcome::TimedOut { feed_submission_id });
+    }
+    sleep(interval);
+
+    let res = GetFeedSubmissionList(
+      client,
+      GetFeedSubmissionListParameters {
+        FeedSubmissionIdList: Some(vec![feed_submission_id.clone()]),
+        ..Default::default()
+      },
+    );
+    let info = match res {
+      Ok(res) => res
+        .FeedSubmissionInfo
+        .into_iter()
+        .find(|info| info.FeedSubmissionId == feed_submission_id),
+      Err(ref err) if err.should_try_again() => continue,
+      Err(err) => return Err(err),
+    };
+
+    match info.map(|info| info.FeedProcessingStatus) {
+      Some(FeedProcessingStatus::_DONE_) => break,
+      Some(FeedProcessingStatus::_CANCELLED_) => {
+        return Ok(SubmitAndWaitOutcome::Cancelled { feed_submission_id })
+      }
+      _ => {}
+    }
+  }
+
+  let mut body = vec![];
+  GetFeedSubmissionResult(client, feed_submission_id.clone(), &mut body)?;
+  let report = ProcessingReport::from_reader(Cursor::new(body))?;
+  Ok(SubmitAndWaitOutcome::Done(FeedSubmissionOutcome::new(
+    feed_submission_id,
+    envelope,
+    report,
+  )))
+}
+
+/// Validates `envelope` with `Envelope::validate`, then submits it with `submit_and_wait`.
+///
+/// Nothing is submitted if any message is invalid, the violations are returned as
+/// `MwsError::FeedValidation`.
+pub fn submit_and_wait_validated<'a, M>(
+  client: &Client,
+  feed_type: FeedType,
+  envelope: &'a Envelope<M>,
+  options: SubmitFeedOptions,
+) -> MwsResult<SubmitAndWaitOutcome<'a, M>>
+where
+  M: Message + Validate + 'a,
+  Envelope<M>: XmlWrite<EventWriter<Vec<u8>>>,
+{
+  envelope.validate().map_err(MwsError::FeedValidation)?;
+  submit_and_wait(client, feed_type, envelope, options)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::super::message::inventory::InventoryMessage;
+  use super::*;
+
+  fn result(message_id: &str, code: &str) -> ProcessingResult {
+    ProcessingResult {
+      MessageID: message_id.to_string(),
+      ResultCode: code.to_string(),
+      ..Default::default()
+    }
+  }
+
+  #[test]
+  fn test_feed_submission_outcome() {
+    let mut envelope = Envelope::<InventoryMessage>::new("M_EXAMPLE_123456".to_string());
+    for (id, sku) in &[("1", "sku-1"), ("2", "sku-2"), ("3", "sku-3")] {
+      envelope.add_message(
+        InventoryMessage {
+          message_id: id.to_string(),
+          sku: sku.to_string(),
+          quantity: 1,
+          fulfillment_latency: 1,
+          switch_fulfillment_to: None,
+        },
+        None,
+      );
+    }
+
+    let outcome = FeedSubmissionOutcome::new(
+      "50047018011".to_string(),
+      &envelope,
+      ProcessingReport {
+        DocumentTransactionID: "50047018011".to_string(),
+        StatusCode: "Complete".to_string(),
+        Result: vec![
+          result("2", "Error"),
+          result("3", "Warning"),
+          result("0", "Warning"),
+        ],
+        ..Default::default()
+      },
+    );
+
+    assert!(!outcome.is_success());
+    let status: Vec<_> = outcome
+      .messages
+      .iter()
+      .map(|m| (m.message.data.sku.as_ref(), m.is_success(), m.results.len()))
+      .collect();
+    assert_eq!(
+      status,
+      vec![("sku-1", true, 0), ("sku-2", false, 1), ("sku-3", true, 1)]
+    );
+    assert_eq!(outcome.failed_messages().len(), 1);
+    assert_eq!(outcome.unmatched_results, vec![result("0", "Warning")]);
+  }
+}
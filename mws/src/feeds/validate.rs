@@ -0,0 +1,211 @@
+//! Client side validation of feed messages
+//!
+//! Catches values Amazon would reject while processing the feed, before it is submitted.
+
+use super::{Envelope, Message};
+use std::collections::HashSet;
+use std::fmt;
+
+/// Max length of a SKU
+pub const MAX_SKU_LEN: usize = 40;
+
+/// A rule broken by a message field
+#[derive(Debug, Clone, PartialEq)]
+pub struct Violation {
+  pub field: String,
+  pub message: String,
+}
+
+impl Violation {
+  pub fn new<F: Into<String>, M: Into<String>>(field: F, message: M) -> Self {
+    Violation {
+      field: field.into(),
+      message: message.into(),
+    }
+  }
+}
+
+pub trait Validate {
+  /// Returns all violations of the message, empty if the message is valid
+  fn validate(&self) -> Vec<Violation>;
+}
+
+/// A `Violation` of an envelope message
+#[derive(Debug, Clone, PartialEq)]
+pub struct MessageViolation {
+  pub message_id: String,
+  pub violation: Violation,
+}
+
+/// Returned by `Envelope::validate` if any message is invalid
+#[derive(Debug, Clone, PartialEq)]
+pub struct FeedValidationError {
+  pub violations: Vec<MessageViolation>,
+}
+
+impl fmt::Display for FeedValidationError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    let parts: Vec<_> = self
+      .violations
+      .iter()
+      .map(|v| {
+        format!(
+          "message {}: {}: {}",
+          v.message_id, v.violation.field, v.violation.message
+        )
+      })
+      .collect();
+    write!(f, "{}", parts.join("; "))
+  }
+}
+
+impl<M: Message + Validate> Envelope<M> {
+  /// Validates every message of the envelope, all violations are returned.
+  ///
+  /// A message whose `MessageID` is already used by a previous message is reported as a
+  /// `message_id` violation.
+  pub fn validate(&self) -> Result<(), FeedValidationError> {
+    let mut message_ids = HashSet::new();
+    let mut violations = vec![];
+    for m in self.messages() {
+      let message_id = m.data.message_id();
+      if !message_id.is_empty() && !message_ids.insert(message_id) {
+        violations.push(MessageViolation {
+          message_id: message_id.to_string(),
+          violation: Violation::new("message_id", "duplicate MessageID"),
+        });
+      }
+      violations.extend(
+        m.data
+          .validate()
+          .into_iter()
+          .map(|violation| MessageViolation {
+            message_id: message_id.to_string(),
+            violation,
+          }),
+      );
+    }
+    if violations.is_empty() {
+      Ok(())
+    } else {
+      Err(FeedValidationError { violations })
+    }
+  }
+}
+
+/// Checks that `sku` is not empty and at most `MAX_SKU_LEN` characters long
+pub fn validate_sku(violations: &mut Vec<Violation>, field: &str, sku: &str) {
+  if sku.is_empty() {
+    violations.push(Violation::new(field, "must not be empty"));
+  } else if sku.chars().count() > MAX_SKU_LEN {
+    violations.push(Violation::new(
+      field,
+      format!("must be at most {} characters: '{}'", MAX_SKU_LEN, sku),
+    ));
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use chrono::{Duration, Utc};
+  use feeds::message::inventory::InventoryMessage;
+  use feeds::message::order_fulfillment_data::{OrderFulfillmentItem, OrderFulfillmentMessage};
+
+  fn inventory(sku: &str, quantity: i32, fulfillment_latency: i32) -> InventoryMessage {
+    InventoryMessage {
+      message_id: "".to_string(),
+      sku: sku.to_string(),
+      quantity,
+      fulfillment_latency,
+      switch_fulfillment_to: None,
+    }
+  }
+
+  fn fields(err: &FeedValidationError) -> Vec<(&str, &str)> {
+    err
+      .violations
+      .iter()
+      .map(|v| (v.message_id.as_ref(), v.violation.field.as_ref()))
+      .collect()
+  }
+
+  #[test]
+  fn test_validate_inventory_envelope() {
+    let mut envelope = Envelope::<InventoryMessage>::new("M_EXAMPLE_123456".to_string());
+    envelope
+      .add_message(inventory("sku-1", 1, 2), None)
+      .add_message(inventory(&"x".repeat(41), -1, 0), None)
+      .add_message(inventory("sku-3", 0, 31), None);
+    let err = envelope.validate().unwrap_err();
+    assert_eq!(
+      fields(&err),
+      vec![
+        ("2", "sku"),
+        ("2", "quantity"),
+        ("2", "fulfillment_latency"),
+        ("3", "fulfillment_latency"),
+      ]
+    );
+
+    let mut envelope = Envelope::<InventoryMessage>::new("M_EXAMPLE_123456".to_string());
+    envelope.add_message(inventory(&"x".repeat(40), 0, 30), None);
+    assert_eq!(envelope.validate(), Ok(()));
+  }
+
+  #[test]
+  fn test_validate_duplicate_message_id() {
+    let mut message = inventory("sku-2", 1, 2);
+    message.message_id = "1".to_string();
+    let mut envelope = Envelope::<InventoryMessage>::new("M_EXAMPLE_123456".to_string());
+    envelope
+      .add_message(inventory("sku-1", 1, 2), None)
+      .add_message(message, None);
+    assert_eq!(envelope.messages().len(), 2);
+    let err = envelope.validate().unwrap_err();
+    assert_eq!(
+      err.to_string(),
+      "message 1: message_id: duplicate MessageID"
+    );
+  }
+
+  #[test]
+  fn test_validate_order_fulfillment_envelope() {
+    let message = |carrier_code: &str, fulfillment_date| OrderFulfillmentMessage {
+      message_id: "".to_string(),
+      amazon_order_id: "112-3739032-8075461".to_string(),
+      fulfillment_date,
+      carrier_code: Some(carrier_code.to_string()),
+      carrier_name: String::new(),
+      shipping_method: "Standard".to_string(),
+      shipper_tracking_number: "1Z71178X0261236762".to_string(),
+      items: vec![OrderFulfillmentItem {
+        amazon_order_item_code: "56323517235162".to_string(),
+        quantity: 1,
+        transparency_code: None,
+      }],
+    };
+    let mut envelope = Envelope::<OrderFulfillmentMessage>::new("M_EXAMPLE_123456".to_string());
+    envelope
+      .add_message(message("UPS", Utc::now() - Duration::hours(1)), None)
+      .add_message(
+        message("Pony Express", Utc::now() + Duration::days(1)),
+        None,
+      );
+    let err = envelope.validate().unwrap_err();
+    assert_eq!(
+      fields(&err),
+      vec![("2", "fulfillment_date"), ("2", "carrier_code")]
+    );
+    assert!(err
+      .to_string()
+      .contains("message 2: carrier_code: unknown carrier code: 'Pony Express'"));
+
+    let mut envelope = Envelope::<OrderFulfillmentMessage>::new("M_EXAMPLE_123456".to_string());
+    let mut free_text = message("UPS", Utc::now());
+    free_text.carrier_code = None;
+    free_text.carrier_name = "Pony Express".to_string();
+    envelope.add_message(free_text, None);
+    assert_eq!(envelope.validate(), Ok(()));
+  }
+}
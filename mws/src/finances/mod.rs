@@ -0,0 +1,558 @@
+//! Amazon MWS Finances API - Version 2015-05-01
+//!
+//! [Documentation](http://docs.developer.amazonservices.com/en_US/finances/Finances_Overview.html)
+
+use chrono::{DateTime, Duration, Utc};
+use client::{Client, Method};
+use result::{MwsError, MwsResult};
+use std::thread::sleep;
+use types::GenericXmlResponse;
+mod types;
+pub use self::types::*;
+pub mod reconcile;
+
+static PATH: &'static str = "/Finances/2015-05-01";
+static VERSION: &'static str = "2015-05-01";
+
+/// The longest `PostedAfter` - `PostedBefore` range accepted by `ListFinancialEvents`
+pub const MAX_DATE_RANGE_DAYS: i64 = 180;
+
+/// How long `list_financial_events_by_date_range` waits before retrying a throttled request.
+/// `ListFinancialEvents` restores one request every two seconds.
+const RETRY_INTERVAL_SECS: u64 = 2;
+const MAX_RETRIES: usize = 5;
+
+/// Parameters for `ListFinancialEvents`
+#[allow(non_snake_case)]
+#[derive(Debug, Default, Clone, Serialize, SerializeMwsParams)]
+pub struct ListFinancialEventsParameters {
+  // Optional API Parameters
+  pub MaxResultsPerPage: Option<i32>,
+  pub AmazonOrderId: Option<String>,
+  pub FinancialEventGroupId: Option<String>,
+  pub PostedAfter: Option<DateTime<Utc>>,
+  pub PostedBefore: Option<DateTime<Utc>>,
+}
+
+#[allow(non_snake_case)]
+#[derive(Debug, Default, PartialEq, Serialize, FromXmlStream)]
+pub struct ListFinancialEventsResponse {
+  pub NextToken: Option<String>,
+  pub FinancialEvents: FinancialEvents,
+}
+
+response_envelope_type!(
+  ListFinancialEventsResponseEnvelope<ListFinancialEventsResponse>,
+  "ListFinancialEventsResponse",
+  "ListFinancialEventsResult"
+);
+
+response_envelope_type!(
+  ListFinancialEventsByNextTokenResponseEnvelope<ListFinancialEventsResponse>,
+  "ListFinancialEventsByNextTokenResponse",
+  "ListFinancialEventsByNextTokenResult"
+);
+
+/// Returns financial events for a given order, financial event group, or date range.
+///
+/// [Documentation](http://docs.developer.amazonservices.com/en_US/finances/Finances_ListFinancialEvents.html)
+#[allow(non_snake_case)]
+pub fn ListFinancialEvents(
+  client: &Client,
+  parameters: ListFinancialEventsParameters,
+) -> MwsResult<ListFinancialEventsResponse> {
+  client
+    .request_xml(
+      Method::POST,
+      PATH,
+      VERSION,
+      "ListFinancialEvents",
+      parameters,
+    )
+    .map(|e: ListFinancialEventsResponseEnvelope| e.into_inner())
+    .map_err(|err| err.into())
+}
+
+/// Returns the next page of financial events using the NextToken parameter.
+#[allow(non_snake_case)]
+pub fn ListFinancialEventsByNextToken(
+  client: &Client,
+  next_token: String,
+) -> MwsResult<ListFinancialEventsResponse> {
+  let params = vec![("NextToken".to_string(), next_token)];
+  client
+    .request_xml(
+      Method::POST,
+      PATH,
+      VERSION,
+      "ListFinancialEventsByNextToken",
+      params,
+    )
+    .map(|e: ListFinancialEventsByNextTokenResponseEnvelope| e.into_inner())
+    .map_err(|err| err.into())
+}
+
+/// Parameters for `ListFinancialEventGroups`
+#[allow(non_snake_case)]
+#[derive(Debug, Serialize, SerializeMwsParams)]
+pub struct ListFinancialEventGroupsParameters {
+  // Required API Parameters
+  pub FinancialEventGroupStartedAfter: DateTime<Utc>,
+  // Optional API Parameters
+  pub MaxResultsPerPage: Option<i32>,
+  pub FinancialEventGroupStartedBefore: Option<DateTime<Utc>>,
+}
+
+#[allow(non_snake_case)]
+#[derive(Debug, Default, PartialEq, Serialize, FromXmlStream)]
+pub struct ListFinancialEventGroupsResponse {
+  pub NextToken: Option<String>,
+  pub FinancialEventGroupList: Vec<FinancialEventGroup>,
+}
+
+response_envelope_type!(
+  ListFinancialEventGroupsResponseEnvelope<ListFinancialEventGroupsResponse>,
+  "ListFinancialEventGroupsResponse",
+  "ListFinancialEventGroupsResult"
+);
+
+response_envelope_type!(
+  ListFinancialEventGroupsByNextTokenResponseEnvelope<ListFinancialEventGroupsResponse>,
+  "ListFinancialEventGroupsByNextTokenResponse",
+  "ListFinancialEventGroupsByNextTokenResult"
+);
+
+/// Returns financial event groups for a given date range.
+///
+/// [Documentation](http://docs.developer.amazonservices.com/en_US/finances/Finances_ListFinancialEventGroups.html)
+#[allow(non_snake_case)]
+pub fn ListFinancialEventGroups(
+  client: &Client,
+  parameters: ListFinancialEventGroupsParameters,
+) -> MwsResult<ListFinancialEventGroupsResponse> {
+  client
+    .request_xml(
+      Method::POST,
+      PATH,
+      VERSION,
+      "ListFinancialEventGroups",
+      parameters,
+    )
+    .map(|e: ListFinancialEventGroupsResponseEnvelope| e.into_inner())
+    .map_err(|err| err.into())
+}
+
+/// Returns the next page of financial event groups using the NextToken parameter.
+#[allow(non_snake_case)]
+pub fn ListFinancialEventGroupsByNextToken(
+  client: &Client,
+  next_token: String,
+) -> MwsResult<ListFinancialEventGroupsResponse> {
+  let params = vec![("NextToken".to_string(), next_token)];
+  client
+    .request_xml(
+      Method::POST,
+      PATH,
+      VERSION,
+      "ListFinancialEventGroupsByNextToken",
+      params,
+    )
+    .map(|e: ListFinancialEventGroupsByNextTokenResponseEnvelope| e.into_inner())
+    .map_err(|err| err.into())
+}
+
+/// Splits `[after, before)` into consecutive ranges no longer than `max`.
+pub fn split_date_range(
+  after: DateTime<Utc>,
+  before: DateTime<Utc>,
+  max: Duration,
+) -> Vec<(DateTime<Utc>, DateTime<Utc>)> {
+  let mut ranges = vec![];
+  let mut start = after;
+  while start < before {
+    let end = ::std::cmp::min(start + max, before);
+    ranges.push((start, end));
+    start = end;
+  }
+  ranges
+}
+
+/// Calls `ListFinancialEvents` for every `MAX_DATE_RANGE_DAYS` long chunk of
+/// `PostedAfter` - `PostedBefore`, follows `NextToken`s and passes every page to `f`.
+///
+/// `PostedAfter` is required. A missing `PostedBefore` defaults to two minutes before now,
+/// the latest value the API accepts. Temporary errors (see `MwsError::should_try_again`) are
+/// retried a few times.
+pub fn list_financial_events_by_date_range<F>(
+  client: &Client,
+  parameters: ListFinancialEventsParameters,
+  mut f: F,
+) -> MwsResult<()>
+where
+  F: FnMut(ListFinancialEventsResponse) -> MwsResult<()>,
+{
+  for params in split_parameters(parameters)? {
+    let mut res = with_retry(|| ListFinancialEvents(client, params.clone()))?;
+    loop {
+      let next_token = res.NextToken.take();
+      f(res)?;
+      match next_token {
+        Some(token) => {
+          res = with_retry(|| ListFinancialEventsByNextToken(client, token.clone()))?;
+        }
+        None => break,
+      }
+    }
+  }
+
+  Ok(())
+}
+
+/// Same as `list_financial_events_by_date_range`, but passes the untyped response of every page
+/// to `f`, e.g. to keep the raw XML with `result_element.write`.
+pub fn list_financial_events_xml_by_date_range<F>(
+  client: &Client,
+  parameters: ListFinancialEventsParameters,
+  mut f: F,
+) -> MwsResult<()>
+where
+  F: FnMut(GenericXmlResponse) -> MwsResult<()>,
+{
+  for params in split_parameters(parameters)? {
+    let mut res = with_retry(|| {
+      client.request_xml_generic(
+        Method::POST,
+        PATH,
+        VERSION,
+        "ListFinancialEvents",
+        params.clone(),
+      )
+    })?;
+    loop {
+      let next_token = res.next_token().map(|token| token.to_string());
+      f(res)?;
+      match next_token {
+        Some(token) => {
+          res = with_retry(|| {
+            client.request_xml_generic(
+              Method::POST,
+              PATH,
+              VERSION,
+              "ListFinancialEventsByNextToken",
+              vec![("NextToken".to_string(), token.clone())],
+            )
+          })?;
+        }
+        None => break,
+      }
+    }
+  }
+
+  Ok(())
+}
+
+/// Splits the `PostedAfter` - `PostedBefore` range of `parameters` into `MAX_DATE_RANGE_DAYS`
+/// long chunks.
+fn split_parameters(
+  parameters: ListFinancialEventsParameters,
+) -> MwsResult<Vec<ListFinancialEventsParameters>> {
+  let after = match parameters.PostedAfter {
+    Some(after) => after,
+    None => return Err(MwsError::Msg("PostedAfter is required".to_string())),
+  };
+  let before = parameters
+    .PostedBefore
+    .unwrap_or_else(|| Utc::now() - Duration::minutes(2));
+
+  Ok(
+    split_date_range(after, before, Duration::days(MAX_DATE_RANGE_DAYS))
+      .into_iter()
+      .map(|(after, before)| ListFinancialEventsParameters {
+        PostedAfter: Some(after),
+        PostedBefore: Some(before),
+        ..parameters.clone()
+      })
+      .collect(),
+  )
+}
+
+fn with_retry<T, F>(mut f: F) -> MwsResult<T>
+where
+  F: FnMut() -> MwsResult<T>,
+{
+  let mut retries = 0;
+  loop {
+    match f() {
+      Err(ref err) if err.should_try_again() && retries < MAX_RETRIES => {
+        retries += 1;
+        sleep(::std::time::Duration::from_secs(RETRY_INTERVAL_SECS));
+      }
+      res => return res,
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use chrono::TimeZone;
+
+  #[test]
+  fn test_split_date_range() {
+    let after = Utc.ymd(2018, 1, 1).and_hms(0, 0, 0);
+    let before = Utc.ymd(2019, 1, 1).and_hms(0, 0, 0);
+    assert_eq!(
+      split_date_range(after, before, Duration::days(MAX_DATE_RANGE_DAYS)),
+      vec![
+        (after, Utc.ymd(2018, 6, 30).and_hms(0, 0, 0)),
+        (
+          Utc.ymd(2018, 6, 30).and_hms(0, 0, 0),
+          Utc.ymd(2018, 12, 27).and_hms(0, 0, 0)
+        ),
+        (Utc.ymd(2018, 12, 27).and_hms(0, 0, 0), before),
+      ]
+    );
+    assert_eq!(split_date_range(before, after, Duration::days(1)), vec![]);
+  }
+
+  #[test]
+  fn test_decode_list_financial_events_response() {
+    test_decode_envelope!(
+      ListFinancialEventsResponseEnvelope,
+      r#"
+        <ListFinancialEventsResponse xmlns="http://mws.amazonservices.com/Finances/2015-05-01">
+          <ListFinancialEventsResult>
+            <NextToken>2YgYW55IGNhcm5hbCBwbGVhcEXAMPLE</NextToken>
+            <FinancialEvents>
+              <ShipmentEventList>
+                <ShipmentEvent>
+                  <AmazonOrderId>111-3081581-6666666</AmazonOrderId>
+                  <SellerOrderId>111-3081581-6666666</SellerOrderId>
+                  <MarketplaceName>Amazon.com</MarketplaceName>
+                  <PostedDate>2019-01-02T10:12:45Z</PostedDate>
+                  <ShipmentItemList>
+                    <ShipmentItem>
+                      <SellerSKU>edifier-r1280t</SellerSKU>
+                      <OrderItemId>46510268396154</OrderItemId>
+                      <QuantityShipped>1</QuantityShipped>
+                      <ItemChargeList>
+                        <ChargeComponent>
+                          <ChargeType>Principal</ChargeType>
+                          <ChargeAmount>
+                            <CurrencyCode>USD</CurrencyCode>
+                            <CurrencyAmount>99.99</CurrencyAmount>
+                          </ChargeAmount>
+                        </ChargeComponent>
+                      </ItemChargeList>
+                      <ItemFeeList>
+                        <FeeComponent>
+                          <FeeType>Commission</FeeType>
+                          <FeeAmount>
+                            <CurrencyCode>USD</CurrencyCode>
+                            <CurrencyAmount>-15.00</CurrencyAmount>
+                          </FeeAmount>
+                        </FeeComponent>
+                      </ItemFeeList>
+                      <PromotionList>
+                        <Promotion>
+                          <PromotionType>PromotionMetaDataDefinitionValue</PromotionType>
+                          <PromotionId>PLM-1234</PromotionId>
+                          <PromotionAmount>
+                            <CurrencyCode>USD</CurrencyCode>
+                            <CurrencyAmount>-5.00</CurrencyAmount>
+                          </PromotionAmount>
+                        </Promotion>
+                      </PromotionList>
+                    </ShipmentItem>
+                  </ShipmentItemList>
+                </ShipmentEvent>
+              </ShipmentEventList>
+              <RefundEventList>
+                <ShipmentEvent>
+                  <AmazonOrderId>112-8095165-5463447</AmazonOrderId>
+                  <MarketplaceName>Amazon.com</MarketplaceName>
+                  <PostedDate>2019-01-07T09:02:44Z</PostedDate>
+                  <ShipmentItemAdjustmentList>
+                    <ShipmentItem>
+                      <SellerSKU>edifier-s1000db-fba</SellerSKU>
+                      <OrderAdjustmentItemId>52986411826454</OrderAdjustmentItemId>
+                      <QuantityShipped>1</QuantityShipped>
+                      <ItemChargeAdjustmentList>
+                        <ChargeComponent>
+                          <ChargeType>Principal</ChargeType>
+                          <ChargeAmount>
+                            <CurrencyCode>USD</CurrencyCode>
+                            <CurrencyAmount>-249.99</CurrencyAmount>
+                          </ChargeAmount>
+                        </ChargeComponent>
+                      </ItemChargeAdjustmentList>
+                    </ShipmentItem>
+                  </ShipmentItemAdjustmentList>
+                </ShipmentEvent>
+              </RefundEventList>
+              <ServiceFeeEventList>
+                <ServiceFeeEvent>
+                  <FeeDescription>Subscription Fee</FeeDescription>
+                  <FeeList>
+                    <FeeComponent>
+                      <FeeType>Subscription</FeeType>
+                      <FeeAmount>
+                        <CurrencyCode>USD</CurrencyCode>
+                        <CurrencyAmount>-39.99</CurrencyAmount>
+                      </FeeAmount>
+                    </FeeComponent>
+                  </FeeList>
+                </ServiceFeeEvent>
+              </ServiceFeeEventList>
+              <ProductAdsPaymentEventList>
+                <ProductAdsPaymentEvent>
+                  <postedDate>2019-01-03T00:00:00Z</postedDate>
+                  <transactionType>Charge</transactionType>
+                  <invoiceId>TR1T7Z7DR</invoiceId>
+                  <transactionValue>
+                    <CurrencyCode>USD</CurrencyCode>
+                    <CurrencyAmount>-25.00</CurrencyAmount>
+                  </transactionValue>
+                </ProductAdsPaymentEvent>
+              </ProductAdsPaymentEventList>
+            </FinancialEvents>
+          </ListFinancialEventsResult>
+          <ResponseMetadata>
+            <RequestId>1105b931-6f1c-4480-8e97-f3b467840a9e</RequestId>
+          </ResponseMetadata>
+        </ListFinancialEventsResponse>
+      "#,
+      ListFinancialEventsResponse {
+        NextToken: Some("2YgYW55IGNhcm5hbCBwbGVhcEXAMPLE".to_string()),
+        FinancialEvents: FinancialEvents {
+          ShipmentEventList: vec![ShipmentEvent {
+            AmazonOrderId: "111-3081581-6666666".to_string(),
+            SellerOrderId: "111-3081581-6666666".to_string(),
+            MarketplaceName: "Amazon.com".to_string(),
+            PostedDate: Some("2019-01-02T10:12:45Z".parse().unwrap()),
+            ShipmentItemList: vec![ShipmentItem {
+              SellerSKU: "edifier-r1280t".to_string(),
+              OrderItemId: "46510268396154".to_string(),
+              QuantityShipped: 1,
+              ItemChargeList: vec![ChargeComponent {
+                ChargeType: "Principal".to_string(),
+                ChargeAmount: CurrencyAmount {
+                  CurrencyCode: "USD".to_string(),
+                  CurrencyAmount: "99.99".to_string(),
+                },
+              }],
+              ItemFeeList: vec![FeeComponent {
+                FeeType: "Commission".to_string(),
+                FeeAmount: CurrencyAmount {
+                  CurrencyCode: "USD".to_string(),
+                  CurrencyAmount: "-15.00".to_string(),
+                },
+              }],
+              PromotionList: vec![Promotion {
+                PromotionType: "PromotionMetaDataDefinitionValue".to_string(),
+                PromotionId: "PLM-1234".to_string(),
+                PromotionAmount: CurrencyAmount {
+                  CurrencyCode: "USD".to_string(),
+                  CurrencyAmount: "-5.00".to_string(),
+                },
+              }],
+              ..Default::default()
+            }],
+            ..Default::default()
+          }],
+          RefundEventList: vec![ShipmentEvent {
+            AmazonOrderId: "112-8095165-5463447".to_string(),
+            MarketplaceName: "Amazon.com".to_string(),
+            PostedDate: Some("2019-01-07T09:02:44Z".parse().unwrap()),
+            ShipmentItemAdjustmentList: vec![ShipmentItem {
+              SellerSKU: "edifier-s1000db-fba".to_string(),
+              OrderAdjustmentItemId: "52986411826454".to_string(),
+              QuantityShipped: 1,
+              ItemChargeAdjustmentList: vec![ChargeComponent {
+                ChargeType: "Principal".to_string(),
+                ChargeAmount: CurrencyAmount {
+                  CurrencyCode: "USD".to_string(),
+                  CurrencyAmount: "-249.99".to_string(),
+                },
+              }],
+              ..Default::default()
+            }],
+            ..Default::default()
+          }],
+          ServiceFeeEventList: vec![ServiceFeeEvent {
+            FeeDescription: "Subscription Fee".to_string(),
+            FeeList: vec![FeeComponent {
+              FeeType: "Subscription".to_string(),
+              FeeAmount: CurrencyAmount {
+                CurrencyCode: "USD".to_string(),
+                CurrencyAmount: "-39.99".to_string(),
+              },
+            }],
+            ..Default::default()
+          }],
+          ProductAdsPaymentEventList: vec![ProductAdsPaymentEvent {
+            postedDate: Some("2019-01-03T00:00:00Z".parse().unwrap()),
+            transactionType: "Charge".to_string(),
+            invoiceId: "TR1T7Z7DR".to_string(),
+            transactionValue: Some(CurrencyAmount {
+              CurrencyCode: "USD".to_string(),
+              CurrencyAmount: "-25.00".to_string(),
+            }),
+            ..Default::default()
+          }],
+          ..Default::default()
+        },
+      }
+    );
+  }
+
+  #[test]
+  fn test_decode_list_financial_event_groups_response() {
+    test_decode_envelope!(
+      ListFinancialEventGroupsResponseEnvelope,
+      r#"
+        <ListFinancialEventGroupsResponse xmlns="http://mws.amazonservices.com/Finances/2015-05-01">
+          <ListFinancialEventGroupsResult>
+            <FinancialEventGroupList>
+              <FinancialEventGroup>
+                <FinancialEventGroupId>22YgYW55IGNhcm5hbCBwbGVhEXAMPLE</FinancialEventGroupId>
+                <ProcessingStatus>Closed</ProcessingStatus>
+                <FundTransferStatus>Successful</FundTransferStatus>
+                <OriginalTotal>
+                  <CurrencyCode>USD</CurrencyCode>
+                  <CurrencyAmount>1234.56</CurrencyAmount>
+                </OriginalTotal>
+                <FundTransferDate>2019-01-15T22:12:05Z</FundTransferDate>
+                <TraceId>128311029381HSADJEXAMPLE</TraceId>
+                <AccountTail>1212</AccountTail>
+                <FinancialEventGroupStart>2019-01-01T00:00:00Z</FinancialEventGroupStart>
+                <FinancialEventGroupEnd>2019-01-15T00:00:00Z</FinancialEventGroupEnd>
+              </FinancialEventGroup>
+            </FinancialEventGroupList>
+          </ListFinancialEventGroupsResult>
+          <ResponseMetadata>
+            <RequestId>1105b931-6f1c-4480-8e97-f3b467840a9e</RequestId>
+          </ResponseMetadata>
+        </ListFinancialEventGroupsResponse>
+      "#,
+      ListFinancialEventGroupsResponse {
+        NextToken: None,
+        FinancialEventGroupList: vec![FinancialEventGroup {
+          FinancialEventGroupId: "22YgYW55IGNhcm5hbCBwbGVhEXAMPLE".to_string(),
+          ProcessingStatus: "Closed".to_string(),
+          FundTransferStatus: "Successful".to_string(),
+          OriginalTotal: Some(CurrencyAmount {
+            CurrencyCode: "USD".to_string(),
+            CurrencyAmount: "1234.56".to_string(),
+          }),
+          FundTransferDate: Some("2019-01-15T22:12:05Z".parse().unwrap()),
+          TraceId: "128311029381HSADJEXAMPLE".to_string(),
+          AccountTail: "1212".to_string(),
+          FinancialEventGroupStart: Some("2019-01-01T00:00:00Z".parse().unwrap()),
+          FinancialEventGroupEnd: Some("2019-01-15T00:00:00Z".parse().unwrap()),
+          ..Default::default()
+        }],
+      }
+    );
+  }
+}
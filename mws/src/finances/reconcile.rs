@@ -0,0 +1,720 @@
+//! Matches shipment and refund events from `ListFinancialEvents` to the order items returned by
+//! `orders::ListOrderItems`.
+//!
+//! Items are joined on `AmazonOrderId` and `OrderItemId`. Refund items usually only carry an
+//! `OrderAdjustmentItemId`, so they fall back to `AmazonOrderId` and `SellerSKU`.
+//! Items that can't be matched unambiguously or whose currencies conflict are reported as
+//! unmatched with the reason.
+//! Amounts are summed with exact decimals, financial events with multiple pages or partial
+//! shipments can be added in any order.
+
+use super::types::{CurrencyAmount, FinancialEvents, ShipmentEvent, ShipmentItem};
+use orders::{CurrencyAmount as OrderCurrencyAmount, OrderItem};
+use result::{MwsError, MwsResult};
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+pub use rust_decimal::Decimal;
+
+/// Amount sums of one order item
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
+pub struct ItemAmounts {
+  /// `Principal` charges, or `ItemPrice` of the order item
+  pub principal: Decimal,
+  /// `Tax` charges, or `ItemTax` of the order item
+  pub tax: Decimal,
+  /// Sum of all item fees, always zero for order items
+  pub fees: Decimal,
+  /// Sum of all promotions. Negative, `PromotionDiscount` of the order item is negated to match.
+  pub promotions: Decimal,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub enum AmountComponent {
+  Principal,
+  Tax,
+  Promotions,
+}
+
+/// An amount that differs between the order item and its shipment events
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AmountMismatch {
+  pub component: AmountComponent,
+  /// Amount from the order item
+  pub ordered: Decimal,
+  /// Amount from the shipment events
+  pub shipped: Decimal,
+}
+
+/// A matched order item
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ItemReconciliation {
+  pub amazon_order_id: String,
+  pub order_item_id: String,
+  pub seller_sku: String,
+  pub currency: Option<String>,
+  pub ordered: ItemAmounts,
+  pub shipped: ItemAmounts,
+  pub refunded: ItemAmounts,
+  pub mismatches: Vec<AmountMismatch>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub enum EventKind {
+  Shipment,
+  Refund,
+}
+
+/// Why an event item was not matched
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub enum UnmatchedEventReason {
+  /// No order item with the `OrderItemId`, or with the `SellerSKU` in the order
+  NotFound,
+  /// The item has no `OrderItemId` and several order items of the order have its `SellerSKU`
+  AmbiguousSku,
+  /// The currency of the item differs from the currency of the order item
+  CurrencyMismatch,
+  /// The amounts of the item have different currencies
+  MixedCurrencies,
+}
+
+/// A shipment or refund item without a matching order item
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct UnmatchedEventItem {
+  pub kind: EventKind,
+  pub reason: UnmatchedEventReason,
+  pub amazon_order_id: String,
+  pub order_item_id: String,
+  pub seller_sku: String,
+  pub currency: Option<String>,
+  pub amounts: ItemAmounts,
+}
+
+/// Why an order item was not matched
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub enum UnmatchedOrderItemReason {
+  /// No shipment event for the item
+  NotShipped,
+  /// The amounts of the item have different currencies, its events are not matched
+  MixedCurrencies,
+}
+
+/// An order item without any shipment event, or with conflicting currencies
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct UnmatchedOrderItem {
+  pub reason: UnmatchedOrderItemReason,
+  pub amazon_order_id: String,
+  pub order_item_id: String,
+  pub seller_sku: String,
+  pub amounts: ItemAmounts,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
+pub struct ReconciliationReport {
+  /// Matched items, including those with mismatched amounts
+  pub items: Vec<ItemReconciliation>,
+  pub unmatched_events: Vec<UnmatchedEventItem>,
+  pub unmatched_order_items: Vec<UnmatchedOrderItem>,
+}
+
+impl ReconciliationReport {
+  /// Returns the matched items with at least one mismatched amount
+  pub fn mismatched_items(&self) -> Vec<&ItemReconciliation> {
+    self
+      .items
+      .iter()
+      .filter(|item| !item.mismatches.is_empty())
+      .collect()
+  }
+
+  /// Returns `true` if every item matched without amount differences
+  pub fn is_balanced(&self) -> bool {
+    self.unmatched_events.is_empty()
+      && self.unmatched_order_items.is_empty()
+      && self.items.iter().all(|item| item.mismatches.is_empty())
+  }
+}
+
+struct OrderItemEntry {
+  amazon_order_id: String,
+  order_item_id: String,
+  seller_sku: String,
+  currency: ItemCurrency,
+  ordered: ItemAmounts,
+  shipped: Option<ItemAmounts>,
+  refunded: ItemAmounts,
+}
+
+/// Collects order items and financial events, then builds a `ReconciliationReport`.
+///
+/// ```no_run
+/// # use mws::finances::reconcile::Reconciler;
+/// # fn f(events: mws::finances::FinancialEvents, items: mws::orders::ListOrderItemsResponse) -> mws::result::MwsResult<()> {
+/// let mut reconciler = Reconciler::new();
+/// reconciler.add_order_items(&items.AmazonOrderId, &items.OrderItems)?;
+/// reconciler.add_financial_events(&events)?;
+/// let report = reconciler.reconcile();
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Default)]
+pub struct Reconciler {
+  // (AmazonOrderId, OrderItemId) -> entry
+  order_items: BTreeMap<(String, String), OrderItemEntry>,
+  unmatched_events: Vec<UnmatchedEventItem>,
+}
+
+impl Reconciler {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Adds the items of an order. Adding the same item twice replaces it.
+  pub fn add_order_items(&mut self, amazon_order_id: &str, items: &[OrderItem]) -> MwsResult<()> {
+    for item in items {
+      let mut currency = ItemCurrency::default();
+      let ordered = ItemAmounts {
+        principal: order_amount(&item.ItemPrice, &mut currency)?,
+        tax: order_amount(&item.ItemTax, &mut currency)?,
+        fees: Decimal::ZERO,
+        promotions: -order_amount(&item.PromotionDiscount, &mut currency)?,
+      };
+      self.order_items.insert(
+        (amazon_order_id.to_string(), item.OrderItemId.clone()),
+        OrderItemEntry {
+          amazon_order_id: amazon_order_id.to_string(),
+          order_item_id: item.OrderItemId.clone(),
+          seller_sku: item.SellerSKU.clone(),
+          currency,
+          ordered,
+          shipped: None,
+          refunded: ItemAmounts::default(),
+        },
+      );
+    }
+    Ok(())
+  }
+
+  /// Adds the shipment and refund events. Other event types are ignored.
+  ///
+  /// Order items should be added first, events of orders added later are reported as unmatched.
+  pub fn add_financial_events(&mut self, events: &FinancialEvents) -> MwsResult<()> {
+    for event in &events.ShipmentEventList {
+      self.add_event(EventKind::Shipment, event)?;
+    }
+    for event in &events.RefundEventList {
+      self.add_event(EventKind::Refund, event)?;
+    }
+    Ok(())
+  }
+
+  fn add_event(&mut self, kind: EventKind, event: &ShipmentEvent) -> MwsResult<()> {
+    let items = match kind {
+      EventKind::Shipment => &event.ShipmentItemList,
+      EventKind::Refund => &event.ShipmentItemAdjustmentList,
+    };
+    for item in items {
+      let mut currency = ItemCurrency::default();
+      let amounts = event_item_amounts(item, &mut currency)?;
+      let matched = if currency.mixed {
+        Err(UnmatchedEventReason::MixedCurrencies)
+      } else {
+        self.match_order_item(&event.AmazonOrderId, item, &currency)
+      };
+      match matched {
+        Ok(key) => {
+          let entry = self.order_items.get_mut(&key).expect("matched order item");
+          if entry.currency.code.is_none() {
+            entry.currency = currency;
+          }
+          match kind {
+            EventKind::Shipment => {
+              let shipped = entry.shipped.get_or_insert_with(ItemAmounts::default);
+              shipped.add(&amounts);
+            }
+            EventKind::Refund => entry.refunded.add(&amounts),
+          }
+        }
+        Err(reason) => self.unmatched_events.push(UnmatchedEventItem {
+          kind,
+          reason,
+          amazon_order_id: event.AmazonOrderId.clone(),
+          order_item_id: item.OrderItemId.clone(),
+          seller_sku: item.SellerSKU.clone(),
+          currency: currency.code,
+          amounts,
+        }),
+      }
+    }
+    Ok(())
+  }
+
+  fn match_order_item(
+    &self,
+    amazon_order_id: &str,
+    item: &ShipmentItem,
+    currency: &ItemCurrency,
+  ) -> Result<(String, String), UnmatchedEventReason> {
+    let key = self.find_order_item(amazon_order_id, item)?;
+    let entry = &self.order_items[&key];
+    if entry.currency.mixed {
+      return Err(UnmatchedEventReason::CurrencyMismatch);
+    }
+    match (entry.currency.code.as_ref(), currency.code.as_ref()) {
+      (Some(a), Some(b)) if a != b => Err(UnmatchedEventReason::CurrencyMismatch),
+      _ => Ok(key),
+    }
+  }
+
+  fn find_order_item(
+    &self,
+    amazon_order_id: &str,
+    item: &ShipmentItem,
+  ) -> Result<(String, String), UnmatchedEventReason> {
+    if !item.OrderItemId.is_empty() {
+      let key = (amazon_order_id.to_string(), item.OrderItemId.clone());
+      if self.order_items.contains_key(&key) {
+        return Ok(key);
+      }
+    }
+    if item.SellerSKU.is_empty() {
+      return Err(UnmatchedEventReason::NotFound);
+    }
+    let mut matches = self.order_items.values().filter(|entry| {
+      entry.amazon_order_id == amazon_order_id && entry.seller_sku == item.SellerSKU
+    });
+    match (matches.next(), matches.next()) {
+      (Some(entry), None) => Ok((entry.amazon_order_id.clone(), entry.order_item_id.clone())),
+      (Some(_), Some(_)) => Err(UnmatchedEventReason::AmbiguousSku),
+      (None, _) => Err(UnmatchedEventReason::NotFound),
+    }
+  }
+
+  pub fn reconcile(self) -> ReconciliationReport {
+    let mut report = ReconciliationReport {
+      unmatched_events: self.unmatched_events,
+      ..Default::default()
+    };
+    for (_, entry) in self.order_items {
+      let shipped = match entry.shipped {
+        Some(shipped) if !entry.currency.mixed => shipped,
+        _ => {
+          let reason = if entry.currency.mixed {
+            UnmatchedOrderItemReason::MixedCurrencies
+          } else {
+            UnmatchedOrderItemReason::NotShipped
+          };
+          report.unmatched_order_items.push(UnmatchedOrderItem {
+            reason,
+            amazon_order_id: entry.amazon_order_id,
+            order_item_id: entry.order_item_id,
+            seller_sku: entry.seller_sku,
+            amounts: entry.ordered,
+          });
+          continue;
+        }
+      };
+      let mismatches = [
+        (AmountComponent::Principal, entry.ordered.principal, shipped.principal),
+        (AmountComponent::Tax, entry.ordered.tax, shipped.tax),
+        (AmountComponent::Promotions, entry.ordered.promotions, shipped.promotions),
+      ]
+      .iter()
+      .filter(|&&(_, ordered, shipped)| ordered != shipped)
+      .map(|&(component, ordered, shipped)| AmountMismatch {
+        component,
+        ordered,
+        shipped,
+      })
+      .collect();
+      report.items.push(ItemReconciliation {
+        amazon_order_id: entry.amazon_order_id,
+        order_item_id: entry.order_item_id,
+        seller_sku: entry.seller_sku,
+        currency: entry.currency.code,
+        ordered: entry.ordered,
+        shipped,
+        refunded: entry.refunded,
+        mismatches,
+      });
+    }
+    report
+  }
+}
+
+impl ItemAmounts {
+  fn add(&mut self, other: &ItemAmounts) {
+    self.principal += other.principal;
+    self.tax += other.tax;
+    self.fees += other.fees;
+    self.promotions += other.promotions;
+  }
+}
+
+/// Currency of the amounts of one item
+#[derive(Default)]
+struct ItemCurrency {
+  /// The first currency code
+  code: Option<String>,
+  /// `true` if the amounts have different currency codes
+  mixed: bool,
+}
+
+impl ItemCurrency {
+  fn add(&mut self, code: &str) {
+    if code.is_empty() {
+      return;
+    }
+    match self.code {
+      Some(ref current) => {
+        if current != code {
+          self.mixed = true;
+        }
+      }
+      None => self.code = Some(code.to_string()),
+    }
+  }
+}
+
+fn event_item_amounts(item: &ShipmentItem, currency: &mut ItemCurrency) -> MwsResult<ItemAmounts> {
+  let mut amounts = ItemAmounts::default();
+  let charges = item.ItemChargeList.iter().chain(item.ItemChargeAdjustmentList.iter());
+  for charge in charges {
+    match charge.ChargeType.as_ref() {
+      "Principal" => amounts.principal += event_amount(&charge.ChargeAmount, currency)?,
+      "Tax" => amounts.tax += event_amount(&charge.ChargeAmount, currency)?,
+      _ => {}
+    }
+  }
+  for fee in item.ItemFeeList.iter().chain(item.ItemFeeAdjustmentList.iter()) {
+    amounts.fees += event_amount(&fee.FeeAmount, currency)?;
+  }
+  for promotion in item.PromotionList.iter().chain(item.PromotionAdjustmentList.iter()) {
+    amounts.promotions += event_amount(&promotion.PromotionAmount, currency)?;
+  }
+  Ok(amounts)
+}
+
+fn event_amount(amount: &CurrencyAmount, currency: &mut ItemCurrency) -> MwsResult<Decimal> {
+  currency.add(&amount.CurrencyCode);
+  parse_decimal(&amount.CurrencyAmount)
+}
+
+fn order_amount(
+  amount: &Option<OrderCurrencyAmount>,
+  currency: &mut ItemCurrency,
+) -> MwsResult<Decimal> {
+  match *amount {
+    Some(ref amount) => {
+      currency.add(&amount.CurrencyCode);
+      parse_decimal(&amount.Amount)
+    }
+    None => Ok(Decimal::ZERO),
+  }
+}
+
+fn parse_decimal(value: &str) -> MwsResult<Decimal> {
+  if value.is_empty() {
+    return Ok(Decimal::ZERO);
+  }
+  Decimal::from_str(value).map_err(|err| MwsError::ParseString {
+    what: value.to_string(),
+    message: err.to_string(),
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::super::types::{ChargeComponent, FeeComponent, Promotion};
+  use super::*;
+
+  fn amount(value: &str) -> CurrencyAmount {
+    CurrencyAmount {
+      CurrencyCode: "USD".to_string(),
+      CurrencyAmount: value.to_string(),
+    }
+  }
+
+  fn order_money(value: &str) -> Option<OrderCurrencyAmount> {
+    Some(OrderCurrencyAmount {
+      CurrencyCode: "USD".to_string(),
+      Amount: value.to_string(),
+    })
+  }
+
+  fn charge(charge_type: &str, value: &str) -> ChargeComponent {
+    ChargeComponent {
+      ChargeType: charge_type.to_string(),
+      ChargeAmount: amount(value),
+    }
+  }
+
+  fn d(value: &str) -> Decimal {
+    value.parse().unwrap()
+  }
+
+  #[test]
+  fn test_reconcile() {
+    let mut reconciler = Reconciler::new();
+    reconciler
+      .add_order_items(
+        "111-3081581-6666666",
+        &[
+          OrderItem {
+            OrderItemId: "1".to_string(),
+            SellerSKU: "sku-1".to_string(),
+            ItemPrice: order_money("99.99"),
+            ItemTax: order_money("6.50"),
+            PromotionDiscount: order_money("5.00"),
+            ..Default::default()
+          },
+          OrderItem {
+            OrderItemId: "2".to_string(),
+            SellerSKU: "sku-2".to_string(),
+            ItemPrice: order_money("0.30"),
+            ..Default::default()
+          },
+          OrderItem {
+            OrderItemId: "3".to_string(),
+            SellerSKU: "sku-3".to_string(),
+            ItemPrice: order_money("10.00"),
+            ..Default::default()
+          },
+        ],
+      )
+      .unwrap();
+
+    let events = FinancialEvents {
+      ShipmentEventList: vec![
+        ShipmentEvent {
+          AmazonOrderId: "111-3081581-6666666".to_string(),
+          ShipmentItemList: vec![
+            ShipmentItem {
+              OrderItemId: "1".to_string(),
+              SellerSKU: "sku-1".to_string(),
+              ItemChargeList: vec![charge("Principal", "99.99"), charge("Tax", "6.50")],
+              ItemFeeList: vec![
+                FeeComponent {
+                  FeeType: "Commission".to_string(),
+                  FeeAmount: amount("-15.00"),
+                },
+                FeeComponent {
+                  FeeType: "FBAPerUnitFulfillmentFee".to_string(),
+                  FeeAmount: amount("-3.19"),
+                },
+              ],
+              PromotionList: vec![Promotion {
+                PromotionAmount: amount("-5.00"),
+                ..Default::default()
+              }],
+              ..Default::default()
+            },
+            // partial shipment, 0.10 + 0.10 != 0.30
+            ShipmentItem {
+              OrderItemId: "2".to_string(),
+              ItemChargeList: vec![charge("Principal", "0.10")],
+              ..Default::default()
+            },
+          ],
+          ..Default::default()
+        },
+        ShipmentEvent {
+          AmazonOrderId: "111-3081581-6666666".to_string(),
+          ShipmentItemList: vec![ShipmentItem {
+            OrderItemId: "2".to_string(),
+            ItemChargeList: vec![charge("Principal", "0.10")],
+            ..Default::default()
+          }],
+          ..Default::default()
+        },
+        ShipmentEvent {
+          AmazonOrderId: "112-0000000-0000000".to_string(),
+          ShipmentItemList: vec![ShipmentItem {
+            OrderItemId: "9".to_string(),
+            SellerSKU: "sku-9".to_string(),
+            ItemChargeList: vec![charge("Principal", "1.00")],
+            ..Default::default()
+          }],
+          ..Default::default()
+        },
+      ],
+      RefundEventList: vec![ShipmentEvent {
+        AmazonOrderId: "111-3081581-6666666".to_string(),
+        ShipmentItemAdjustmentList: vec![ShipmentItem {
+          OrderAdjustmentItemId: "52986411826454".to_string(),
+          SellerSKU: "sku-1".to_string(),
+          ItemChargeAdjustmentList: vec![charge("Principal", "-99.99")],
+          ..Default::default()
+        }],
+        ..Default::default()
+      }],
+      ..Default::default()
+    };
+    reconciler.add_financial_events(&events).unwrap();
+
+    let report = reconciler.reconcile();
+    assert!(!report.is_balanced());
+
+    assert_eq!(report.items.len(), 2);
+    let item = &report.items[0];
+    assert_eq!(item.order_item_id, "1");
+    assert_eq!(item.currency, Some("USD".to_string()));
+    assert_eq!(
+      item.shipped,
+      ItemAmounts {
+        principal: d("99.99"),
+        tax: d("6.50"),
+        fees: d("-18.19"),
+        promotions: d("-5.00"),
+      }
+    );
+    assert_eq!(item.refunded.principal, d("-99.99"));
+    assert_eq!(item.mismatches, vec![]);
+
+    let item = &report.items[1];
+    assert_eq!(item.order_item_id, "2");
+    assert_eq!(
+      item.mismatches,
+      vec![AmountMismatch {
+        component: AmountComponent::Principal,
+        ordered: d("0.30"),
+        shipped: d("0.20"),
+      }]
+    );
+    assert_eq!(report.mismatched_items().len(), 1);
+
+    assert_eq!(report.unmatched_events.len(), 1);
+    assert_eq!(report.unmatched_events[0].kind, EventKind::Shipment);
+    assert_eq!(report.unmatched_events[0].reason, UnmatchedEventReason::NotFound);
+    assert_eq!(report.unmatched_events[0].order_item_id, "9");
+    assert_eq!(report.unmatched_events[0].amounts.principal, d("1.00"));
+
+    assert_eq!(report.unmatched_order_items.len(), 1);
+    assert_eq!(report.unmatched_order_items[0].order_item_id, "3");
+    assert_eq!(
+      report.unmatched_order_items[0].reason,
+      UnmatchedOrderItemReason::NotShipped
+    );
+  }
+
+  #[test]
+  fn test_reconcile_conflicts() {
+    let mut reconciler = Reconciler::new();
+    reconciler
+      .add_order_items(
+        "111-3081581-6666666",
+        &[
+          OrderItem {
+            OrderItemId: "1".to_string(),
+            SellerSKU: "sku-1".to_string(),
+            ItemPrice: order_money("10.00"),
+            ..Default::default()
+          },
+          OrderItem {
+            OrderItemId: "2".to_string(),
+            SellerSKU: "sku-1".to_string(),
+            ItemPrice: order_money("10.00"),
+            ..Default::default()
+          },
+          OrderItem {
+            OrderItemId: "3".to_string(),
+            SellerSKU: "sku-3".to_string(),
+            ItemPrice: order_money("10.00"),
+            ItemTax: Some(OrderCurrencyAmount {
+              CurrencyCode: "CAD".to_string(),
+              Amount: "1.00".to_string(),
+            }),
+            ..Default::default()
+          },
+        ],
+      )
+      .unwrap();
+
+    let mut eur_item = ShipmentItem {
+      OrderItemId: "1".to_string(),
+      ItemChargeList: vec![charge("Principal", "10.00")],
+      ..Default::default()
+    };
+    eur_item.ItemChargeList[0].ChargeAmount.CurrencyCode = "EUR".to_string();
+    let events = FinancialEvents {
+      ShipmentEventList: vec![ShipmentEvent {
+        AmazonOrderId: "111-3081581-6666666".to_string(),
+        ShipmentItemList: vec![
+          eur_item,
+          ShipmentItem {
+            OrderItemId: "2".to_string(),
+            ItemChargeList: vec![charge("Principal", "10.00")],
+            ..Default::default()
+          },
+          ShipmentItem {
+            OrderItemId: "3".to_string(),
+            ItemChargeList: vec![charge("Principal", "10.00")],
+            ..Default::default()
+          },
+        ],
+        ..Default::default()
+      }],
+      RefundEventList: vec![ShipmentEvent {
+        AmazonOrderId: "111-3081581-6666666".to_string(),
+        ShipmentItemAdjustmentList: vec![ShipmentItem {
+          SellerSKU: "sku-1".to_string(),
+          ItemChargeAdjustmentList: vec![charge("Principal", "-10.00")],
+          ..Default::default()
+        }],
+        ..Default::default()
+      }],
+      ..Default::default()
+    };
+    reconciler.add_financial_events(&events).unwrap();
+
+    let report = reconciler.reconcile();
+    let reasons: Vec<_> = report
+      .unmatched_events
+      .iter()
+      .map(|item| (item.kind, item.reason))
+      .collect();
+    assert_eq!(
+      reasons,
+      vec![
+        (EventKind::Shipment, UnmatchedEventReason::CurrencyMismatch),
+        (EventKind::Shipment, UnmatchedEventReason::CurrencyMismatch),
+        (EventKind::Refund, UnmatchedEventReason::AmbiguousSku),
+      ]
+    );
+    assert_eq!(report.unmatched_events[0].currency, Some("EUR".to_string()));
+
+    assert_eq!(report.items.len(), 1);
+    assert_eq!(report.items[0].order_item_id, "2");
+    assert_eq!(report.items[0].refunded, ItemAmounts::default());
+
+    let reasons: Vec<_> = report
+      .unmatched_order_items
+      .iter()
+      .map(|item| (item.order_item_id.as_str(), item.reason))
+      .collect();
+    assert_eq!(
+      reasons,
+      vec![
+        ("1", UnmatchedOrderItemReason::NotShipped),
+        ("3", UnmatchedOrderItemReason::MixedCurrencies),
+      ]
+    );
+  }
+
+  #[test]
+  fn test_invalid_amount() {
+    let mut reconciler = Reconciler::new();
+    let res = reconciler.add_order_items(
+      "111-3081581-6666666",
+      &[OrderItem {
+        OrderItemId: "1".to_string(),
+        ItemPrice: order_money("9,99"),
+        ..Default::default()
+      }],
+    );
+    match res {
+      Err(MwsError::ParseString { what, .. }) => assert_eq!(what, "9,99"),
+      other => panic!("unexpected result: {:?}", other),
+    }
+  }
+}
@@ -0,0 +1,268 @@
+use chrono::{DateTime, Utc};
+
+/// A currency type and amount, the amount is kept as returned to avoid rounding errors.
+#[allow(non_snake_case)]
+#[derive(Debug, Default, Clone, PartialEq, Serialize, FromXmlStream)]
+pub struct CurrencyAmount {
+  pub CurrencyCode: String,
+  pub CurrencyAmount: String,
+}
+
+/// Information about a financial event group.
+#[allow(non_snake_case)]
+#[derive(Debug, Default, PartialEq, Serialize, FromXmlStream)]
+pub struct FinancialEventGroup {
+  pub FinancialEventGroupId: String,
+  /// `Open` or `Closed`
+  pub ProcessingStatus: String,
+  pub FundTransferStatus: String,
+  pub OriginalTotal: Option<CurrencyAmount>,
+  pub ConvertedTotal: Option<CurrencyAmount>,
+  pub FundTransferDate: Option<DateTime<Utc>>,
+  pub TraceId: String,
+  pub AccountTail: String,
+  pub BeginningBalance: Option<CurrencyAmount>,
+  pub FinancialEventGroupStart: Option<DateTime<Utc>>,
+  pub FinancialEventGroupEnd: Option<DateTime<Utc>>,
+}
+
+/// A charge on a shipment item, e.g. `Principal`, `Tax` or `ShippingCharge`
+#[allow(non_snake_case)]
+#[derive(Debug, Default, PartialEq, Serialize, FromXmlStream)]
+pub struct ChargeComponent {
+  pub ChargeType: String,
+  pub ChargeAmount: CurrencyAmount,
+}
+
+/// A fee, e.g. `Commission` or `FBAPerUnitFulfillmentFee`
+#[allow(non_snake_case)]
+#[derive(Debug, Default, PartialEq, Serialize, FromXmlStream)]
+pub struct FeeComponent {
+  pub FeeType: String,
+  pub FeeAmount: CurrencyAmount,
+}
+
+#[allow(non_snake_case)]
+#[derive(Debug, Default, PartialEq, Serialize, FromXmlStream)]
+pub struct Promotion {
+  pub PromotionType: String,
+  pub PromotionId: String,
+  pub PromotionAmount: CurrencyAmount,
+}
+
+/// Taxes withheld by Amazon under a tax collection model, e.g. `MarketplaceFacilitator`
+#[allow(non_snake_case)]
+#[derive(Debug, Default, PartialEq, Serialize, FromXmlStream)]
+pub struct TaxWithheldComponent {
+  pub TaxCollectionModel: String,
+  pub TaxesWithheld: Vec<ChargeComponent>,
+}
+
+#[allow(non_snake_case)]
+#[derive(Debug, Default, PartialEq, Serialize, FromXmlStream)]
+pub struct DirectPayment {
+  pub DirectPaymentType: String,
+  pub DirectPaymentAmount: CurrencyAmount,
+}
+
+#[allow(non_snake_case)]
+#[derive(Debug, Default, PartialEq, Serialize, FromXmlStream)]
+pub struct ShipmentItem {
+  pub SellerSKU: String,
+  pub OrderItemId: String,
+  pub OrderAdjustmentItemId: String,
+  pub QuantityShipped: i32,
+  pub ItemChargeList: Vec<ChargeComponent>,
+  pub ItemChargeAdjustmentList: Vec<ChargeComponent>,
+  pub ItemFeeList: Vec<FeeComponent>,
+  pub ItemFeeAdjustmentList: Vec<FeeComponent>,
+  pub ItemTaxWithheldList: Vec<TaxWithheldComponent>,
+  pub PromotionList: Vec<Promotion>,
+  pub PromotionAdjustmentList: Vec<Promotion>,
+  pub CostOfPointsGranted: Option<CurrencyAmount>,
+  pub CostOfPointsReturned: Option<CurrencyAmount>,
+}
+
+/// A shipment, refund, guarantee claim or chargeback event
+#[allow(non_snake_case)]
+#[derive(Debug, Default, PartialEq, Serialize, FromXmlStream)]
+pub struct ShipmentEvent {
+  pub AmazonOrderId: String,
+  pub SellerOrderId: String,
+  pub MarketplaceName: String,
+  pub OrderChargeList: Vec<ChargeComponent>,
+  pub OrderChargeAdjustmentList: Vec<ChargeComponent>,
+  pub ShipmentFeeList: Vec<FeeComponent>,
+  pub ShipmentFeeAdjustmentList: Vec<FeeComponent>,
+  pub OrderFeeList: Vec<FeeComponent>,
+  pub OrderFeeAdjustmentList: Vec<FeeComponent>,
+  pub DirectPaymentList: Vec<DirectPayment>,
+  pub PostedDate: Option<DateTime<Utc>>,
+  pub ShipmentItemList: Vec<ShipmentItem>,
+  pub ShipmentItemAdjustmentList: Vec<ShipmentItem>,
+}
+
+/// A service fee not tied to a shipment, e.g. a subscription or FBA inbound fee
+#[allow(non_snake_case)]
+#[derive(Debug, Default, PartialEq, Serialize, FromXmlStream)]
+pub struct ServiceFeeEvent {
+  pub AmazonOrderId: String,
+  pub FeeReason: String,
+  pub FeeList: Vec<FeeComponent>,
+  pub SellerSKU: String,
+  pub FnSKU: String,
+  pub FeeDescription: String,
+  pub ASIN: String,
+}
+
+#[allow(non_snake_case)]
+#[derive(Debug, Default, PartialEq, Serialize, FromXmlStream)]
+pub struct AdjustmentItem {
+  pub Quantity: String,
+  pub PerUnitAmount: Option<CurrencyAmount>,
+  pub TotalAmount: Option<CurrencyAmount>,
+  pub SellerSKU: String,
+  pub FnSKU: String,
+  pub ProductDescription: String,
+  pub ASIN: String,
+}
+
+/// An adjustment, e.g. an FBA inventory reimbursement
+#[allow(non_snake_case)]
+#[derive(Debug, Default, PartialEq, Serialize, FromXmlStream)]
+pub struct AdjustmentEvent {
+  pub AdjustmentType: String,
+  pub PostedDate: Option<DateTime<Utc>>,
+  pub AdjustmentAmount: CurrencyAmount,
+  pub AdjustmentItemList: Vec<AdjustmentItem>,
+}
+
+/// A retrocharge or retrocharge reversal
+#[allow(non_snake_case)]
+#[derive(Debug, Default, PartialEq, Serialize, FromXmlStream)]
+pub struct RetrochargeEvent {
+  pub RetrochargeEventType: String,
+  pub AmazonOrderId: String,
+  pub PostedDate: Option<DateTime<Utc>>,
+  pub BaseTax: Option<CurrencyAmount>,
+  pub ShippingTax: Option<CurrencyAmount>,
+  pub MarketplaceName: String,
+  pub RetrochargeTaxWithheldList: Vec<TaxWithheldComponent>,
+}
+
+#[allow(non_snake_case)]
+#[derive(Debug, Default, PartialEq, Serialize, FromXmlStream)]
+pub struct CouponPaymentEvent {
+  pub PostedDate: Option<DateTime<Utc>>,
+  pub CouponId: String,
+  pub SellerCouponDescription: String,
+  pub ClipOrRedemptionCount: i64,
+  pub PaymentEventId: String,
+  pub FeeComponent: Option<FeeComponent>,
+  pub ChargeComponent: Option<ChargeComponent>,
+  pub TotalAmount: Option<CurrencyAmount>,
+}
+
+#[allow(non_snake_case)]
+#[derive(Debug, Default, PartialEq, Serialize, FromXmlStream)]
+pub struct SAFETReimbursementItem {
+  pub ItemChargeList: Vec<ChargeComponent>,
+  pub ProductDescription: String,
+  pub Quantity: String,
+}
+
+/// A reimbursement of a SAFE-T claim
+#[allow(non_snake_case)]
+#[derive(Debug, Default, PartialEq, Serialize, FromXmlStream)]
+pub struct SAFETReimbursementEvent {
+  pub PostedDate: Option<DateTime<Utc>>,
+  pub SAFETClaimId: String,
+  pub ReimbursedAmount: Option<CurrencyAmount>,
+  pub ReasonCode: String,
+  pub SAFETReimbursementItemList: Vec<SAFETReimbursementItem>,
+}
+
+/// Sponsored Products payment, element names are in camel case
+#[allow(non_snake_case)]
+#[derive(Debug, Default, PartialEq, Serialize, FromXmlStream)]
+pub struct ProductAdsPaymentEvent {
+  pub postedDate: Option<DateTime<Utc>>,
+  pub transactionType: String,
+  pub invoiceId: String,
+  pub baseValue: Option<CurrencyAmount>,
+  pub taxValue: Option<CurrencyAmount>,
+  pub transactionValue: Option<CurrencyAmount>,
+}
+
+/// Lightning Deal fee, element names are in camel case
+#[allow(non_snake_case)]
+#[derive(Debug, Default, PartialEq, Serialize, FromXmlStream)]
+pub struct SellerDealPaymentEvent {
+  pub postedDate: Option<DateTime<Utc>>,
+  pub dealId: String,
+  pub dealDescription: String,
+  pub eventType: String,
+  pub feeType: String,
+  pub feeAmount: Option<CurrencyAmount>,
+  pub taxAmount: Option<CurrencyAmount>,
+  pub totalAmount: Option<CurrencyAmount>,
+}
+
+#[allow(non_snake_case)]
+#[derive(Debug, Default, PartialEq, Serialize, FromXmlStream)]
+pub struct FBALiquidationEvent {
+  pub PostedDate: Option<DateTime<Utc>>,
+  pub OriginalRemovalOrderId: String,
+  pub LiquidationProceedsAmount: Option<CurrencyAmount>,
+  pub LiquidationFeeAmount: Option<CurrencyAmount>,
+}
+
+/// All the financial events returned by one page of `ListFinancialEvents`
+///
+/// [Documentation](http://docs.developer.amazonservices.com/en_US/finances/Finances_Datatypes.html#FinancialEvents)
+#[allow(non_snake_case)]
+#[derive(Debug, Default, PartialEq, Serialize, FromXmlStream)]
+pub struct FinancialEvents {
+  pub ShipmentEventList: Vec<ShipmentEvent>,
+  pub RefundEventList: Vec<ShipmentEvent>,
+  pub GuaranteeClaimEventList: Vec<ShipmentEvent>,
+  pub ChargebackEventList: Vec<ShipmentEvent>,
+  pub ServiceFeeEventList: Vec<ServiceFeeEvent>,
+  pub AdjustmentEventList: Vec<AdjustmentEvent>,
+  pub RetrochargeEventList: Vec<RetrochargeEvent>,
+  pub CouponPaymentEventList: Vec<CouponPaymentEvent>,
+  pub SAFETReimbursementEventList: Vec<SAFETReimbursementEvent>,
+  pub ProductAdsPaymentEventList: Vec<ProductAdsPaymentEvent>,
+  pub SellerDealPaymentEventList: Vec<SellerDealPaymentEvent>,
+  pub FBALiquidationEventList: Vec<FBALiquidationEvent>,
+}
+
+impl FinancialEvents {
+  /// Moves the events of `other` to the end of the lists of `self`
+  pub fn append(&mut self, other: &mut FinancialEvents) {
+    self.ShipmentEventList.append(&mut other.ShipmentEventList);
+    self.RefundEventList.append(&mut other.RefundEventList);
+    self
+      .GuaranteeClaimEventList
+      .append(&mut other.GuaranteeClaimEventList);
+    self.ChargebackEventList.append(&mut other.ChargebackEventList);
+    self.ServiceFeeEventList.append(&mut other.ServiceFeeEventList);
+    self.AdjustmentEventList.append(&mut other.AdjustmentEventList);
+    self.RetrochargeEventList.append(&mut other.RetrochargeEventList);
+    self
+      .CouponPaymentEventList
+      .append(&mut other.CouponPaymentEventList);
+    self
+      .SAFETReimbursementEventList
+      .append(&mut other.SAFETReimbursementEventList);
+    self
+      .ProductAdsPaymentEventList
+      .append(&mut other.ProductAdsPaymentEventList);
+    self
+      .SellerDealPaymentEventList
+      .append(&mut other.SellerDealPaymentEventList);
+    self
+      .FBALiquidationEventList
+      .append(&mut other.FBALiquidationEventList);
+  }
+}
@@ -7,21 +7,30 @@ extern crate base64;
 extern crate chrono;
 extern crate sha2;
 extern crate hmac;
+extern crate md5;
 extern crate failure;
 extern crate reqwest;
+extern crate rust_decimal;
 extern crate url;
 extern crate xml;
 #[macro_use]
 extern crate failure_derive;
 extern crate csv;
+extern crate encoding_rs;
+extern crate encoding_rs_io;
 extern crate serde;
 #[macro_use]
 extern crate serde_derive;
+#[cfg_attr(test, macro_use)]
+extern crate serde_json;
 #[macro_use]
 extern crate mws_derive;
 extern crate xmltree;
 
-pub use mws_derive::FromTdffRow;
+pub use mws_derive::{FromTdffRow, ToTdffRow};
+
+// Code generated by `#[derive(FromTdffRow)]` and `#[derive(ToTdffRow)]` refers to `::mws`
+extern crate self as mws;
 
 #[cfg(test)]
 extern crate dotenv;
@@ -30,6 +39,8 @@ extern crate dotenv;
 extern crate pretty_assertions;
 #[macro_use]
 extern crate lazy_static;
+#[macro_use]
+extern crate log;
 
 pub mod result;
 #[macro_use]
@@ -41,9 +52,11 @@ mod macros;
 pub mod tdff;
 pub mod client;
 pub mod constants;
+pub mod encoding;
 mod sign;
 
 pub mod feeds;
+pub mod finances;
 pub mod fulfillment_inbound_shipment;
 pub mod fulfillment_inventory;
 pub mod fulfillment_outbound;
@@ -0,0 +1,382 @@
+//! Flattens `Order` and `OrderItem` into a stable column schema and writes CSV or
+//! newline-delimited JSON.
+//!
+//! Every row starts with the [`ORDER_COLUMNS`](constant.ORDER_COLUMNS.html). In
+//! `OrderExportGranularity::PerItem` mode they are followed by the
+//! [`ITEM_COLUMNS`](constant.ITEM_COLUMNS.html); in `OrderExportGranularity::PerOrder`
+//! mode by the [`ORDER_SUMMARY_COLUMNS`](constant.ORDER_SUMMARY_COLUMNS.html).
+//!
+//! All values are written as strings, amounts are copied verbatim from the API response and
+//! dates are formatted as ISO 8601 (`2018-12-16T18:53:45Z`). Missing values are empty cells in
+//! CSV and `null` in NDJSON.
+
+use super::types::{CurrencyAmount, Order, OrderItem};
+use csv::Writer as CsvWriter;
+use result::MwsResult;
+use serde_json::{Map, Value};
+use std::io::Write;
+use types::ToIso8601;
+
+/// Order level columns, present in every row.
+pub const ORDER_COLUMNS: &[&str] = &[
+  "amazon_order_id",
+  "seller_order_id",
+  "purchase_date",
+  "last_update_date",
+  "order_status",
+  "fulfillment_channel",
+  "sales_channel",
+  "marketplace_id",
+  "order_type",
+  "ship_service_level",
+  "shipment_service_level_category",
+  "is_business_order",
+  "is_prime",
+  "is_premium_order",
+  "is_replacement_order",
+  "buyer_name",
+  "buyer_email",
+  "ship_city",
+  "ship_state_or_region",
+  "ship_postal_code",
+  "ship_country_code",
+  "order_total_currency",
+  "order_total_amount",
+  "number_of_items_shipped",
+  "number_of_items_unshipped",
+];
+
+/// Item level columns, present in `PerItem` rows.
+///
+/// Amount columns are in `currency`, which is taken from the first amount present on the item.
+pub const ITEM_COLUMNS: &[&str] = &[
+  "order_item_id",
+  "asin",
+  "seller_sku",
+  "title",
+  "quantity_ordered",
+  "quantity_shipped",
+  "currency",
+  "item_price",
+  "item_tax",
+  "shipping_price",
+  "shipping_tax",
+  "shipping_discount",
+  "gift_wrap_price",
+  "gift_wrap_tax",
+  "promotion_discount",
+];
+
+/// Item summary columns, present in `PerOrder` rows.
+///
+/// `seller_skus` is a `;` separated list in item order.
+pub const ORDER_SUMMARY_COLUMNS: &[&str] = &[
+  "item_count",
+  "quantity_ordered",
+  "quantity_shipped",
+  "seller_skus",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OrderExportFormat {
+  /// Comma-separated values with a header row
+  Csv,
+  /// One JSON object per line, keyed by column name
+  Ndjson,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OrderExportGranularity {
+  /// One row per order, items are summarized
+  PerOrder,
+  /// One row per order item. An order without items produces a single row with empty item columns.
+  PerItem,
+}
+
+enum Sink<W: Write> {
+  Csv(Box<CsvWriter<W>>),
+  Ndjson(W),
+}
+
+/// Writes orders to any `Write`
+pub struct OrderExporter<W: Write> {
+  sink: Sink<W>,
+  granularity: OrderExportGranularity,
+  header_written: bool,
+}
+
+impl<W: Write> OrderExporter<W> {
+  pub fn new(out: W, format: OrderExportFormat, granularity: OrderExportGranularity) -> Self {
+    OrderExporter {
+      sink: match format {
+        OrderExportFormat::Csv => Sink::Csv(Box::new(CsvWriter::from_writer(out))),
+        OrderExportFormat::Ndjson => Sink::Ndjson(out),
+      },
+      granularity,
+      header_written: false,
+    }
+  }
+
+  /// Column names of the rows produced by this exporter, in order.
+  pub fn columns(&self) -> Vec<&'static str> {
+    let extra = match self.granularity {
+      OrderExportGranularity::PerOrder => ORDER_SUMMARY_COLUMNS,
+      OrderExportGranularity::PerItem => ITEM_COLUMNS,
+    };
+    ORDER_COLUMNS.iter().chain(extra.iter()).cloned().collect()
+  }
+
+  /// Writes an order and its items.
+  pub fn write_order(&mut self, order: &Order, items: &[OrderItem]) -> MwsResult<()> {
+    let order_values = order_values(order);
+    match self.granularity {
+      OrderExportGranularity::PerOrder => {
+        let mut row = order_values;
+        row.extend(order_summary_values(items));
+        self.write_row(row)
+      }
+      OrderExportGranularity::PerItem => {
+        if items.is_empty() {
+          let mut row = order_values;
+          row.extend(ITEM_COLUMNS.iter().map(|_| None));
+          return self.write_row(row);
+        }
+
+        for item in items {
+          let mut row = order_values.clone();
+          row.extend(item_values(item));
+          self.write_row(row)?;
+        }
+        Ok(())
+      }
+    }
+  }
+
+  /// Flushes buffered rows and returns the underlying writer.
+  pub fn finish(self) -> MwsResult<W> {
+    match self.sink {
+      Sink::Csv(w) => w.into_inner().map_err(|err| err.into_error().into()),
+      Sink::Ndjson(mut w) => {
+        w.flush()?;
+        Ok(w)
+      }
+    }
+  }
+
+  fn write_row(&mut self, row: Vec<Option<String>>) -> MwsResult<()> {
+    let columns = self.columns();
+    match self.sink {
+      Sink::Csv(ref mut w) => {
+        if !self.header_written {
+          w.write_record(&columns)?;
+          self.header_written = true;
+        }
+        w.write_record(row.iter().map(|v| v.as_ref().map(AsRef::as_ref).unwrap_or("")))?;
+      }
+      Sink::Ndjson(ref mut w) => {
+        let object: Map<String, Value> = columns
+          .into_iter()
+          .zip(row)
+          .map(|(k, v)| (k.to_string(), v.map(Value::String).unwrap_or(Value::Null)))
+          .collect();
+        ::serde_json::to_writer(&mut *w, &object)?;
+        w.write_all(b"\n")?;
+      }
+    }
+    Ok(())
+  }
+}
+
+fn non_empty(v: &str) -> Option<String> {
+  if v.is_empty() {
+    None
+  } else {
+    Some(v.to_string())
+  }
+}
+
+fn order_values(order: &Order) -> Vec<Option<String>> {
+  let address = order.ShippingAddress.as_ref();
+  let total = order.OrderTotal.as_ref();
+  vec![
+    non_empty(&order.AmazonOrderId),
+    non_empty(&order.SellerOrderId),
+    order.PurchaseDate.as_ref().map(ToIso8601::to_iso8601),
+    order.LastUpdateDate.as_ref().map(ToIso8601::to_iso8601),
+    non_empty(&order.OrderStatus),
+    non_empty(&order.FulfillmentChannel),
+    non_empty(&order.SalesChannel),
+    non_empty(&order.MarketplaceId),
+    non_empty(&order.OrderType),
+    non_empty(&order.ShipServiceLevel),
+    non_empty(&order.ShipmentServiceLevelCategory),
+    Some(order.IsBusinessOrder.to_string()),
+    Some(order.IsPrime.to_string()),
+    Some(order.IsPremiumOrder.to_string()),
+    Some(order.IsReplacementOrder.to_string()),
+    non_empty(&order.BuyerName),
+    non_empty(&order.BuyerEmail),
+    address.and_then(|a| non_empty(&a.City)),
+    address.and_then(|a| non_empty(&a.StateOrRegion)),
+    address.and_then(|a| non_empty(&a.PostalCode)),
+    address.and_then(|a| non_empty(&a.CountryCode)),
+    total.and_then(|v| non_empty(&v.CurrencyCode)),
+    total.and_then(|v| non_empty(&v.Amount)),
+    Some(order.NumberOfItemsShipped.to_string()),
+    Some(order.NumberOfItemsUnshipped.to_string()),
+  ]
+}
+
+fn item_values(item: &OrderItem) -> Vec<Option<String>> {
+  let amounts = [
+    &item.ItemPrice,
+    &item.ItemTax,
+    &item.ShippingPrice,
+    &item.ShippingTax,
+    &item.ShippingDiscount,
+    &item.GiftWrapPrice,
+    &item.GiftWrapTax,
+    &item.PromotionDiscount,
+  ];
+  let currency = amounts
+    .iter()
+    .filter_map(|v| v.as_ref().and_then(|v| non_empty(&v.CurrencyCode)))
+    .next();
+  let amount = |v: &Option<CurrencyAmount>| v.as_ref().and_then(|v| non_empty(&v.Amount));
+
+  let mut values = vec![
+    non_empty(&item.OrderItemId),
+    non_empty(&item.ASIN),
+    non_empty(&item.SellerSKU),
+    non_empty(&item.Title),
+    Some(item.QuantityOrdered.to_string()),
+    Some(item.QuantityShipped.to_string()),
+    currency,
+  ];
+  values.extend(amounts.iter().map(|v| amount(v)));
+  values
+}
+
+fn order_summary_values(items: &[OrderItem]) -> Vec<Option<String>> {
+  let skus: Vec<&str> = items
+    .iter()
+    .map(|item| item.SellerSKU.as_ref())
+    .filter(|sku: &&str| !sku.is_empty())
+    .collect();
+  vec![
+    Some(items.len().to_string()),
+    Some(
+      items
+        .iter()
+        .map(|item| item.QuantityOrdered)
+        .sum::<i32>()
+        .to_string(),
+    ),
+    Some(
+      items
+        .iter()
+        .map(|item| item.QuantityShipped)
+        .sum::<i32>()
+        .to_string(),
+    ),
+    non_empty(&skus.join(";")),
+  ]
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use orders::types::{FulfillmentChannel, OrderStatus};
+
+  fn get_order() -> (Order, Vec<OrderItem>) {
+    let order = Order {
+      AmazonOrderId: "111-3081581-6666666".to_string(),
+      PurchaseDate: Some("2018-12-16T18:53:45.856Z".parse().unwrap()),
+      OrderStatus: OrderStatus::Shipped,
+      FulfillmentChannel: FulfillmentChannel::MFN,
+      MarketplaceId: "ATVPDKIKX0DER".to_string(),
+      OrderTotal: Some(CurrencyAmount {
+        CurrencyCode: "USD".to_string(),
+        Amount: "109.98".to_string(),
+      }),
+      NumberOfItemsShipped: 2,
+      ..Default::default()
+    };
+    let items = vec![
+      OrderItem {
+        OrderItemId: "46510268396154".to_string(),
+        SellerSKU: "sku-1".to_string(),
+        Title: "Speaker, \"Wood\"".to_string(),
+        QuantityOrdered: 1,
+        QuantityShipped: 1,
+        ItemPrice: Some(CurrencyAmount {
+          CurrencyCode: "USD".to_string(),
+          Amount: "99.99".to_string(),
+        }),
+        ..Default::default()
+      },
+      OrderItem {
+        OrderItemId: "46510268396155".to_string(),
+        SellerSKU: "sku-2".to_string(),
+        QuantityOrdered: 1,
+        QuantityShipped: 1,
+        ItemPrice: Some(CurrencyAmount {
+          CurrencyCode: "USD".to_string(),
+          Amount: "9.99".to_string(),
+        }),
+        ..Default::default()
+      },
+    ];
+    (order, items)
+  }
+
+  #[test]
+  fn test_export_csv_per_item() {
+    let (order, items) = get_order();
+    let mut exporter =
+      OrderExporter::new(vec![], OrderExportFormat::Csv, OrderExportGranularity::PerItem);
+    exporter.write_order(&order, &items).unwrap();
+    let out = String::from_utf8(exporter.finish().unwrap()).unwrap();
+    let lines: Vec<_> = out.lines().collect();
+    assert_eq!(lines.len(), 3);
+    assert_eq!(
+      lines[0],
+      ORDER_COLUMNS
+        .iter()
+        .chain(ITEM_COLUMNS.iter())
+        .cloned()
+        .collect::<Vec<_>>()
+        .join(",")
+    );
+    assert_eq!(
+      lines[1],
+      "111-3081581-6666666,,2018-12-16T18:53:45Z,,Shipped,MFN,,ATVPDKIKX0DER,,,,false,false,false,false,,,,,,,USD,109.98,2,0,\
+       46510268396154,,sku-1,\"Speaker, \"\"Wood\"\"\",1,1,USD,99.99,,,,,,,"
+    );
+  }
+
+  #[test]
+  fn test_export_ndjson_per_order() {
+    let (order, items) = get_order();
+    let mut exporter = OrderExporter::new(
+      vec![],
+      OrderExportFormat::Ndjson,
+      OrderExportGranularity::PerOrder,
+    );
+    exporter.write_order(&order, &items).unwrap();
+    exporter.write_order(&order, &[]).unwrap();
+    let out = String::from_utf8(exporter.finish().unwrap()).unwrap();
+    let rows: Vec<Value> = out
+      .lines()
+      .map(|line| ::serde_json::from_str(line).unwrap())
+      .collect();
+    assert_eq!(rows.len(), 2);
+    assert_eq!(rows[0]["amazon_order_id"], "111-3081581-6666666");
+    assert_eq!(rows[0]["seller_order_id"], Value::Null);
+    assert_eq!(rows[0]["item_count"], "2");
+    assert_eq!(rows[0]["quantity_ordered"], "2");
+    assert_eq!(rows[0]["seller_skus"], "sku-1;sku-2");
+    assert_eq!(rows[1]["item_count"], "0");
+    assert_eq!(rows[1]["seller_skus"], Value::Null);
+  }
+}
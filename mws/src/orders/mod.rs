@@ -4,7 +4,12 @@
 
 use chrono::{DateTime, Utc};
 use client::{Client, Method};
+mod export;
 mod types;
+pub use self::export::{
+  OrderExportFormat, OrderExportGranularity, OrderExporter, ITEM_COLUMNS, ORDER_COLUMNS,
+  ORDER_SUMMARY_COLUMNS,
+};
 pub use self::types::*;
 use result::MwsResult;
 
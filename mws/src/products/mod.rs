@@ -11,7 +11,7 @@ pub use self::types::*;
 static PATH: &'static str = "/Products/2011-10-01";
 static VERSION: &'static str = "2011-10-01";
 
-#[derive(FromXmlStream, Default, Debug)]
+#[derive(FromXmlStream, Default, Debug, Serialize)]
 #[allow(non_snake_case)]
 pub struct GetLowestPricedOffersForSKUResponse {
   pub Identifier: Identifier,
@@ -65,7 +65,7 @@ pub struct GetMyPriceForASINParameters {
   pub ItemCondition: Option<ItemCondition>,
 }
 
-#[derive(FromXmlStream, Default, Debug, PartialEq)]
+#[derive(FromXmlStream, Default, Debug, PartialEq, Serialize)]
 #[allow(non_snake_case)]
 pub struct GetMyPriceForASINResult {
   #[from_xml_stream(from_attr = "ASIN")]
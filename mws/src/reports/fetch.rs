@@ -0,0 +1,288 @@
+//! Request → poll → download workflow
+
+use super::{
+  GetReportList, GetReportListParameters, GetReportRequestList,
+  GetReportRequestListParameters, ReportProcessingStatus, RequestReport, RequestReportParameters,
+};
+use client::{Client, Method};
+use encoding::{decode_reader, ReportEncoding};
+use reqwest::header::CONTENT_TYPE;
+use result::{MwsError, MwsResult};
+use std::io::{self, Read, Write};
+use std::thread::sleep;
+use std::time::{Duration, Instant};
+
+/// Controls how `fetch_report` polls `GetReportRequestList`.
+///
+/// The first poll happens after `initial_interval`, every following interval is multiplied by
+/// `multiplier` and capped at `max_interval`. A `multiplier` of `0` is treated as `1`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PollPolicy {
+  pub initial_interval: Duration,
+  pub max_interval: Duration,
+  pub multiplier: u32,
+  /// Gives up and returns `FetchReportOutcome::TimedOut` once this much time has passed since
+  /// the report was requested.
+  pub timeout: Duration,
+}
+
+impl Default for PollPolicy {
+  fn default() -> Self {
+    PollPolicy {
+      initial_interval: Duration::from_secs(15),
+      max_interval: Duration::from_secs(300),
+      multiplier: 2,
+      timeout: Duration::from_secs(3600),
+    }
+  }
+}
+
+impl PollPolicy {
+  /// Returns the interval to wait before the `attempt`th (0-based) poll.
+  pub fn interval(&self, attempt: u32) -> Duration {
+    let mut interval = self.initial_interval;
+    for _ in 0..attempt {
+      if interval >= self.max_interval {
+        break;
+      }
+      interval *= ::std::cmp::max(self.multiplier, 1);
+    }
+    ::std::cmp::min(interval, self.max_interval)
+  }
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub enum FetchReportOutcome {
+  /// The report was written to the sink and its Content-MD5 was verified.
+  Downloaded {
+    report_request_id: String,
+    report_id: String,
+    size: u64,
+  },
+  /// The report request completed but there was no data to return.
+  NoData { report_request_id: String },
+  /// The report request was cancelled.
+  Cancelled { report_request_id: String },
+  /// The report request was still being processed when `PollPolicy::timeout` elapsed.
+  TimedOut { report_request_id: String },
+}
+
+/// Requests a report, polls its processing status until it is done, then downloads it to `sink`
+/// with `download_report`.
+///
+/// Throttling and other temporary errors (see `MwsError::should_try_again`) during polling are
+/// retried with the poll interval. `ReportEncoding::Auto` only transcodes flat file and CSV reports,
+/// see `ReportEncoding::for_report_format`. `ReportEncoding::Auto(None)` falls back to the report
+/// encoding of the first marketplace in `MarketplaceIdList`.
+pub fn fetch_report<W: Write>(
+  client: &Client,
+  request: RequestReportParameters,
+  sink: &mut W,
+  poll_policy: PollPolicy,
+  encoding: ReportEncoding,
+) -> MwsResult<FetchReportOutcome> {
+  if let Some(info) = request.ReportType.info() {
+    if !info.can_request {
+      return Err(MwsError::Msg(format!(
+        "report type '{}' can only be scheduled",
+        request.ReportType.as_ref() as &str
+      )));
+    }
+    if info.requires_date_range && (request.StartDate.is_none() || request.EndDate.is_none()) {
+      return Err(MwsError::Msg(format!(
+        "report type '{}' requires StartDate and EndDate",
+        request.ReportType.as_ref() as &str
+      )));
+    }
+  }
+
+  let encoding = encoding.for_report_format(request.ReportType.info().map(|info| info.format));
+  let encoding = match (encoding, request.MarketplaceIdList.as_ref()) {
+    (ReportEncoding::Auto(None), Some(ids)) if !ids.is_empty() => {
+      ReportEncoding::for_marketplace(&ids[0])
+    }
+    (encoding, _) => encoding,
+  };
+
+  let started = Instant::now();
+  let report_request_id = RequestReport(client, request)?
+    .ReportRequestInfo
+    .ReportRequestId;
+
+  let mut attempt = 0;
+  let generated_report_id = loop {
+    let interval = poll_policy.interval(attempt);
+    attempt += 1;
+    if started.elapsed() + interval > poll_policy.timeout {
+      return Ok(FetchReportOutcome::TimedOut { report_request_id });
+    }
+    sleep(interval);
+
+    let res = GetReportRequestList(
+      client,
+      GetReportRequestListParameters {
+        ReportRequestIdList: Some(vec![report_request_id.clone()]),
+        ..Default::default()
+      },
+    );
+    let info = match res {
+      Ok(res) => res
+        .ReportRequestInfo
+        .into_iter()
+        .find(|info| info.ReportRequestId == report_request_id),
+      Err(ref err) if err.should_try_again() => continue,
+      Err(err) => return Err(err),
+    };
+
+    let info = match info {
+      Some(info) => info,
+      None => continue,
+    };
+
+    match info.ReportProcessingStatus {
+      ReportProcessingStatus::_DONE_ => break info.GeneratedReportId,
+      ReportProcessingStatus::_DONE_NO_DATA_ => {
+        return Ok(FetchReportOutcome::NoData { report_request_id })
+      }
+      ReportProcessingStatus::_CANCELLED_ => {
+        return Ok(FetchReportOutcome::Cancelled { report_request_id })
+      }
+      _ => {}
+    }
+  };
+
+  let report_id = match generated_report_id {
+    Some(id) => id,
+    None => find_report_id(client, &report_request_id)?,
+  };
+
+  let size = download_report(client, report_id.clone(), sink, encoding)?;
+
+  Ok(FetchReportOutcome::Downloaded {
+    report_request_id,
+    report_id,
+    size,
+  })
+}
+
+/// `GeneratedReportId` is not always returned, look the report up by request id.
+fn find_report_id(client: &Client, report_request_id: &str) -> MwsResult<String> {
+  let res = GetReportList(
+    client,
+    GetReportListParameters {
+      ReportRequestIdList: Some(vec![report_request_id.to_string()]),
+      ..Default::default()
+    },
+  )?;
+  res
+    .ReportInfo
+    .into_iter()
+    .find(|info| info.ReportRequestId == report_request_id)
+    .map(|info| info.ReportId)
+    .ok_or_else(|| {
+      MwsError::Msg(format!(
+        "report not found for report request '{}'",
+        report_request_id
+      ))
+    })
+}
+
+/// Downloads a report to `sink`, transcoding it to UTF-8 as decided by `encoding`.
+///
+/// The report body is checked against the `Content-MD5` header before it is transcoded, a mismatch
+/// is reported as `MwsError::ContentMD5Mismatch`. Returns the number of bytes written to `sink`.
+pub fn download_report<W: Write>(
+  client: &Client,
+  report_id: String,
+  sink: &mut W,
+  encoding: ReportEncoding,
+) -> MwsResult<u64> {
+  let params = vec![("ReportId".to_string(), report_id)];
+  let resp = client.request(Method::POST, super::PATH, super::VERSION, "GetReport", params)?;
+  let content_md5 = resp
+    .headers()
+    .get("Content-MD5")
+    .ok_or_else(|| MwsError::ContentMD5HeaderMissing)
+    .and_then(|data| ::std::str::from_utf8(data.as_bytes()).map_err(Into::into))?
+    .to_owned();
+  let content_type = resp
+    .headers()
+    .get(CONTENT_TYPE)
+    .and_then(|v| v.to_str().ok())
+    .map(ToString::to_string);
+
+  let mut body = Md5Reader::new(resp);
+  let size = match encoding.resolve(content_type.as_ref().map(AsRef::as_ref)) {
+    Some(encoding) => io::copy(&mut decode_reader(&mut body, encoding), sink)?,
+    None => io::copy(&mut body, sink)?,
+  };
+  let actual = body.digest_base64();
+  if actual != content_md5 {
+    return Err(MwsError::ContentMD5Mismatch {
+      expected: content_md5,
+      actual,
+    });
+  }
+  Ok(size)
+}
+
+/// Computes the MD5 digest of the bytes read through it.
+struct Md5Reader<R: Read> {
+  inner: R,
+  context: ::md5::Context,
+}
+
+impl<R: Read> Md5Reader<R> {
+  fn new(inner: R) -> Self {
+    Md5Reader {
+      inner,
+      context: ::md5::Context::new(),
+    }
+  }
+
+  fn digest_base64(self) -> String {
+    ::base64::encode(&*self.context.compute())
+  }
+}
+
+impl<R: Read> Read for Md5Reader<R> {
+  fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+    let size = self.inner.read(buf)?;
+    self.context.consume(&buf[..size]);
+    Ok(size)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_poll_policy_interval() {
+    let policy = PollPolicy {
+      initial_interval: Duration::from_secs(10),
+      max_interval: Duration::from_secs(60),
+      multiplier: 2,
+      timeout: Duration::from_secs(600),
+    };
+    let intervals: Vec<_> = (0..5).map(|i| policy.interval(i).as_secs()).collect();
+    assert_eq!(intervals, vec![10, 20, 40, 60, 60]);
+    assert_eq!(policy.interval(1000).as_secs(), 60);
+
+    let policy = PollPolicy {
+      multiplier: 0,
+      ..policy
+    };
+    let intervals: Vec<_> = (0..3).map(|i| policy.interval(i).as_secs()).collect();
+    assert_eq!(intervals, vec![10, 10, 10]);
+  }
+
+  #[test]
+  fn test_md5_reader() {
+    let mut out = vec![];
+    let mut r = Md5Reader::new(&b"hello world"[..]);
+    io::copy(&mut r, &mut out).unwrap();
+    assert_eq!(r.digest_base64(), "XrY7u+Ae7tCTyyK7j1rNww==");
+    assert_eq!(out, b"hello world");
+  }
+}
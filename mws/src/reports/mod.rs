@@ -4,8 +4,15 @@
 
 use chrono::{DateTime, Utc};
 use client::{Client, Method};
+mod fetch;
+pub mod rows;
+pub mod settlement;
 mod types;
-pub use self::types::{ReportInfo, ReportProcessingStatus, ReportRequestInfo};
+pub use self::fetch::{download_report, fetch_report, FetchReportOutcome, PollPolicy};
+pub use self::types::{
+  ReportFormat, ReportInfo, ReportProcessingStatus, ReportRequestInfo, ReportSchedule, ReportType,
+  ReportTypeInfo, Schedule,
+};
 use result::{MwsError, MwsResult};
 use std::io::{self, Write};
 
@@ -18,7 +25,7 @@ static VERSION: &'static str = "2009-01-01";
 pub struct GetReportListParameters {
   pub MaxCount: Option<i32>,
   #[mws_param(list_item_type_name = "Type")]
-  pub ReportTypeList: Option<Vec<String>>,
+  pub ReportTypeList: Option<Vec<ReportType>>,
   pub Acknowledged: Option<bool>,
   pub AvailableFromDate: Option<DateTime<Utc>>,
   pub AvailableToDate: Option<DateTime<Utc>>,
@@ -104,7 +111,7 @@ pub fn GetReport<W: Write>(
 pub struct GetReportRequestListParameters {
   pub MaxCount: Option<i32>,
   #[mws_param(list_item_type_name = "Type")]
-  pub ReportTypeList: Option<Vec<String>>,
+  pub ReportTypeList: Option<Vec<ReportType>>,
   pub RequestedFromDate: Option<DateTime<Utc>>,
   pub RequestedToDate: Option<DateTime<Utc>>,
   #[mws_param(list_item_type_name = "Id")]
@@ -170,7 +177,7 @@ pub fn GetReportRequestListByNextToken(
 #[allow(non_snake_case)]
 #[derive(Debug, Default, Serialize, SerializeMwsParams)]
 pub struct RequestReportParameters {
-  pub ReportType: String,
+  pub ReportType: ReportType,
   pub StartDate: Option<DateTime<Utc>>,
   pub EndDate: Option<DateTime<Utc>>,
   pub ReportOptions: Option<String>,
@@ -201,19 +208,314 @@ pub fn RequestReport(
     .map_err(|err| err.into())
 }
 
+/// Parameters for `CancelReportRequests`
+#[allow(non_snake_case)]
+#[derive(Debug, Default, Serialize, SerializeMwsParams)]
+pub struct CancelReportRequestsParameters {
+  #[mws_param(list_item_type_name = "Id")]
+  pub ReportRequestIdList: Option<Vec<String>>,
+  #[mws_param(list_item_type_name = "Type")]
+  pub ReportTypeList: Option<Vec<ReportType>>,
+  #[mws_param(list_item_type_name = "Status")]
+  pub ReportProcessingStatusList: Option<Vec<ReportProcessingStatus>>,
+  pub RequestedFromDate: Option<DateTime<Utc>>,
+  pub RequestedToDate: Option<DateTime<Utc>>,
+}
+
+#[allow(non_snake_case)]
+#[derive(Debug, Default, Serialize, FromXmlStream)]
+pub struct CancelReportRequestsResponse {
+  pub Count: i32,
+  #[from_xml_stream(no_list_wrapper)]
+  pub ReportRequestInfo: Vec<ReportRequestInfo>,
+}
+
+response_envelope_type!(
+  CancelReportRequestsEnvelope<CancelReportRequestsResponse>,
+  "CancelReportRequestsResponse",
+  "CancelReportRequestsResult"
+);
+
+/// Cancels one or more report requests.
+///
+/// If no parameters are specified, all report requests that have not started processing are cancelled.
+#[allow(non_snake_case)]
+pub fn CancelReportRequests(
+  client: &Client,
+  params: CancelReportRequestsParameters,
+) -> MwsResult<CancelReportRequestsResponse> {
+  client
+    .request_xml(Method::POST, PATH, VERSION, "CancelReportRequests", params)
+    .map(|e: CancelReportRequestsEnvelope| e.into_inner())
+    .map_err(|err| err.into())
+}
+
+/// Parameters for `GetReportRequestCount`
+#[allow(non_snake_case)]
+#[derive(Debug, Default, Serialize, SerializeMwsParams)]
+pub struct GetReportRequestCountParameters {
+  #[mws_param(list_item_type_name = "Type")]
+  pub ReportTypeList: Option<Vec<ReportType>>,
+  #[mws_param(list_item_type_name = "Status")]
+  pub ReportProcessingStatusList: Option<Vec<ReportProcessingStatus>>,
+  pub RequestedFromDate: Option<DateTime<Utc>>,
+  pub RequestedToDate: Option<DateTime<Utc>>,
+}
+
+#[allow(non_snake_case)]
+#[derive(Debug, Default, Serialize, FromXmlStream)]
+pub struct GetCountResponse {
+  pub Count: i32,
+}
+
+response_envelope_type!(
+  GetReportRequestCountEnvelope<GetCountResponse>,
+  "GetReportRequestCountResponse",
+  "GetReportRequestCountResult"
+);
+
+/// Returns a count of report requests that have been submitted to Amazon MWS for processing.
+#[allow(non_snake_case)]
+pub fn GetReportRequestCount(
+  client: &Client,
+  params: GetReportRequestCountParameters,
+) -> MwsResult<GetCountResponse> {
+  client
+    .request_xml(Method::POST, PATH, VERSION, "GetReportRequestCount", params)
+    .map(|e: GetReportRequestCountEnvelope| e.into_inner())
+    .map_err(|err| err.into())
+}
+
+/// Parameters for `GetReportCount`
+#[allow(non_snake_case)]
+#[derive(Debug, Default, Serialize, SerializeMwsParams)]
+pub struct GetReportCountParameters {
+  #[mws_param(list_item_type_name = "Type")]
+  pub ReportTypeList: Option<Vec<ReportType>>,
+  pub Acknowledged: Option<bool>,
+  pub AvailableFromDate: Option<DateTime<Utc>>,
+  pub AvailableToDate: Option<DateTime<Utc>>,
+}
+
+response_envelope_type!(
+  GetReportCountEnvelope<GetCountResponse>,
+  "GetReportCountResponse",
+  "GetReportCountResult"
+);
+
+/// Returns a count of reports that were created in the previous 90 days.
+#[allow(non_snake_case)]
+pub fn GetReportCount(
+  client: &Client,
+  params: GetReportCountParameters,
+) -> MwsResult<GetCountResponse> {
+  client
+    .request_xml(Method::POST, PATH, VERSION, "GetReportCount", params)
+    .map(|e: GetReportCountEnvelope| e.into_inner())
+    .map_err(|err| err.into())
+}
+
+/// Parameters for `UpdateReportAcknowledgements`
+#[allow(non_snake_case)]
+#[derive(Debug, Default, Serialize, SerializeMwsParams)]
+pub struct UpdateReportAcknowledgementsParameters {
+  #[mws_param(list_item_type_name = "Id")]
+  pub ReportIdList: Vec<String>,
+  pub Acknowledged: Option<bool>,
+}
+
+#[allow(non_snake_case)]
+#[derive(Debug, Default, Serialize, FromXmlStream)]
+pub struct UpdateReportAcknowledgementsResponse {
+  pub Count: i32,
+  #[from_xml_stream(no_list_wrapper)]
+  pub ReportInfo: Vec<ReportInfo>,
+}
+
+response_envelope_type!(
+  UpdateReportAcknowledgementsEnvelope<UpdateReportAcknowledgementsResponse>,
+  "UpdateReportAcknowledgementsResponse",
+  "UpdateReportAcknowledgementsResult"
+);
+
+/// Updates the acknowledged status of one or more reports.
+#[allow(non_snake_case)]
+pub fn UpdateReportAcknowledgements(
+  client: &Client,
+  params: UpdateReportAcknowledgementsParameters,
+) -> MwsResult<UpdateReportAcknowledgementsResponse> {
+  client
+    .request_xml(
+      Method::POST,
+      PATH,
+      VERSION,
+      "UpdateReportAcknowledgements",
+      params,
+    )
+    .map(|e: UpdateReportAcknowledgementsEnvelope| e.into_inner())
+    .map_err(|err| err.into())
+}
+
+/// Parameters for `ManageReportSchedule`
+#[allow(non_snake_case)]
+#[derive(Debug, Default, Serialize, SerializeMwsParams)]
+pub struct ManageReportScheduleParameters {
+  pub ReportType: ReportType,
+  pub Schedule: Schedule,
+  /// The date when the next report is scheduled to run. Limited to no more than 366 days in the future.
+  pub ScheduleDate: Option<DateTime<Utc>>,
+}
+
+#[allow(non_snake_case)]
+#[derive(Debug, Default, PartialEq, Serialize, FromXmlStream)]
+pub struct ManageReportScheduleResponse {
+  pub Count: i32,
+  #[from_xml_stream(no_list_wrapper)]
+  pub ReportSchedule: Vec<ReportSchedule>,
+}
+
+response_envelope_type!(
+  ManageReportScheduleEnvelope<ManageReportScheduleResponse>,
+  "ManageReportScheduleResponse",
+  "ManageReportScheduleResult"
+);
+
+/// Creates, updates, or deletes a report request schedule for a specified report type.
+///
+/// Use `Schedule::_NEVER_` to delete a schedule.
+#[allow(non_snake_case)]
+pub fn ManageReportSchedule(
+  client: &Client,
+  params: ManageReportScheduleParameters,
+) -> MwsResult<ManageReportScheduleResponse> {
+  client
+    .request_xml(Method::POST, PATH, VERSION, "ManageReportSchedule", params)
+    .map(|e: ManageReportScheduleEnvelope| e.into_inner())
+    .map_err(|err| err.into())
+}
+
+/// Parameters for `GetReportScheduleList` and `GetReportScheduleCount`
+#[allow(non_snake_case)]
+#[derive(Debug, Default, Serialize, SerializeMwsParams)]
+pub struct GetReportScheduleListParameters {
+  #[mws_param(list_item_type_name = "Type")]
+  pub ReportTypeList: Option<Vec<ReportType>>,
+}
+
+#[allow(non_snake_case)]
+#[derive(Debug, Default, Serialize, FromXmlStream)]
+pub struct GetReportScheduleListResponse {
+  #[from_xml_stream(no_list_wrapper)]
+  pub ReportSchedule: Vec<ReportSchedule>,
+  pub NextToken: Option<String>,
+  pub HasNext: bool,
+}
+
+response_envelope_type!(
+  GetReportScheduleListEnvelope<GetReportScheduleListResponse>,
+  "GetReportScheduleListResponse",
+  "GetReportScheduleListResult"
+);
+
+response_envelope_type!(
+  GetReportScheduleListByNextTokenEnvelope<GetReportScheduleListResponse>,
+  "GetReportScheduleListByNextTokenResponse",
+  "GetReportScheduleListByNextTokenResult"
+);
+
+/// Returns a list of order report requests that are scheduled to be submitted to Amazon MWS for processing.
+#[allow(non_snake_case)]
+pub fn GetReportScheduleList(
+  client: &Client,
+  params: GetReportScheduleListParameters,
+) -> MwsResult<GetReportScheduleListResponse> {
+  client
+    .request_xml(Method::POST, PATH, VERSION, "GetReportScheduleList", params)
+    .map(|e: GetReportScheduleListEnvelope| e.into_inner())
+    .map_err(|err| err.into())
+}
+
+/// Returns a list of report request schedules using the NextToken, which was supplied by a previous
+/// request to either GetReportScheduleListByNextToken or GetReportScheduleList, where the value of
+/// HasNext was true in that previous request.
+#[allow(non_snake_case)]
+pub fn GetReportScheduleListByNextToken(
+  client: &Client,
+  next_token: String,
+) -> MwsResult<GetReportScheduleListResponse> {
+  let params = vec![("NextToken".to_string(), next_token)];
+  client
+    .request_xml(
+      Method::POST,
+      PATH,
+      VERSION,
+      "GetReportScheduleListByNextToken",
+      params,
+    )
+    .map(|e: GetReportScheduleListByNextTokenEnvelope| e.into_inner())
+    .map_err(|err| err.into())
+}
+
+response_envelope_type!(
+  GetReportScheduleCountEnvelope<GetCountResponse>,
+  "GetReportScheduleCountResponse",
+  "GetReportScheduleCountResult"
+);
+
+/// Returns a count of order report requests that are scheduled to be submitted to Amazon MWS.
+#[allow(non_snake_case)]
+pub fn GetReportScheduleCount(
+  client: &Client,
+  params: GetReportScheduleListParameters,
+) -> MwsResult<GetCountResponse> {
+  client
+    .request_xml(Method::POST, PATH, VERSION, "GetReportScheduleCount", params)
+    .map(|e: GetReportScheduleCountEnvelope| e.into_inner())
+    .map_err(|err| err.into())
+}
+
 #[cfg(test)]
 mod tests {
   use super::super::client::get_test_client;
   use super::*;
   use dotenv::dotenv;
 
+  #[test]
+  fn test_decode_manage_report_schedule_response() {
+    test_decode_envelope!(
+      ManageReportScheduleEnvelope,
+      r#"<?xml version="1.0"?>
+        <ManageReportScheduleResponse xmlns="http://mws.amazonaws.com/doc/2009-01-01/">
+          <ManageReportScheduleResult>
+            <Count>1</Count>
+            <ReportSchedule>
+              <ReportType>_GET_ORDERS_DATA_</ReportType>
+              <Schedule>_15_MINUTES_</Schedule>
+              <ScheduledDate>2009-02-20T02:10:42+00:00</ScheduledDate>
+            </ReportSchedule>
+          </ManageReportScheduleResult>
+          <ResponseMetadata>
+            <RequestId>7ee4cb05-2fd4-4c2d-a5fd-1c4d0cc7f0e9</RequestId>
+          </ResponseMetadata>
+        </ManageReportScheduleResponse>"#,
+      ManageReportScheduleResponse {
+        Count: 1,
+        ReportSchedule: vec![ReportSchedule {
+          ReportType: ReportType::OrdersData,
+          Schedule: Schedule::_15_MINUTES_,
+          ScheduledDate: Some("2009-02-20T02:10:42+00:00".parse().unwrap()),
+        }],
+      }
+    );
+  }
+
   #[test]
   #[ignore]
   fn test_get_report_list() {
     dotenv().ok();
     let c = get_test_client();
     let mut params = GetReportListParameters::default();
-    params.ReportTypeList = Some(vec!["_GET_V2_SETTLEMENT_REPORT_DATA_FLAT_FILE_".to_owned()]);
+    params.ReportTypeList = Some(vec![ReportType::V2SettlementReportDataFlatFile]);
     let res = GetReportList(&c, params).expect("GetReportList");
     println!("{:?}", res);
   }
@@ -234,7 +536,7 @@ mod tests {
     dotenv().ok();
     let c = get_test_client();
     let mut params = GetReportRequestListParameters::default();
-    params.ReportTypeList = Some(vec!["_GET_AFN_INVENTORY_DATA_".to_owned()]);
+    params.ReportTypeList = Some(vec![ReportType::AfnInventoryData]);
     let res = GetReportRequestList(&c, params).expect("GetReportRequestList");
     println!("{:?}", res);
 
@@ -0,0 +1,285 @@
+//! Typed rows of commonly used tab-delimited flat file reports
+//!
+//! Parse a downloaded report with [`TdffParser`](../../tdff/struct.TdffParser.html):
+//!
+//! ```no_run
+//! # use mws::reports::rows::MerchantListingsAllDataRow;
+//! # use mws::tdff::TdffParser;
+//! let f = std::fs::File::open("listings.txt").unwrap();
+//! let rows = TdffParser::new(f)
+//!   .unwrap()
+//!   .parse_all::<MerchantListingsAllDataRow>()
+//!   .unwrap();
+//! ```
+//!
+//! Amounts and dates are kept as the strings found in the report because their format depends
+//! on the marketplace. The columns identifying a row are marked as required, use
+//! `TdffHeaderMode::Strict` to detect column changes before parsing.
+
+/// A row of `_GET_MERCHANT_LISTINGS_ALL_DATA_`
+#[derive(Debug, Default, PartialEq, Serialize, FromTdffRow)]
+pub struct MerchantListingsAllDataRow {
+  #[from_tdff_row(key = "item-name")]
+  pub item_name: String,
+  #[from_tdff_row(key = "item-description")]
+  pub item_description: String,
+  #[from_tdff_row(key = "listing-id")]
+  pub listing_id: String,
+  #[from_tdff_row(key = "seller-sku", required)]
+  pub seller_sku: String,
+  pub price: String,
+  pub quantity: i32,
+  #[from_tdff_row(key = "open-date")]
+  pub open_date: String,
+  #[from_tdff_row(key = "image-url")]
+  pub image_url: String,
+  #[from_tdff_row(key = "item-is-marketplace")]
+  pub item_is_marketplace: String,
+  #[from_tdff_row(key = "product-id-type")]
+  pub product_id_type: String,
+  #[from_tdff_row(key = "item-note")]
+  pub item_note: String,
+  #[from_tdff_row(key = "item-condition")]
+  pub item_condition: String,
+  pub asin1: String,
+  pub asin2: String,
+  pub asin3: String,
+  #[from_tdff_row(key = "will-ship-internationally")]
+  pub will_ship_internationally: String,
+  #[from_tdff_row(key = "expedited-shipping")]
+  pub expedited_shipping: String,
+  #[from_tdff_row(key = "product-id")]
+  pub product_id: String,
+  #[from_tdff_row(key = "add-delete")]
+  pub add_delete: String,
+  #[from_tdff_row(key = "pending-quantity")]
+  pub pending_quantity: i32,
+  #[from_tdff_row(key = "fulfillment-channel")]
+  pub fulfillment_channel: String,
+  #[from_tdff_row(key = "merchant-shipping-group")]
+  pub merchant_shipping_group: String,
+  pub status: String,
+}
+
+/// A row of `_GET_FBA_MYI_UNSUPPRESSED_INVENTORY_DATA_`
+#[derive(Debug, Default, PartialEq, Serialize, FromTdffRow)]
+pub struct FbaMyiUnsuppressedInventoryDataRow {
+  #[from_tdff_row(required)]
+  pub sku: String,
+  pub fnsku: String,
+  pub asin: String,
+  #[from_tdff_row(key = "product-name")]
+  pub product_name: String,
+  pub condition: String,
+  #[from_tdff_row(key = "your-price")]
+  pub your_price: String,
+  #[from_tdff_row(key = "mfn-listing-exists")]
+  pub mfn_listing_exists: String,
+  #[from_tdff_row(key = "mfn-fulfillable-quantity")]
+  pub mfn_fulfillable_quantity: i32,
+  #[from_tdff_row(key = "afn-listing-exists")]
+  pub afn_listing_exists: String,
+  #[from_tdff_row(key = "afn-warehouse-quantity")]
+  pub afn_warehouse_quantity: i32,
+  #[from_tdff_row(key = "afn-fulfillable-quantity")]
+  pub afn_fulfillable_quantity: i32,
+  #[from_tdff_row(key = "afn-unsellable-quantity")]
+  pub afn_unsellable_quantity: i32,
+  #[from_tdff_row(key = "afn-reserved-quantity")]
+  pub afn_reserved_quantity: i32,
+  #[from_tdff_row(key = "afn-total-quantity")]
+  pub afn_total_quantity: i32,
+  #[from_tdff_row(key = "per-unit-volume")]
+  pub per_unit_volume: String,
+  #[from_tdff_row(key = "afn-inbound-working-quantity")]
+  pub afn_inbound_working_quantity: i32,
+  #[from_tdff_row(key = "afn-inbound-shipped-quantity")]
+  pub afn_inbound_shipped_quantity: i32,
+  #[from_tdff_row(key = "afn-inbound-receiving-quantity")]
+  pub afn_inbound_receiving_quantity: i32,
+}
+
+/// A row of `_GET_AFN_INVENTORY_DATA_`
+#[derive(Debug, Default, PartialEq, Serialize, FromTdffRow)]
+pub struct AfnInventoryDataRow {
+  #[from_tdff_row(key = "seller-sku", required)]
+  pub seller_sku: String,
+  #[from_tdff_row(key = "fulfillment-channel-sku")]
+  pub fulfillment_channel_sku: String,
+  pub asin: String,
+  #[from_tdff_row(key = "condition-type")]
+  pub condition_type: String,
+  /// `SELLABLE` or `UNSELLABLE`
+  #[from_tdff_row(key = "Warehouse-Condition-code", required)]
+  pub warehouse_condition_code: String,
+  #[from_tdff_row(key = "Quantity Available", required)]
+  pub quantity_available: i32,
+}
+
+/// A row of `_GET_FLAT_FILE_ALL_ORDERS_DATA_BY_LAST_UPDATE_` and
+/// `_GET_FLAT_FILE_ALL_ORDERS_DATA_BY_ORDER_DATE_`, one row per order item
+#[derive(Debug, Default, PartialEq, Serialize, FromTdffRow)]
+pub struct FlatFileAllOrdersDataRow {
+  #[from_tdff_row(key = "amazon-order-id", required)]
+  pub amazon_order_id: String,
+  #[from_tdff_row(key = "merchant-order-id")]
+  pub merchant_order_id: String,
+  #[from_tdff_row(key = "purchase-date")]
+  pub purchase_date: String,
+  #[from_tdff_row(key = "last-updated-date")]
+  pub last_updated_date: String,
+  #[from_tdff_row(key = "order-status")]
+  pub order_status: String,
+  #[from_tdff_row(key = "fulfillment-channel")]
+  pub fulfillment_channel: String,
+  #[from_tdff_row(key = "sales-channel")]
+  pub sales_channel: String,
+  #[from_tdff_row(key = "order-channel")]
+  pub order_channel: String,
+  pub url: String,
+  #[from_tdff_row(key = "ship-service-level")]
+  pub ship_service_level: String,
+  #[from_tdff_row(key = "product-name")]
+  pub product_name: String,
+  #[from_tdff_row(required)]
+  pub sku: String,
+  pub asin: String,
+  #[from_tdff_row(key = "item-status")]
+  pub item_status: String,
+  pub quantity: i32,
+  pub currency: String,
+  #[from_tdff_row(key = "item-price")]
+  pub item_price: String,
+  #[from_tdff_row(key = "item-tax")]
+  pub item_tax: String,
+  #[from_tdff_row(key = "shipping-price")]
+  pub shipping_price: String,
+  #[from_tdff_row(key = "shipping-tax")]
+  pub shipping_tax: String,
+  #[from_tdff_row(key = "gift-wrap-price")]
+  pub gift_wrap_price: String,
+  #[from_tdff_row(key = "gift-wrap-tax")]
+  pub gift_wrap_tax: String,
+  #[from_tdff_row(key = "item-promotion-discount")]
+  pub item_promotion_discount: String,
+  #[from_tdff_row(key = "ship-promotion-discount")]
+  pub ship_promotion_discount: String,
+  #[from_tdff_row(key = "ship-city")]
+  pub ship_city: String,
+  #[from_tdff_row(key = "ship-state")]
+  pub ship_state: String,
+  #[from_tdff_row(key = "ship-postal-code")]
+  pub ship_postal_code: String,
+  #[from_tdff_row(key = "ship-country")]
+  pub ship_country: String,
+  #[from_tdff_row(key = "promotion-ids")]
+  pub promotion_ids: String,
+  #[from_tdff_row(key = "is-business-order")]
+  pub is_business_order: String,
+  #[from_tdff_row(key = "purchase-order-number")]
+  pub purchase_order_number: String,
+  #[from_tdff_row(key = "price-designation")]
+  pub price_designation: String,
+}
+
+/// A row of `_GET_FBA_FULFILLMENT_CUSTOMER_RETURNS_DATA_`
+#[derive(Debug, Default, PartialEq, Serialize, FromTdffRow)]
+pub struct FbaFulfillmentCustomerReturnsDataRow {
+  #[from_tdff_row(key = "return-date")]
+  pub return_date: String,
+  #[from_tdff_row(key = "order-id", required)]
+  pub order_id: String,
+  #[from_tdff_row(required)]
+  pub sku: String,
+  pub asin: String,
+  pub fnsku: String,
+  #[from_tdff_row(key = "product-name")]
+  pub product_name: String,
+  pub quantity: i32,
+  #[from_tdff_row(key = "fulfillment-center-id")]
+  pub fulfillment_center_id: String,
+  #[from_tdff_row(key = "detailed-disposition")]
+  pub detailed_disposition: String,
+  pub reason: String,
+  pub status: String,
+  #[from_tdff_row(key = "license-plate-number")]
+  pub license_plate_number: String,
+  #[from_tdff_row(key = "customer-comments")]
+  pub customer_comments: String,
+}
+
+/// A row of `_GET_V2_SETTLEMENT_REPORT_DATA_FLAT_FILE_V2_`
+///
+/// The first row of a settlement report is a summary row, only the `settlement_*`, `deposit_date`,
+/// `total_amount` and `currency` fields are set.
+#[derive(Debug, Default, PartialEq, Serialize, FromTdffRow)]
+pub struct V2SettlementReportDataFlatFileV2Row {
+  #[from_tdff_row(key = "settlement-id", required)]
+  pub settlement_id: String,
+  #[from_tdff_row(key = "settlement-start-date")]
+  pub settlement_start_date: String,
+  #[from_tdff_row(key = "settlement-end-date")]
+  pub settlement_end_date: String,
+  #[from_tdff_row(key = "deposit-date")]
+  pub deposit_date: String,
+  #[from_tdff_row(key = "total-amount")]
+  pub total_amount: String,
+  pub currency: String,
+  #[from_tdff_row(key = "transaction-type")]
+  pub transaction_type: String,
+  #[from_tdff_row(key = "order-id")]
+  pub order_id: String,
+  #[from_tdff_row(key = "merchant-order-id")]
+  pub merchant_order_id: String,
+  #[from_tdff_row(key = "adjustment-id")]
+  pub adjustment_id: String,
+  #[from_tdff_row(key = "shipment-id")]
+  pub shipment_id: String,
+  #[from_tdff_row(key = "marketplace-name")]
+  pub marketplace_name: String,
+  #[from_tdff_row(key = "amount-type")]
+  pub amount_type: String,
+  #[from_tdff_row(key = "amount-description")]
+  pub amount_description: String,
+  pub amount: String,
+  #[from_tdff_row(key = "fulfillment-id")]
+  pub fulfillment_id: String,
+  #[from_tdff_row(key = "posted-date")]
+  pub posted_date: String,
+  #[from_tdff_row(key = "posted-date-time")]
+  pub posted_date_time: String,
+  #[from_tdff_row(key = "order-item-code")]
+  pub order_item_code: String,
+  #[from_tdff_row(key = "merchant-order-item-id")]
+  pub merchant_order_item_id: String,
+  #[from_tdff_row(key = "merchant-adjustment-item-id")]
+  pub merchant_adjustment_item_id: String,
+  pub sku: String,
+  #[from_tdff_row(key = "quantity-purchased")]
+  pub quantity_purchased: i32,
+  #[from_tdff_row(key = "promotion-id")]
+  pub promotion_id: String,
+}
+
+/// A row of `_GET_RESERVED_INVENTORY_DATA_`
+#[derive(Debug, Default, PartialEq, Serialize, FromTdffRow)]
+pub struct ReservedInventoryDataRow {
+  #[from_tdff_row(required)]
+  pub sku: String,
+  pub fnsku: String,
+  pub asin: String,
+  #[from_tdff_row(key = "product-name")]
+  pub product_name: String,
+  /// Total reserved units, the sum of the following fields
+  #[from_tdff_row(key = "reserved_qty")]
+  pub reserved_qty: i32,
+  /// Units reserved for customer orders
+  #[from_tdff_row(key = "reserved_customerorders")]
+  pub reserved_customerorders: i32,
+  /// Units being transferred between fulfillment centers
+  #[from_tdff_row(key = "reserved_fc-transfers")]
+  pub reserved_fc_transfers: i32,
+  /// Units sidelined at fulfillment centers for additional processing
+  #[from_tdff_row(key = "reserved_fc-processing")]
+  pub reserved_fc_processing: i32,
+}
@@ -0,0 +1,584 @@
+//! Settlement XML report (`_GET_V2_SETTLEMENT_REPORT_DATA_XML_`)
+//!
+//! Settlement reports can be large, `SettlementReportReader` reads the document one transaction at
+//! a time:
+//!
+//! ```no_run
+//! # use mws::reports::settlement::{SettlementReportReader, SettlementTransaction};
+//! let f = std::fs::File::open("settlement.xml").unwrap();
+//! let mut reader = SettlementReportReader::new(f).unwrap();
+//! println!("{:?}", reader.settlement_data());
+//! for transaction in reader {
+//!   if let SettlementTransaction::Order(order) = transaction.unwrap() {
+//!     println!("{}", order.AmazonOrderID);
+//!   }
+//! }
+//! ```
+
+use chrono::{DateTime, Utc};
+use result::MwsResult;
+use std::io::Read;
+use xml::reader::XmlEvent;
+use xmlhelper::decode::{
+  element, seek_element, start_document, start_element, AnyElementName, FromXmlStream, Stream,
+  XmlEventStream,
+};
+
+/// An amount with its currency attribute, e.g. `<Amount currency="USD">-15.00</Amount>`
+#[allow(non_snake_case)]
+#[derive(Debug, Default, PartialEq, Serialize, FromXmlStream)]
+pub struct Amount {
+  #[from_xml_stream(from_attr = "currency")]
+  pub Currency: String,
+  #[from_xml_stream(from_content)]
+  pub Value: String,
+}
+
+#[allow(non_snake_case)]
+#[derive(Debug, Default, PartialEq, Serialize, FromXmlStream)]
+pub struct SettlementData {
+  pub AmazonSettlementID: String,
+  pub TotalAmount: Amount,
+  pub StartDate: Option<DateTime<Utc>>,
+  pub EndDate: Option<DateTime<Utc>>,
+  pub DepositDate: Option<DateTime<Utc>>,
+}
+
+/// A price component, e.g. `Principal`, `Shipping` or `Tax`
+#[allow(non_snake_case)]
+#[derive(Debug, Default, PartialEq, Serialize, FromXmlStream)]
+pub struct Component {
+  pub Type: String,
+  pub Amount: Amount,
+}
+
+/// A fee, e.g. `Commission` or `FBAPerUnitFulfillmentFee`
+#[allow(non_snake_case)]
+#[derive(Debug, Default, PartialEq, Serialize, FromXmlStream)]
+pub struct Fee {
+  pub Type: String,
+  pub Amount: Amount,
+}
+
+#[allow(non_snake_case)]
+#[derive(Debug, Default, PartialEq, Serialize, FromXmlStream)]
+pub struct Promotion {
+  pub MerchantPromotionID: String,
+  pub Type: String,
+  pub Amount: Amount,
+}
+
+#[allow(non_snake_case)]
+#[derive(Debug, Default, PartialEq, Serialize, FromXmlStream)]
+pub struct OrderItem {
+  pub AmazonOrderItemCode: String,
+  pub SKU: String,
+  pub Quantity: i32,
+  pub ItemPrice: Vec<Component>,
+  pub ItemFees: Vec<Fee>,
+  #[from_xml_stream(no_list_wrapper)]
+  pub Promotion: Vec<Promotion>,
+}
+
+#[allow(non_snake_case)]
+#[derive(Debug, Default, PartialEq, Serialize, FromXmlStream)]
+pub struct OrderFulfillment {
+  pub MerchantFulfillmentID: String,
+  pub PostedDate: Option<DateTime<Utc>>,
+  #[from_xml_stream(no_list_wrapper)]
+  pub Item: Vec<OrderItem>,
+}
+
+#[allow(non_snake_case)]
+#[derive(Debug, Default, PartialEq, Serialize, FromXmlStream)]
+pub struct Order {
+  pub AmazonOrderID: String,
+  pub MerchantOrderID: String,
+  pub ShipmentID: String,
+  pub MarketplaceName: String,
+  pub Fulfillment: OrderFulfillment,
+}
+
+/// An item of a `Refund`, `Chargeback` or `GuaranteeClaim`
+#[allow(non_snake_case)]
+#[derive(Debug, Default, PartialEq, Serialize, FromXmlStream)]
+pub struct AdjustmentItem {
+  pub AmazonOrderItemCode: String,
+  pub MerchantAdjustmentItemID: String,
+  pub SKU: String,
+  pub ItemPriceAdjustments: Vec<Component>,
+  pub ItemFeeAdjustments: Vec<Fee>,
+  #[from_xml_stream(no_list_wrapper)]
+  pub PromotionAdjustment: Vec<Promotion>,
+}
+
+#[allow(non_snake_case)]
+#[derive(Debug, Default, PartialEq, Serialize, FromXmlStream)]
+pub struct AdjustmentFulfillment {
+  pub MerchantFulfillmentID: String,
+  pub PostedDate: Option<DateTime<Utc>>,
+  #[from_xml_stream(no_list_wrapper)]
+  pub AdjustedItem: Vec<AdjustmentItem>,
+}
+
+/// A `Refund`, `Chargeback` or `GuaranteeClaim`
+#[allow(non_snake_case)]
+#[derive(Debug, Default, PartialEq, Serialize, FromXmlStream)]
+pub struct Refund {
+  pub AmazonOrderID: String,
+  pub MerchantOrderID: String,
+  pub AdjustmentID: String,
+  pub MarketplaceName: String,
+  pub Fulfillment: AdjustmentFulfillment,
+}
+
+#[allow(non_snake_case)]
+#[derive(Debug, Default, PartialEq, Serialize, FromXmlStream)]
+pub struct OtherTransactionItem {
+  pub SKU: String,
+  pub Quantity: i32,
+  pub Amount: Amount,
+}
+
+/// Transactions not related to an order item, e.g. `Transfer`, `Subscription Fee` or
+/// `FBA Inventory Reimbursement`
+#[allow(non_snake_case)]
+#[derive(Debug, Default, PartialEq, Serialize, FromXmlStream)]
+pub struct OtherTransaction {
+  pub TransactionType: String,
+  pub TransactionID: String,
+  pub AmazonOrderID: Option<String>,
+  pub ShipmentID: Option<String>,
+  pub PostedDate: Option<DateTime<Utc>>,
+  pub Amount: Amount,
+  pub Fees: Vec<Fee>,
+  #[from_xml_stream(no_list_wrapper)]
+  pub OtherTransactionItem: Vec<OtherTransactionItem>,
+}
+
+#[allow(non_snake_case)]
+#[derive(Debug, Default, PartialEq, Serialize, FromXmlStream)]
+pub struct SellerCouponPayment {
+  pub TransactionID: String,
+  pub PostedDate: Option<DateTime<Utc>>,
+  pub CouponID: String,
+  pub SellerCouponDescription: String,
+  pub Count: i32,
+  pub PaymentEventId: String,
+  pub FeeComponent: Option<Fee>,
+  pub TotalAmount: Amount,
+}
+
+#[allow(non_snake_case)]
+#[derive(Debug, Default, PartialEq, Serialize, FromXmlStream)]
+pub struct SellerDealPayment {
+  pub TransactionID: String,
+  pub PostedDate: Option<DateTime<Utc>>,
+  pub DealID: String,
+  pub DealDescription: String,
+  pub EventType: String,
+  pub FeeType: String,
+  pub FeeAmount: Amount,
+  pub TaxAmount: Amount,
+  pub TotalAmount: Amount,
+}
+
+#[allow(non_snake_case)]
+#[derive(Debug, Default, PartialEq, Serialize, FromXmlStream)]
+pub struct AdvertisingTransactionDetails {
+  pub TransactionType: String,
+  pub PostedDate: Option<DateTime<Utc>>,
+  pub InvoiceId: String,
+  pub BaseAmount: Amount,
+  pub TaxAmount: Amount,
+  pub TransactionAmount: Amount,
+}
+
+/// A child element of `<SettlementReport>` following `<SettlementData>`
+#[derive(Debug, PartialEq, Serialize)]
+pub enum SettlementTransaction {
+  Order(Order),
+  Refund(Refund),
+  Chargeback(Refund),
+  GuaranteeClaim(Refund),
+  OtherTransaction(OtherTransaction),
+  SellerCouponPayment(SellerCouponPayment),
+  SellerDealPayment(SellerDealPayment),
+  AdvertisingTransactionDetails(AdvertisingTransactionDetails),
+  /// An element this crate doesn't know about, its content is skipped
+  Unknown(String),
+}
+
+/// A whole settlement report, see `SettlementReportReader::read_all`.
+#[allow(non_snake_case)]
+#[derive(Debug, Default, PartialEq, Serialize, FromXmlStream)]
+pub struct SettlementReport {
+  pub SettlementData: SettlementData,
+  #[from_xml_stream(no_list_wrapper)]
+  pub Order: Vec<Order>,
+  #[from_xml_stream(no_list_wrapper)]
+  pub Refund: Vec<Refund>,
+  #[from_xml_stream(no_list_wrapper)]
+  pub Chargeback: Vec<Refund>,
+  #[from_xml_stream(no_list_wrapper)]
+  pub GuaranteeClaim: Vec<Refund>,
+  #[from_xml_stream(no_list_wrapper)]
+  pub OtherTransaction: Vec<OtherTransaction>,
+  #[from_xml_stream(no_list_wrapper)]
+  pub SellerCouponPayment: Vec<SellerCouponPayment>,
+  #[from_xml_stream(no_list_wrapper)]
+  pub SellerDealPayment: Vec<SellerDealPayment>,
+  #[from_xml_stream(no_list_wrapper)]
+  pub AdvertisingTransactionDetails: Vec<AdvertisingTransactionDetails>,
+}
+
+/// Streams the transactions of a settlement report.
+///
+/// `new` reads up to and including `<SettlementData>`, the iterator then yields one transaction per
+/// call.
+pub struct SettlementReportReader<R: Read> {
+  stream: Stream<R>,
+  settlement_data: SettlementData,
+  done: bool,
+}
+
+impl<R: Read> SettlementReportReader<R> {
+  pub fn new(source: R) -> MwsResult<Self> {
+    let mut stream = Stream::new(source);
+    start_document(&mut stream)?;
+    start_element(&mut stream, "AmazonEnvelope")?;
+    seek_element(&mut stream, "Message")?;
+    start_element(&mut stream, "Message")?;
+    seek_element(&mut stream, "SettlementReport")?;
+    start_element(&mut stream, "SettlementReport")?;
+    seek_element(&mut stream, "SettlementData")?;
+    let settlement_data = element(&mut stream, "SettlementData", |s| {
+      SettlementData::from_xml(s)
+    })?;
+    Ok(SettlementReportReader {
+      stream,
+      settlement_data,
+      done: false,
+    })
+  }
+
+  pub fn settlement_data(&self) -> &SettlementData {
+    &self.settlement_data
+  }
+
+  /// Reads the remaining transactions into a `SettlementReport`, unknown elements are dropped.
+  pub fn read_all(mut self) -> MwsResult<SettlementReport> {
+    let mut report = SettlementReport::default();
+    for transaction in &mut self {
+      match transaction? {
+        SettlementTransaction::Order(v) => report.Order.push(v),
+        SettlementTransaction::Refund(v) => report.Refund.push(v),
+        SettlementTransaction::Chargeback(v) => report.Chargeback.push(v),
+        SettlementTransaction::GuaranteeClaim(v) => report.GuaranteeClaim.push(v),
+        SettlementTransaction::OtherTransaction(v) => report.OtherTransaction.push(v),
+        SettlementTransaction::SellerCouponPayment(v) => report.SellerCouponPayment.push(v),
+        SettlementTransaction::SellerDealPayment(v) => report.SellerDealPayment.push(v),
+        SettlementTransaction::AdvertisingTransactionDetails(v) => {
+          report.AdvertisingTransactionDetails.push(v)
+        }
+        SettlementTransaction::Unknown(_) => {}
+      }
+    }
+    report.SettlementData = self.settlement_data;
+    Ok(report)
+  }
+
+  fn read_transaction(&mut self) -> MwsResult<Option<SettlementTransaction>> {
+    loop {
+      let is_start = match self.stream.peek() {
+        Some(&Ok(XmlEvent::StartElement { .. })) => true,
+        Some(&Ok(XmlEvent::EndElement { .. })) | None => return Ok(None),
+        Some(&Ok(_)) => false,
+        Some(Err(err)) => return Err(err.clone().into()),
+      };
+
+      if !is_start {
+        self.stream.next();
+        continue;
+      }
+
+      let transaction = element(&mut self.stream, AnyElementName, |s| {
+        let name = s.local_name().to_string();
+        Ok(match name.as_ref() {
+          "Order" => SettlementTransaction::Order(FromXmlStream::from_xml(s)?),
+          "Refund" => SettlementTransaction::Refund(FromXmlStream::from_xml(s)?),
+          "Chargeback" => SettlementTransaction::Chargeback(FromXmlStream::from_xml(s)?),
+          "GuaranteeClaim" => SettlementTransaction::GuaranteeClaim(FromXmlStream::from_xml(s)?),
+          "OtherTransaction" => SettlementTransaction::OtherTransaction(FromXmlStream::from_xml(s)?),
+          "SellerCouponPayment" => {
+            SettlementTransaction::SellerCouponPayment(FromXmlStream::from_xml(s)?)
+          }
+          "SellerDealPayment" => {
+            SettlementTransaction::SellerDealPayment(FromXmlStream::from_xml(s)?)
+          }
+          "AdvertisingTransactionDetails" => {
+            SettlementTransaction::AdvertisingTransactionDetails(FromXmlStream::from_xml(s)?)
+          }
+          _ => SettlementTransaction::Unknown(name),
+        })
+      })?;
+      return Ok(Some(transaction));
+    }
+  }
+}
+
+impl<R: Read> Iterator for SettlementReportReader<R> {
+  type Item = MwsResult<SettlementTransaction>;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    if self.done {
+      return None;
+    }
+
+    match self.read_transaction() {
+      Ok(Some(transaction)) => Some(Ok(transaction)),
+      Ok(None) => {
+        self.done = true;
+        None
+      }
+      Err(err) => {
+        self.done = true;
+        Some(Err(err))
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::io::Cursor;
+
+  const REPORT: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<AmazonEnvelope xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance" xsi:noNamespaceSchemaLocation="amzn-envelope.xsd">
+  <Header>
+    <DocumentVersion>1.01</DocumentVersion>
+    <MerchantIdentifier>M_EXAMPLE_123456</MerchantIdentifier>
+  </Header>
+  <MessageType>SettlementReport</MessageType>
+  <Message>
+    <MessageID>1</MessageID>
+    <SettlementReport>
+      <SettlementData>
+        <AmazonSettlementID>11720839581</AmazonSettlementID>
+        <TotalAmount currency="USD">1234.56</TotalAmount>
+        <StartDate>2019-01-01T00:00:00+00:00</StartDate>
+        <EndDate>2019-01-15T00:00:00+00:00</EndDate>
+        <DepositDate>2019-01-17T00:00:00+00:00</DepositDate>
+      </SettlementData>
+      <Order>
+        <AmazonOrderID>111-3081581-6666666</AmazonOrderID>
+        <MerchantOrderID>111-3081581-6666666</MerchantOrderID>
+        <ShipmentID>DKqM1p1L9</ShipmentID>
+        <MarketplaceName>Amazon.com</MarketplaceName>
+        <Fulfillment>
+          <MerchantFulfillmentID>AFN</MerchantFulfillmentID>
+          <PostedDate>2019-01-02T10:12:45+00:00</PostedDate>
+          <Item>
+            <AmazonOrderItemCode>46510268396154</AmazonOrderItemCode>
+            <SKU>edifier-r1280t-fba</SKU>
+            <Quantity>1</Quantity>
+            <ItemPrice>
+              <Component>
+                <Type>Principal</Type>
+                <Amount currency="USD">99.99</Amount>
+              </Component>
+              <Component>
+                <Type>Tax</Type>
+                <Amount currency="USD">6.50</Amount>
+              </Component>
+            </ItemPrice>
+            <ItemFees>
+              <Fee>
+                <Type>Commission</Type>
+                <Amount currency="USD">-15.00</Amount>
+              </Fee>
+            </ItemFees>
+          </Item>
+        </Fulfillment>
+      </Order>
+      <Refund>
+        <AmazonOrderID>112-8095165-5463447</AmazonOrderID>
+        <MerchantOrderID>112-8095165-5463447</MerchantOrderID>
+        <AdjustmentID>amzn1:crow:Hk1Rq8</AdjustmentID>
+        <MarketplaceName>Amazon.com</MarketplaceName>
+        <Fulfillment>
+          <MerchantFulfillmentID>AFN</MerchantFulfillmentID>
+          <PostedDate>2019-01-08T03:20:11+00:00</PostedDate>
+          <AdjustedItem>
+            <AmazonOrderItemCode>34620934221098</AmazonOrderItemCode>
+            <MerchantAdjustmentItemID>RFD-1</MerchantAdjustmentItemID>
+            <SKU>edifier-s1000db-fba</SKU>
+            <ItemPriceAdjustments>
+              <Component>
+                <Type>Principal</Type>
+                <Amount currency="USD">-489.99</Amount>
+              </Component>
+            </ItemPriceAdjustments>
+            <ItemFeeAdjustments>
+              <Fee>
+                <Type>Commission</Type>
+                <Amount currency="USD">58.80</Amount>
+              </Fee>
+            </ItemFeeAdjustments>
+            <PromotionAdjustment>
+              <MerchantPromotionID>PLM-1234</MerchantPromotionID>
+              <Type>Principal</Type>
+              <Amount currency="USD">50.00</Amount>
+            </PromotionAdjustment>
+          </AdjustedItem>
+        </Fulfillment>
+      </Refund>
+      <OtherTransaction>
+        <TransactionType>Subscription Fee</TransactionType>
+        <TransactionID>4z5qVTeD</TransactionID>
+        <PostedDate>2019-01-10T00:00:00+00:00</PostedDate>
+        <Amount currency="USD">-39.99</Amount>
+      </OtherTransaction>
+      <SellerCouponPayment>
+        <TransactionID>ZyxW123</TransactionID>
+        <PostedDate>2019-01-11T00:00:00+00:00</PostedDate>
+        <CouponID>coupon-1</CouponID>
+        <SellerCouponDescription>Save 10%</SellerCouponDescription>
+        <Count>2</Count>
+        <PaymentEventId>PE-1</PaymentEventId>
+        <FeeComponent>
+          <Type>CouponRedemptionFee</Type>
+          <Amount currency="USD">-1.20</Amount>
+        </FeeComponent>
+        <TotalAmount currency="USD">-1.20</TotalAmount>
+      </SellerCouponPayment>
+      <NewTransactionType>
+        <Amount currency="USD">1.00</Amount>
+      </NewTransactionType>
+    </SettlementReport>
+  </Message>
+</AmazonEnvelope>"#;
+
+  fn usd(value: &str) -> Amount {
+    Amount {
+      Currency: "USD".to_string(),
+      Value: value.to_string(),
+    }
+  }
+
+  #[test]
+  fn test_settlement_report_reader() {
+    let mut reader = SettlementReportReader::new(Cursor::new(REPORT)).unwrap();
+    assert_eq!(
+      reader.settlement_data(),
+      &SettlementData {
+        AmazonSettlementID: "11720839581".to_string(),
+        TotalAmount: usd("1234.56"),
+        StartDate: Some("2019-01-01T00:00:00Z".parse().unwrap()),
+        EndDate: Some("2019-01-15T00:00:00Z".parse().unwrap()),
+        DepositDate: Some("2019-01-17T00:00:00Z".parse().unwrap()),
+      }
+    );
+
+    match reader.next().unwrap().unwrap() {
+      SettlementTransaction::Order(order) => assert_eq!(
+        order,
+        Order {
+          AmazonOrderID: "111-3081581-6666666".to_string(),
+          MerchantOrderID: "111-3081581-6666666".to_string(),
+          ShipmentID: "DKqM1p1L9".to_string(),
+          MarketplaceName: "Amazon.com".to_string(),
+          Fulfillment: OrderFulfillment {
+            MerchantFulfillmentID: "AFN".to_string(),
+            PostedDate: Some("2019-01-02T10:12:45Z".parse().unwrap()),
+            Item: vec![OrderItem {
+              AmazonOrderItemCode: "46510268396154".to_string(),
+              SKU: "edifier-r1280t-fba".to_string(),
+              Quantity: 1,
+              ItemPrice: vec![
+                Component {
+                  Type: "Principal".to_string(),
+                  Amount: usd("99.99"),
+                },
+                Component {
+                  Type: "Tax".to_string(),
+                  Amount: usd("6.50"),
+                },
+              ],
+              ItemFees: vec![Fee {
+                Type: "Commission".to_string(),
+                Amount: usd("-15.00"),
+              }],
+              Promotion: vec![],
+            }],
+          },
+        }
+      ),
+      other => panic!("unexpected transaction: {:?}", other),
+    }
+
+    match reader.next().unwrap().unwrap() {
+      SettlementTransaction::Refund(refund) => {
+        assert_eq!(refund.AdjustmentID, "amzn1:crow:Hk1Rq8");
+        let item = &refund.Fulfillment.AdjustedItem[0];
+        assert_eq!(item.ItemPriceAdjustments[0].Amount, usd("-489.99"));
+        assert_eq!(item.ItemFeeAdjustments[0].Amount, usd("58.80"));
+        assert_eq!(item.PromotionAdjustment[0].MerchantPromotionID, "PLM-1234");
+      }
+      other => panic!("unexpected transaction: {:?}", other),
+    }
+
+    match reader.next().unwrap().unwrap() {
+      SettlementTransaction::OtherTransaction(t) => {
+        assert_eq!(t.TransactionType, "Subscription Fee");
+        assert_eq!(t.Amount, usd("-39.99"));
+        assert_eq!(t.AmazonOrderID, None);
+      }
+      other => panic!("unexpected transaction: {:?}", other),
+    }
+
+    match reader.next().unwrap().unwrap() {
+      SettlementTransaction::SellerCouponPayment(p) => {
+        assert_eq!(p.Count, 2);
+        assert_eq!(p.FeeComponent.unwrap().Type, "CouponRedemptionFee");
+        assert_eq!(p.TotalAmount, usd("-1.20"));
+      }
+      other => panic!("unexpected transaction: {:?}", other),
+    }
+
+    assert_eq!(
+      reader.next().unwrap().unwrap(),
+      SettlementTransaction::Unknown("NewTransactionType".to_string())
+    );
+    assert!(reader.next().is_none());
+    assert!(reader.next().is_none());
+  }
+
+  #[test]
+  fn test_settlement_report_read_all() {
+    let report = SettlementReportReader::new(Cursor::new(REPORT))
+      .unwrap()
+      .read_all()
+      .unwrap();
+    assert_eq!(report.SettlementData.AmazonSettlementID, "11720839581");
+    assert_eq!(report.Order.len(), 1);
+    assert_eq!(report.Refund.len(), 1);
+    assert_eq!(report.OtherTransaction.len(), 1);
+    assert_eq!(report.SellerCouponPayment.len(), 1);
+    assert!(report.Chargeback.is_empty());
+  }
+
+  #[test]
+  fn test_settlement_report_invalid() {
+    let err = SettlementReportReader::new(Cursor::new(
+      r#"<AmazonEnvelope><Message><MessageID>1</MessageID></Message></AmazonEnvelope>"#,
+    ))
+    .err()
+    .unwrap();
+    assert_eq!(
+      err.to_string(),
+      "unexpected end of xml: expected element: SettlementReport"
+    );
+  }
+}
@@ -1,9 +1,210 @@
 use chrono::{DateTime, Utc};
 
+string_map_enum! {
+  /// Enumerates the report types that are available through the Reports API section.
+  ///
+  /// [Reference](http://docs.developer.amazonservices.com/en_US/reports/Reports_ReportType.html)
+  pub enum ReportType {
+    // Listings Reports
+    FlatFileOpenListingsData = "_GET_FLAT_FILE_OPEN_LISTINGS_DATA_",
+    MerchantListingsAllData = "_GET_MERCHANT_LISTINGS_ALL_DATA_",
+    MerchantListingsData = "_GET_MERCHANT_LISTINGS_DATA_",
+    MerchantListingsInactiveData = "_GET_MERCHANT_LISTINGS_INACTIVE_DATA_",
+    MerchantListingsDataBackCompat = "_GET_MERCHANT_LISTINGS_DATA_BACK_COMPAT_",
+    MerchantListingsDataLite = "_GET_MERCHANT_LISTINGS_DATA_LITE_",
+    MerchantListingsDataLiter = "_GET_MERCHANT_LISTINGS_DATA_LITER_",
+    MerchantCancelledListingsData = "_GET_MERCHANT_CANCELLED_LISTINGS_DATA_",
+    MerchantListingsDefectData = "_GET_MERCHANT_LISTINGS_DEFECT_DATA_",
+    PanEuOfferStatus = "_GET_PAN_EU_OFFER_STATUS_",
+    MfnPanEuOfferStatus = "_GET_MFN_PAN_EU_OFFER_STATUS_",
+    FlatFileGeoOpportunities = "_GET_FLAT_FILE_GEO_OPPORTUNITIES_",
+    XmlBrowseTreeData = "_GET_XML_BROWSE_TREE_DATA_",
+
+    // Order Reports
+    FlatFileActionableOrderData = "_GET_FLAT_FILE_ACTIONABLE_ORDER_DATA_",
+    OrdersData = "_GET_ORDERS_DATA_",
+    FlatFileOrdersData = "_GET_FLAT_FILE_ORDERS_DATA_",
+    ConvergedFlatFileOrderReportData = "_GET_CONVERGED_FLAT_FILE_ORDER_REPORT_DATA_",
+    FlatFileAllOrdersDataByLastUpdate = "_GET_FLAT_FILE_ALL_ORDERS_DATA_BY_LAST_UPDATE_",
+    FlatFileAllOrdersDataByOrderDate = "_GET_FLAT_FILE_ALL_ORDERS_DATA_BY_ORDER_DATE_",
+    XmlAllOrdersDataByLastUpdate = "_GET_XML_ALL_ORDERS_DATA_BY_LAST_UPDATE_",
+    XmlAllOrdersDataByOrderDate = "_GET_XML_ALL_ORDERS_DATA_BY_ORDER_DATE_",
+    FlatFilePendingOrdersData = "_GET_FLAT_FILE_PENDING_ORDERS_DATA_",
+    PendingOrdersData = "_GET_PENDING_ORDERS_DATA_",
+    ConvergedFlatFilePendingOrdersData = "_GET_CONVERGED_FLAT_FILE_PENDING_ORDERS_DATA_",
+
+    // Fulfillment by Amazon (FBA) Reports
+    AmazonFulfilledShipmentsData = "_GET_AMAZON_FULFILLED_SHIPMENTS_DATA_",
+    FbaFulfillmentCustomerShipmentSalesData = "_GET_FBA_FULFILLMENT_CUSTOMER_SHIPMENT_SALES_DATA_",
+    FbaFulfillmentCustomerShipmentPromotionData = "_GET_FBA_FULFILLMENT_CUSTOMER_SHIPMENT_PROMOTION_DATA_",
+    FbaFulfillmentCustomerTaxesData = "_GET_FBA_FULFILLMENT_CUSTOMER_TAXES_DATA_",
+    AfnInventoryData = "_GET_AFN_INVENTORY_DATA_",
+    AfnInventoryDataByCountry = "_GET_AFN_INVENTORY_DATA_BY_COUNTRY_",
+    FbaFulfillmentCurrentInventoryData = "_GET_FBA_FULFILLMENT_CURRENT_INVENTORY_DATA_",
+    FbaFulfillmentMonthlyInventoryData = "_GET_FBA_FULFILLMENT_MONTHLY_INVENTORY_DATA_",
+    FbaFulfillmentInventoryReceiptsData = "_GET_FBA_FULFILLMENT_INVENTORY_RECEIPTS_DATA_",
+    ReservedInventoryData = "_GET_RESERVED_INVENTORY_DATA_",
+    FbaFulfillmentInventorySummaryData = "_GET_FBA_FULFILLMENT_INVENTORY_SUMMARY_DATA_",
+    FbaFulfillmentInventoryAdjustmentsData = "_GET_FBA_FULFILLMENT_INVENTORY_ADJUSTMENTS_DATA_",
+    FbaFulfillmentInventoryHealthData = "_GET_FBA_FULFILLMENT_INVENTORY_HEALTH_DATA_",
+    FbaMyiUnsuppressedInventoryData = "_GET_FBA_MYI_UNSUPPRESSED_INVENTORY_DATA_",
+    FbaMyiAllInventoryData = "_GET_FBA_MYI_ALL_INVENTORY_DATA_",
+    RestockInventoryRecommendationsReport = "_GET_RESTOCK_INVENTORY_RECOMMENDATIONS_REPORT_",
+    FbaFulfillmentInboundNoncomplianceData = "_GET_FBA_FULFILLMENT_INBOUND_NONCOMPLIANCE_DATA_",
+    StrandedInventoryUiData = "_GET_STRANDED_INVENTORY_UI_DATA_",
+    StrandedInventoryLoaderData = "_GET_STRANDED_INVENTORY_LOADER_DATA_",
+    FbaInventoryAgedData = "_GET_FBA_INVENTORY_AGED_DATA_",
+    ExcessInventoryData = "_GET_EXCESS_INVENTORY_DATA_",
+    FbaStorageFeeChargesData = "_GET_FBA_STORAGE_FEE_CHARGES_DATA_",
+    ProductExchangeData = "_GET_PRODUCT_EXCHANGE_DATA_",
+    FbaEstimatedFbaFeesTxtData = "_GET_FBA_ESTIMATED_FBA_FEES_TXT_DATA_",
+    FbaReimbursementsData = "_GET_FBA_REIMBURSEMENTS_DATA_",
+    FbaFulfillmentLongtermStorageFeeChargesData = "_GET_FBA_FULFILLMENT_LONGTERM_STORAGE_FEE_CHARGES_DATA_",
+    FbaFulfillmentCustomerReturnsData = "_GET_FBA_FULFILLMENT_CUSTOMER_RETURNS_DATA_",
+    FbaFulfillmentCustomerShipmentReplacementData = "_GET_FBA_FULFILLMENT_CUSTOMER_SHIPMENT_REPLACEMENT_DATA_",
+    FbaRecommendedRemovalData = "_GET_FBA_RECOMMENDED_REMOVAL_DATA_",
+    FbaFulfillmentRemovalOrderDetailData = "_GET_FBA_FULFILLMENT_REMOVAL_ORDER_DETAIL_DATA_",
+    FbaFulfillmentRemovalShipmentDetailData = "_GET_FBA_FULFILLMENT_REMOVAL_SHIPMENT_DETAIL_DATA_",
+    FbaUnoInventoryData = "_GET_FBA_UNO_INVENTORY_DATA_",
+
+    // Settlement Reports
+    V2SettlementReportDataFlatFile = "_GET_V2_SETTLEMENT_REPORT_DATA_FLAT_FILE_",
+    V2SettlementReportDataXml = "_GET_V2_SETTLEMENT_REPORT_DATA_XML_",
+    V2SettlementReportDataFlatFileV2 = "_GET_V2_SETTLEMENT_REPORT_DATA_FLAT_FILE_V2_",
+
+    // Tax Reports
+    FlatFileSalesTaxData = "_GET_FLAT_FILE_SALES_TAX_DATA_",
+    ScVatTaxReport = "_SC_VAT_TAX_REPORT_",
+    VatTransactionData = "_GET_VAT_TRANSACTION_DATA_",
+    GstMtrB2bCustom = "_GET_GST_MTR_B2B_CUSTOM_",
+    GstMtrB2cCustom = "_GET_GST_MTR_B2C_CUSTOM_",
+
+    // Performance Reports
+    SellerFeedbackData = "_GET_SELLER_FEEDBACK_DATA_",
+    V1SellerPerformanceReport = "_GET_V1_SELLER_PERFORMANCE_REPORT_",
+
+    // Returns Reports
+    XmlReturnsDataByReturnDate = "_GET_XML_RETURNS_DATA_BY_RETURN_DATE_",
+    FlatFileReturnsDataByReturnDate = "_GET_FLAT_FILE_RETURNS_DATA_BY_RETURN_DATE_",
+    XmlMfnPrimeReturnsReport = "_GET_XML_MFN_PRIME_RETURNS_REPORT_",
+    CsvMfnPrimeReturnsReport = "_GET_CSV_MFN_PRIME_RETURNS_REPORT_",
+    XmlMfnSkuReturnAttributesReport = "_GET_XML_MFN_SKU_RETURN_ATTRIBUTES_REPORT_",
+    FlatFileMfnSkuReturnAttributesReport = "_GET_FLAT_FILE_MFN_SKU_RETURN_ATTRIBUTES_REPORT_",
+  }
+}
+
+/// The format of a report document.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ReportFormat {
+  /// Tab-delimited flat file, see [`TdffParser`](../tdff/struct.TdffParser.html)
+  Tdff,
+  Xml,
+  Csv,
+}
+
+/// Describes how a report type can be obtained and what it contains.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ReportTypeInfo {
+  pub format: ReportFormat,
+  /// `false` if the report can only be scheduled with `ManageReportSchedule`
+  /// and cannot be requested with `RequestReport`.
+  pub can_request: bool,
+  /// `true` if `RequestReport` must specify `StartDate` and `EndDate`.
+  pub requires_date_range: bool,
+}
+
+impl ReportType {
+  /// Returns the metadata of a known report type, `None` for `UnknownValue`.
+  pub fn info(&self) -> Option<ReportTypeInfo> {
+    use self::ReportFormat::*;
+    use self::ReportType::*;
+    let (format, can_request, requires_date_range) = match *self {
+      FlatFileOpenListingsData => (Tdff, true, false),
+      MerchantListingsAllData => (Tdff, true, false),
+      MerchantListingsData => (Tdff, true, false),
+      MerchantListingsInactiveData => (Tdff, true, false),
+      MerchantListingsDataBackCompat => (Tdff, true, false),
+      MerchantListingsDataLite => (Tdff, true, false),
+      MerchantListingsDataLiter => (Tdff, true, false),
+      MerchantCancelledListingsData => (Tdff, true, false),
+      MerchantListingsDefectData => (Tdff, true, false),
+      PanEuOfferStatus => (Tdff, true, false),
+      MfnPanEuOfferStatus => (Tdff, true, false),
+      FlatFileGeoOpportunities => (Tdff, true, false),
+      XmlBrowseTreeData => (Xml, true, false),
+      FlatFileActionableOrderData => (Tdff, true, false),
+      OrdersData => (Xml, false, false),
+      FlatFileOrdersData => (Tdff, false, false),
+      ConvergedFlatFileOrderReportData => (Tdff, false, false),
+      FlatFileAllOrdersDataByLastUpdate => (Tdff, true, false),
+      FlatFileAllOrdersDataByOrderDate => (Tdff, true, false),
+      XmlAllOrdersDataByLastUpdate => (Xml, true, false),
+      XmlAllOrdersDataByOrderDate => (Xml, true, false),
+      FlatFilePendingOrdersData => (Tdff, true, false),
+      PendingOrdersData => (Xml, true, false),
+      ConvergedFlatFilePendingOrdersData => (Tdff, true, false),
+      AmazonFulfilledShipmentsData => (Tdff, true, false),
+      FbaFulfillmentCustomerShipmentSalesData => (Tdff, true, false),
+      FbaFulfillmentCustomerShipmentPromotionData => (Tdff, true, false),
+      FbaFulfillmentCustomerTaxesData => (Tdff, true, false),
+      AfnInventoryData => (Tdff, true, false),
+      AfnInventoryDataByCountry => (Tdff, true, false),
+      FbaFulfillmentCurrentInventoryData => (Tdff, true, false),
+      FbaFulfillmentMonthlyInventoryData => (Tdff, true, false),
+      FbaFulfillmentInventoryReceiptsData => (Tdff, true, false),
+      ReservedInventoryData => (Tdff, true, false),
+      FbaFulfillmentInventorySummaryData => (Tdff, true, false),
+      FbaFulfillmentInventoryAdjustmentsData => (Tdff, true, false),
+      FbaFulfillmentInventoryHealthData => (Tdff, true, false),
+      FbaMyiUnsuppressedInventoryData => (Tdff, true, false),
+      FbaMyiAllInventoryData => (Tdff, true, false),
+      RestockInventoryRecommendationsReport => (Tdff, true, false),
+      FbaFulfillmentInboundNoncomplianceData => (Tdff, true, false),
+      StrandedInventoryUiData => (Tdff, true, false),
+      StrandedInventoryLoaderData => (Tdff, true, false),
+      FbaInventoryAgedData => (Tdff, true, false),
+      ExcessInventoryData => (Tdff, true, false),
+      FbaStorageFeeChargesData => (Tdff, true, false),
+      ProductExchangeData => (Tdff, true, false),
+      FbaEstimatedFbaFeesTxtData => (Tdff, true, false),
+      FbaReimbursementsData => (Tdff, true, false),
+      FbaFulfillmentLongtermStorageFeeChargesData => (Tdff, true, false),
+      FbaFulfillmentCustomerReturnsData => (Tdff, true, false),
+      FbaFulfillmentCustomerShipmentReplacementData => (Tdff, true, false),
+      FbaRecommendedRemovalData => (Tdff, true, false),
+      FbaFulfillmentRemovalOrderDetailData => (Tdff, true, false),
+      FbaFulfillmentRemovalShipmentDetailData => (Tdff, true, false),
+      FbaUnoInventoryData => (Tdff, true, false),
+      V2SettlementReportDataFlatFile => (Tdff, false, false),
+      V2SettlementReportDataXml => (Xml, false, false),
+      V2SettlementReportDataFlatFileV2 => (Tdff, false, false),
+      FlatFileSalesTaxData => (Tdff, true, true),
+      ScVatTaxReport => (Csv, true, true),
+      VatTransactionData => (Csv, true, true),
+      GstMtrB2bCustom => (Csv, true, true),
+      GstMtrB2cCustom => (Csv, true, true),
+      SellerFeedbackData => (Tdff, true, false),
+      V1SellerPerformanceReport => (Xml, true, false),
+      XmlReturnsDataByReturnDate => (Xml, true, true),
+      FlatFileReturnsDataByReturnDate => (Tdff, true, true),
+      XmlMfnPrimeReturnsReport => (Xml, true, true),
+      CsvMfnPrimeReturnsReport => (Csv, true, true),
+      XmlMfnSkuReturnAttributesReport => (Xml, true, true),
+      FlatFileMfnSkuReturnAttributesReport => (Tdff, true, true),
+      UnknownValue(_) => return None,
+    };
+    Some(ReportTypeInfo {
+      format,
+      can_request,
+      requires_date_range,
+    })
+  }
+}
+
 #[allow(non_snake_case)]
 #[derive(Debug, Default, PartialEq, Serialize, FromXmlStream)]
 pub struct ReportInfo {
-  pub ReportType: String,
+  pub ReportType: ReportType,
   pub Acknowledged: bool,
   pub AcknowledgedDate: Option<DateTime<Utc>>,
   pub ReportId: String,
@@ -25,7 +226,7 @@ str_enum! {
 #[derive(Debug, Default, PartialEq, Serialize, FromXmlStream)]
 pub struct ReportRequestInfo {
   pub ReportRequestId: String,
-  pub ReportType: String,
+  pub ReportType: ReportType,
   pub StartDate: Option<DateTime<Utc>>,
   pub EndDate: Option<DateTime<Utc>>,
   pub Scheduled: bool,
@@ -35,3 +236,90 @@ pub struct ReportRequestInfo {
   pub StartedProcessingDate: Option<DateTime<Utc>>,
   pub CompletedDate: Option<DateTime<Utc>>,
 }
+
+str_enum! {
+  /// How often a report is generated by `ManageReportSchedule`.
+  ///
+  /// [Reference](http://docs.developer.amazonservices.com/en_US/reports/Reports_Schedule.html)
+  pub enum Schedule {
+    _15_MINUTES_, // Every 15 minutes
+    _30_MINUTES_, // Every 30 minutes
+    _1_HOUR_,     // Every hour
+    _2_HOURS_,    // Every 2 hours
+    _4_HOURS_,    // Every 4 hours
+    _8_HOURS_,    // Every 8 hours
+    _12_HOURS_,   // Every 12 hours
+    _1_DAY_,      // Every day
+    _2_DAYS_,     // Every 2 days
+    _72_HOURS_,   // Every 3 days
+    _1_WEEK_,     // Weekly
+    _14_DAYS_,    // Every 14 days
+    _15_DAYS_,    // Every 15 days
+    _30_DAYS_,    // Every 30 days
+    _NEVER_,      // Delete a previously created report request schedule
+  }
+}
+
+/// Report request schedule information.
+#[allow(non_snake_case)]
+#[derive(Debug, Default, PartialEq, Serialize, FromXmlStream)]
+pub struct ReportSchedule {
+  pub ReportType: ReportType,
+  pub Schedule: Schedule,
+  /// The date when the next report request is scheduled to be submitted.
+  pub ScheduledDate: Option<DateTime<Utc>>,
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_report_type() {
+    let t: ReportType = "_GET_V2_SETTLEMENT_REPORT_DATA_XML_".parse().unwrap();
+    assert_eq!(t, "_GET_V2_SETTLEMENT_REPORT_DATA_XML_");
+    assert_eq!(
+      t.info(),
+      Some(ReportTypeInfo {
+        format: ReportFormat::Xml,
+        can_request: false,
+        requires_date_range: false,
+      })
+    );
+
+    let t: ReportType = "_GET_SOMETHING_NEW_".parse().unwrap();
+    match t {
+      ReportType::UnknownValue(ref v) => assert_eq!(v, "_GET_SOMETHING_NEW_"),
+      _ => panic!("expected UnknownValue"),
+    }
+    assert_eq!(t.info(), None);
+  }
+
+  #[test]
+  fn test_decode_report_request_info() {
+    test_decode!(
+      ReportRequestInfo,
+      r#"
+        <ReportRequestId>2291326454</ReportRequestId>
+        <ReportType>_GET_MERCHANT_LISTINGS_DATA_</ReportType>
+        <StartDate>2009-01-21T02:10:39+00:00</StartDate>
+        <EndDate>2009-02-13T02:10:39+00:00</EndDate>
+        <Scheduled>false</Scheduled>
+        <SubmittedDate>2009-02-20T02:10:39+00:00</SubmittedDate>
+        <ReportProcessingStatus>_SUBMITTED_</ReportProcessingStatus>
+      "#,
+      ReportRequestInfo {
+        ReportRequestId: "2291326454".to_string(),
+        ReportType: ReportType::MerchantListingsData,
+        StartDate: Some("2009-01-21T02:10:39+00:00".parse().unwrap()),
+        EndDate: Some("2009-02-13T02:10:39+00:00".parse().unwrap()),
+        Scheduled: false,
+        SubmittedDate: Some("2009-02-20T02:10:39+00:00".parse().unwrap()),
+        ReportProcessingStatus: ReportProcessingStatus::_SUBMITTED_,
+        GeneratedReportId: None,
+        StartedProcessingDate: None,
+        CompletedDate: None,
+      }
+    );
+  }
+}
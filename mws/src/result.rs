@@ -1,4 +1,6 @@
 pub use client::ErrorResponse as MwsErrorResponse;
+use feeds::FeedValidationError;
+use tdff::TdffHeaderDrift;
 use types::GenericXmlResponseParseError;
 
 #[derive(Fail, Debug)]
@@ -17,6 +19,8 @@ pub enum MwsError {
   GenericXmlResponseParse(#[cause] GenericXmlResponseParseError),
   #[fail(display = "csv error: {}", _0)]
   Csv(#[cause] ::csv::Error),
+  #[fail(display = "json error: {}", _0)]
+  Json(#[cause] ::serde_json::Error),
   #[fail(display = "utf8 error: {}", _0)]
   Utf8(#[cause] ::std::str::Utf8Error),
   #[fail(display = "MWS request is unsuccessful: {:?}", _0)]
@@ -34,6 +38,17 @@ pub enum MwsError {
   InvalidPath(String),
   #[fail(display = "Content-MD5 header missing")]
   ContentMD5HeaderMissing,
+  #[fail(
+    display = "Content-MD5 mismatch: expected '{}', computed '{}'",
+    expected, actual
+  )]
+  ContentMD5Mismatch { expected: String, actual: String },
+  #[fail(display = "tdff row error at line {}: {}", line, error)]
+  TdffRow { line: u64, error: Box<MwsError> },
+  #[fail(display = "tdff header drift: {}", _0)]
+  TdffHeaderDrift(TdffHeaderDrift),
+  #[fail(display = "feed validation error: {}", _0)]
+  FeedValidation(FeedValidationError),
   #[fail(display = "{}", _0)]
   Msg(String),
 }
@@ -82,6 +97,7 @@ impl_from!(Http(::reqwest::Error));
 impl_from!(XmlReader(::xml::reader::Error));
 impl_from!(XmlWriter(::xml::writer::Error));
 impl_from!(Csv(::csv::Error));
+impl_from!(Json(::serde_json::Error));
 impl_from!(Utf8(::std::str::Utf8Error));
 impl_from!(Msg(String));
 impl_from!(XmlElementParse(::xmltree::ParseError));
@@ -41,7 +41,7 @@ pub fn DeregisterDestination(
     .map_err(|err| err.into())
 }
 
-#[derive(FromXmlStream, Default, Debug)]
+#[derive(FromXmlStream, Default, Debug, Serialize)]
 #[allow(non_snake_case)]
 pub struct ListRegisteredDestinationsResponse {
   pub DestinationList: Vec<Destination>,
@@ -111,7 +111,7 @@ pub struct GetSubscriptionParameters {
   pub Destination: Destination,
 }
 
-#[derive(FromXmlStream, Default, Debug)]
+#[derive(FromXmlStream, Default, Debug, Serialize)]
 #[allow(non_snake_case)]
 pub struct GetSubscriptionResponse {
   pub Subscription: Subscription,
@@ -135,7 +135,7 @@ pub fn GetSubscription(
     .map_err(|err| err.into())
 }
 
-#[derive(FromXmlStream, Default, Debug)]
+#[derive(FromXmlStream, Default, Debug, Serialize)]
 #[allow(non_snake_case)]
 pub struct ListSubscriptionsResponse {
   pub SubscriptionList: Vec<Subscription>,
@@ -1,12 +1,43 @@
 //! Tab-delimited flat file helpers
 
-use csv::{Reader, ReaderBuilder};
+use chrono::{DateTime, Utc};
+use csv::{
+  ErrorKind as CsvErrorKind, QuoteStyle, Reader, ReaderBuilder, StringRecord, Writer,
+  WriterBuilder,
+};
+use encoding::{decode_reader, Encoding};
+use encoding_rs_io::DecodeReaderBytes;
 use result::{MwsError, MwsResult};
-use std::io::Read;
+use rust_decimal::Decimal;
+use std::borrow::Cow;
+use std::fmt;
+use std::io::{Read, Write};
+use std::marker::PhantomData;
 
 pub struct TdffParser<R: Read> {
   headers: Vec<String>,
   reader: Reader<R>,
+  header_mode: TdffHeaderMode,
+}
+
+/// Decides how `TdffParser` handles headers that don't match the columns of the row type.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TdffHeaderMode {
+  /// Headers are not checked, missing columns are left as default values
+  Ignore,
+  /// Fails with `MwsError::TdffHeaderDrift` if a required column is missing or a column is
+  /// renamed, see `TdffHeaderDrift::is_breaking`
+  Strict,
+  /// Logs the drift as a warning and parses the rows anyway
+  Lenient,
+}
+
+// `#[default]` on enum variants needs Rust 1.62
+#[allow(clippy::derivable_impls)]
+impl Default for TdffHeaderMode {
+  fn default() -> Self {
+    TdffHeaderMode::Ignore
+  }
 }
 
 impl<R: Read> TdffParser<R> {
@@ -19,37 +50,300 @@ impl<R: Read> TdffParser<R> {
         .map(ToString::to_string)
         .collect(),
       reader: reader,
+      header_mode: TdffHeaderMode::default(),
     })
   }
+
+  pub fn headers(&self) -> &[String] {
+    &self.headers
+  }
+
+  /// Sets how headers are checked when rows are parsed
+  pub fn header_mode(mut self, mode: TdffHeaderMode) -> Self {
+    self.header_mode = mode;
+    self
+  }
+
+  /// Compares the headers with the columns declared by `T`
+  pub fn header_drift<T: FromTdffRow>(&self) -> TdffHeaderDrift {
+    TdffHeaderDrift::new(&self.headers, T::tdff_columns())
+  }
+}
+
+impl<R: Read> TdffParser<DecodeReaderBytes<R, Vec<u8>>> {
+  /// Creates a parser that transcodes `source` from `encoding` to UTF-8 while reading,
+  /// see `encoding::decode_reader`.
+  pub fn with_encoding(source: R, encoding: &'static Encoding) -> MwsResult<Self> {
+    TdffParser::new(decode_reader(source, encoding))
+  }
 }
 
 pub type TdffRow<'a> = Vec<(&'a str, String)>;
 
 pub trait FromTdffRow: Default + Sized {
   fn from_tdff_row(r: &TdffRow) -> MwsResult<Self>;
+
+  /// Returns the columns read by `from_tdff_row`, headers are not checked if it is empty.
+  fn tdff_columns() -> &'static [TdffColumn] {
+    &[]
+  }
+}
+
+/// A column declared by a `FromTdffRow` type
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TdffColumn {
+  /// Header names accepted for the column
+  pub keys: &'static [&'static str],
+  /// Set by `#[from_tdff_row(required)]`
+  pub required: bool,
+}
+
+/// Differences between the headers of a file and the columns of a row type
+#[derive(Debug, Default, PartialEq)]
+pub struct TdffHeaderDrift {
+  /// Required columns not found in the headers
+  pub missing_required: Vec<String>,
+  /// Optional columns not found in the headers
+  pub missing_optional: Vec<String>,
+  /// Headers that don't belong to any column
+  pub unexpected: Vec<String>,
+  /// `(column, header)` pairs of missing columns that match an unexpected header after ignoring
+  /// case and punctuation, e.g. `item-name` and `Item Name`
+  pub renamed: Vec<(String, String)>,
+}
+
+impl TdffHeaderDrift {
+  pub fn new(headers: &[String], columns: &[TdffColumn]) -> Self {
+    let mut drift = TdffHeaderDrift::default();
+    if columns.is_empty() {
+      return drift;
+    }
+
+    let mut unexpected: Vec<&String> = headers
+      .iter()
+      .filter(|h| !columns.iter().any(|c| c.keys.contains(&h.as_str())))
+      .collect();
+
+    for column in columns {
+      if column.keys.iter().any(|k| headers.iter().any(|h| h == k)) {
+        continue;
+      }
+      let name = column.keys.first().cloned().unwrap_or_default().to_string();
+      let renamed_to = unexpected.iter().position(|h| {
+        let h = normalize_header(h);
+        column.keys.iter().any(|k| normalize_header(k) == h)
+      });
+      match renamed_to {
+        Some(i) => drift.renamed.push((name, unexpected.remove(i).clone())),
+        None if column.required => drift.missing_required.push(name),
+        None => drift.missing_optional.push(name),
+      }
+    }
+
+    drift.unexpected = unexpected.into_iter().cloned().collect();
+    drift
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.missing_required.is_empty()
+      && self.missing_optional.is_empty()
+      && self.unexpected.is_empty()
+      && self.renamed.is_empty()
+  }
+
+  /// Returns `true` if there are missing required or renamed columns.
+  ///
+  /// Missing optional and unexpected columns are not breaking, reports often contain columns a row
+  /// type doesn't read.
+  pub fn is_breaking(&self) -> bool {
+    !self.missing_required.is_empty() || !self.renamed.is_empty()
+  }
+}
+
+impl fmt::Display for TdffHeaderDrift {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    let mut parts = vec![];
+    if !self.missing_required.is_empty() {
+      parts.push(format!("missing required: {}", self.missing_required.join(", ")));
+    }
+    if !self.missing_optional.is_empty() {
+      parts.push(format!("missing optional: {}", self.missing_optional.join(", ")));
+    }
+    if !self.unexpected.is_empty() {
+      parts.push(format!("unexpected: {}", self.unexpected.join(", ")));
+    }
+    if !self.renamed.is_empty() {
+      let renamed: Vec<_> = self
+        .renamed
+        .iter()
+        .map(|(column, header)| format!("'{}' -> '{}'", column, header))
+        .collect();
+      parts.push(format!("renamed: {}", renamed.join(", ")));
+    }
+    write!(f, "{}", parts.join("; "))
+  }
+}
+
+fn normalize_header(v: &str) -> String {
+  v.chars()
+    .filter(|c| c.is_alphanumeric())
+    .flat_map(char::to_lowercase)
+    .collect()
 }
 
 impl<R: Read> TdffParser<R> {
-  pub fn parse_all<T>(mut self) -> MwsResult<Vec<T>>
+  pub fn parse_all<T>(self) -> MwsResult<Vec<T>>
   where
     T: FromTdffRow,
   {
-    let size = self.headers.len();
-    let mut row_container = Vec::with_capacity(size);
-    let mut items = vec![];
-    for row in self.reader.records() {
-      row_container.clear();
-      for (i, value) in row?.into_iter().enumerate() {
-        match self.headers.get(i) {
-          Some(key) => {
-            row_container.push((key.as_ref() as &str, value.to_string()));
-          }
-          None => {}
+    self.rows().collect()
+  }
+
+  /// Returns an iterator that parses one row at a time.
+  ///
+  /// Errors are reported as `MwsError::TdffRow` with the 1-based line number of the row.
+  /// Headers are checked first according to the `TdffHeaderMode`, in strict mode a breaking
+  /// drift is returned as the only item.
+  pub fn rows<T>(self) -> TdffRows<R, T>
+  where
+    T: FromTdffRow,
+  {
+    let mut header_error = None;
+    if self.header_mode != TdffHeaderMode::Ignore {
+      let drift = self.header_drift::<T>();
+      match self.header_mode {
+        TdffHeaderMode::Strict if drift.is_breaking() => {
+          header_error = Some(MwsError::TdffHeaderDrift(drift))
         }
+        TdffHeaderMode::Lenient if !drift.is_empty() => warn!("tdff header drift: {}", drift),
+        _ => {}
       }
-      items.push(T::from_tdff_row(&row_container)?);
     }
-    Ok(items)
+
+    TdffRows {
+      headers: self.headers,
+      reader: self.reader,
+      record: StringRecord::new(),
+      row: vec![],
+      values: vec![],
+      done: header_error.is_some(),
+      header_error,
+      _phantom: PhantomData,
+    }
+  }
+}
+
+/// Iterator returned by `TdffParser::rows`
+pub struct TdffRows<R: Read, T> {
+  headers: Vec<String>,
+  reader: Reader<R>,
+  record: StringRecord,
+  /// Row passed to `from_tdff_row`, reused for every record. It is empty between records, its
+  /// keys borrow `headers` only while a record is parsed.
+  row: TdffRow<'static>,
+  /// Value strings of previous records, reused to fill `row`
+  values: Vec<String>,
+  done: bool,
+  header_error: Option<MwsError>,
+  _phantom: PhantomData<T>,
+}
+
+impl<R: Read, T: FromTdffRow> TdffRows<R, T> {
+  /// Skips malformed rows instead of returning their errors, `on_error` is called with each
+  /// skipped error.
+  ///
+  /// I/O errors can not be recovered from, they are passed to `on_error` and end the iteration.
+  pub fn skip_errors<F>(self, on_error: F) -> TdffSkipErrors<R, T, F>
+  where
+    F: FnMut(MwsError),
+  {
+    TdffSkipErrors {
+      rows: self,
+      on_error,
+    }
+  }
+
+  fn parse_record(&mut self) -> MwsResult<T> {
+    let mut row = reuse_row(::std::mem::take(&mut self.row));
+    for (key, value) in self.headers.iter().zip(self.record.iter()) {
+      let mut buf = self.values.pop().unwrap_or_default();
+      buf.clear();
+      buf.push_str(value);
+      row.push((key.as_ref(), buf));
+    }
+    let result = T::from_tdff_row(&row);
+    self.values.extend(row.drain(..).map(|(_, value)| value));
+    self.row = reuse_row(row);
+    result
+  }
+}
+
+impl<R: Read, T: FromTdffRow> Iterator for TdffRows<R, T> {
+  type Item = MwsResult<T>;
+
+  fn next(&mut self) -> Option<MwsResult<T>> {
+    if let Some(err) = self.header_error.take() {
+      return Some(Err(err));
+    }
+
+    if self.done {
+      return None;
+    }
+
+    match self.reader.read_record(&mut self.record) {
+      Ok(true) => {
+        let line = self.record.position().map(|p| p.line()).unwrap_or(0);
+        Some(self.parse_record().map_err(|err| MwsError::TdffRow {
+          line,
+          error: Box::new(err),
+        }))
+      }
+      Ok(false) => {
+        self.done = true;
+        None
+      }
+      Err(err) => {
+        if let CsvErrorKind::Io(_) = *err.kind() {
+          self.done = true;
+        }
+        let line = err.position().map(|p| p.line()).unwrap_or(0);
+        Some(Err(MwsError::TdffRow {
+          line,
+          error: Box::new(err.into()),
+        }))
+      }
+    }
+  }
+}
+
+/// Clears `row` and returns it with another key lifetime, keeping its allocation
+fn reuse_row<'a, 'b>(mut row: TdffRow<'a>) -> TdffRow<'b> {
+  row.clear();
+  row.into_iter().map(|(_, value)| ("", value)).collect()
+}
+
+/// Iterator returned by `TdffRows::skip_errors`
+pub struct TdffSkipErrors<R: Read, T, F> {
+  rows: TdffRows<R, T>,
+  on_error: F,
+}
+
+impl<R, T, F> Iterator for TdffSkipErrors<R, T, F>
+where
+  R: Read,
+  T: FromTdffRow,
+  F: FnMut(MwsError),
+{
+  type Item = T;
+
+  fn next(&mut self) -> Option<T> {
+    for res in &mut self.rows {
+      match res {
+        Ok(item) => return Some(item),
+        Err(err) => (self.on_error)(err),
+      }
+    }
+    None
   }
 }
 
@@ -74,3 +368,152 @@ where
     }
   }
 }
+
+/// Writes rows of a `ToTdffRow` type as a tab-delimited flat file feed body
+///
+/// Fields are never quoted, Amazon's flat file loader doesn't support quoting. Tabs and line
+/// breaks in a field are replaced with spaces, double quotes are written as is.
+///
+/// ```
+/// # #[macro_use] extern crate mws_derive;
+/// # extern crate mws;
+/// # use mws::tdff::TdffWriter;
+/// #[derive(ToTdffRow)]
+/// struct PriceAndQuantity {
+///   sku: String,
+///   price: Option<String>,
+///   quantity: i32,
+/// }
+///
+/// # fn main() {
+/// let mut w = TdffWriter::new(vec![]);
+/// w.write_all(&[PriceAndQuantity {
+///   sku: "edifier-r1280t".to_string(),
+///   price: None,
+///   quantity: 10,
+/// }])
+/// .unwrap();
+/// assert_eq!(w.into_inner().unwrap(), b"sku\tprice\tquantity\nedifier-r1280t\t\t10\n");
+/// # }
+/// ```
+pub struct TdffWriter<W: Write> {
+  writer: Writer<W>,
+}
+
+impl<W: Write> TdffWriter<W> {
+  pub fn new(dest: W) -> Self {
+    TdffWriter {
+      writer: WriterBuilder::new()
+        .delimiter(b'\t')
+        .quote_style(QuoteStyle::Never)
+        .flexible(true)
+        .from_writer(dest),
+    }
+  }
+
+  /// Writes the header rows of `T`, see `ToTdffRow::tdff_header_rows`
+  pub fn write_headers<T: ToTdffRow>(&mut self) -> MwsResult<()> {
+    for row in T::tdff_header_rows() {
+      self.write_record(&row)?;
+    }
+    Ok(())
+  }
+
+  pub fn write_row<T: ToTdffRow>(&mut self, row: &T) -> MwsResult<()> {
+    self.write_record(&row.to_tdff_row())
+  }
+
+  fn write_record(&mut self, record: &[String]) -> MwsResult<()> {
+    let fields: Vec<_> = record.iter().map(|v| sanitize_field(v)).collect();
+    self.writer.write_record(fields.iter().map(|v| v.as_bytes()))?;
+    Ok(())
+  }
+
+  /// Writes the header rows of `T` followed by `rows`
+  pub fn write_all<'a, T, I>(&mut self, rows: I) -> MwsResult<()>
+  where
+    T: ToTdffRow + 'a,
+    I: IntoIterator<Item = &'a T>,
+  {
+    self.write_headers::<T>()?;
+    for row in rows {
+      self.write_row(row)?;
+    }
+    Ok(())
+  }
+
+  /// Flushes the buffered rows and returns the underlying writer
+  pub fn into_inner(self) -> MwsResult<W> {
+    self
+      .writer
+      .into_inner()
+      .map_err(|err| MwsError::Io(err.into_error()))
+  }
+}
+
+/// Replaces the delimiter and line breaks, which can't be escaped without quoting
+fn sanitize_field(v: &str) -> Cow<'_, str> {
+  const BREAKS: &[char] = &['\t', '\r', '\n'];
+  if v.contains(BREAKS) {
+    Cow::Owned(v.replace(BREAKS, " "))
+  } else {
+    Cow::Borrowed(v)
+  }
+}
+
+pub trait ToTdffRow {
+  /// Returns the column names, in the order of `to_tdff_row`
+  fn tdff_keys() -> &'static [&'static str];
+
+  /// Returns the rows written before the records.
+  ///
+  /// Defaults to a single row of `tdff_keys`. Inventory file templates expect the
+  /// `TemplateType=...` row and a row of labels first, see `#[to_tdff_row(template_type)]`.
+  fn tdff_header_rows() -> Vec<Vec<String>> {
+    vec![Self::tdff_keys().iter().map(ToString::to_string).collect()]
+  }
+
+  fn to_tdff_row(&self) -> Vec<String>;
+}
+
+pub trait ToTdffField {
+  fn to_tdff_field(&self) -> String;
+}
+
+impl<T: ToTdffField> ToTdffField for Option<T> {
+  fn to_tdff_field(&self) -> String {
+    match *self {
+      Some(ref v) => v.to_tdff_field(),
+      None => String::new(),
+    }
+  }
+}
+
+impl<T: ToTdffField + ?Sized> ToTdffField for &T {
+  fn to_tdff_field(&self) -> String {
+    (**self).to_tdff_field()
+  }
+}
+
+impl ToTdffField for DateTime<Utc> {
+  fn to_tdff_field(&self) -> String {
+    use types::ToIso8601;
+    self.to_iso8601()
+  }
+}
+
+macro_rules! impl_to_tdff_field_display {
+  ($($ty:ty),*) => {
+    $(
+      impl ToTdffField for $ty {
+        fn to_tdff_field(&self) -> String {
+          self.to_string()
+        }
+      }
+    )*
+  };
+}
+
+impl_to_tdff_field_display!(
+  str, String, bool, i8, i16, i32, i64, u8, u16, u32, u64, usize, f32, f64, Decimal
+);
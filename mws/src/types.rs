@@ -1,6 +1,8 @@
 use chrono::{DateTime, TimeZone};
+use serde_json::{Map, Value};
 use std::borrow::Cow;
 pub use xmltree::Element;
+use xmltree::XMLNode;
 
 pub trait ToIso8601 {
   fn to_iso8601(&self) -> String;
@@ -181,6 +183,67 @@ impl GenericXmlResponse {
       .get_child("NextToken")
       .and_then(|v| v.get_text())
   }
+
+  /// Converts the result element to JSON, see `element_to_json`.
+  pub fn to_json(&self) -> Value {
+    element_to_json(&self.result_element)
+  }
+}
+
+/// Converts an XML element to JSON.
+///
+/// - An element without attributes and child elements becomes a string of its text content,
+///   `<Amount>1.00</Amount>` becomes `"1.00"`. Numbers and booleans are kept as strings.
+/// - Other elements become objects. Attributes are keyed by `@` + attribute name, child elements
+///   by element name, and non-whitespace text content by `#text`.
+/// - Child elements with the same name are collected into an array in document order.
+///   A single child element is never wrapped in an array.
+/// - Prefixed element names keep their prefix, `<ns2:Item>` is keyed by `ns2:Item`.
+///   Namespace declarations, comments and processing instructions are dropped.
+pub fn element_to_json(elem: &Element) -> Value {
+  let mut text = String::new();
+  let mut map = Map::new();
+
+  for (name, value) in &elem.attributes {
+    map.insert(format!("@{}", name), Value::String(value.clone()));
+  }
+
+  let mut has_children = false;
+  for node in &elem.children {
+    match *node {
+      XMLNode::Element(ref child) => {
+        has_children = true;
+        let name = match child.prefix {
+          Some(ref prefix) => format!("{}:{}", prefix, child.name),
+          None => child.name.clone(),
+        };
+        let value = element_to_json(child);
+        match map.remove(&name) {
+          Some(Value::Array(mut items)) => {
+            items.push(value);
+            map.insert(name, Value::Array(items));
+          }
+          Some(prev) => {
+            map.insert(name, Value::Array(vec![prev, value]));
+          }
+          None => {
+            map.insert(name, value);
+          }
+        }
+      }
+      XMLNode::Text(ref v) | XMLNode::CData(ref v) => text.push_str(v),
+      _ => {}
+    }
+  }
+
+  if !has_children && elem.attributes.is_empty() {
+    return Value::String(text);
+  }
+
+  if !text.trim().is_empty() {
+    map.insert("#text".to_string(), Value::String(text));
+  }
+  Value::Object(map)
 }
 
 #[derive(Fail, Debug)]
@@ -190,3 +253,67 @@ pub enum GenericXmlResponseParseError {
   #[fail(display = "result element not found: {}", _0)]
   ResultElementNotFound(String),
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_element_to_json() {
+    let elem = Element::parse(
+      r#"<ListMarketplaceParticipationsResult xmlns:ns2="urn:x">
+        <NextToken />
+        <ListParticipations>
+          <Participation>
+            <MarketplaceId>ATVPDKIKX0DER</MarketplaceId>
+            <HasSellerSuspendedListings>No</HasSellerSuspendedListings>
+          </Participation>
+          <Participation>
+            <MarketplaceId>A2EUQ1WTGCTBG2</MarketplaceId>
+            <HasSellerSuspendedListings>No</HasSellerSuspendedListings>
+          </Participation>
+        </ListParticipations>
+        <ListMarketplaces>
+          <Marketplace id="1">
+            <MarketplaceId>ATVPDKIKX0DER</MarketplaceId>
+            <ns2:Count>1</ns2:Count>
+          </Marketplace>
+        </ListMarketplaces>
+        <Amount currency="USD">1.50</Amount>
+        <Note><![CDATA[a & b]]></Note>
+      </ListMarketplaceParticipationsResult>"#
+        .as_bytes(),
+    )
+    .unwrap();
+    assert_eq!(
+      element_to_json(&elem),
+      json!({
+        "NextToken": "",
+        "ListParticipations": {
+          "Participation": [
+            {
+              "MarketplaceId": "ATVPDKIKX0DER",
+              "HasSellerSuspendedListings": "No"
+            },
+            {
+              "MarketplaceId": "A2EUQ1WTGCTBG2",
+              "HasSellerSuspendedListings": "No"
+            }
+          ]
+        },
+        "ListMarketplaces": {
+          "Marketplace": {
+            "@id": "1",
+            "MarketplaceId": "ATVPDKIKX0DER",
+            "ns2:Count": "1"
+          }
+        },
+        "Amount": {
+          "@currency": "USD",
+          "#text": "1.50"
+        },
+        "Note": "a & b"
+      })
+    );
+  }
+}
@@ -387,6 +387,33 @@ pub fn skip_element<S: XmlEventStream>(stream: &mut S) -> MwsResult<()> {
   Ok(())
 }
 
+/// Skips sibling elements until the next event is the start of element `name`
+pub fn seek_element<S: XmlEventStream>(stream: &mut S, name: &str) -> MwsResult<()> {
+  loop {
+    let found = match stream.peek() {
+      Some(&Ok(XmlEvent::StartElement { name: ref elem_name, .. })) => {
+        elem_name.local_name == name
+      }
+      Some(&Ok(XmlEvent::EndElement { .. })) | None => {
+        return Err(MwsError::UnexpectedEndOfXml(format!(
+          "expected element: {}",
+          name
+        )))
+      }
+      Some(&Ok(_)) => {
+        stream.next();
+        continue;
+      }
+      Some(Err(err)) => return Err(err.clone().into()),
+    };
+
+    if found {
+      return Ok(());
+    }
+    skip_element(stream)?;
+  }
+}
+
 pub trait ElementNameSet: ::std::fmt::Debug {
   fn contains_element_name(&self, value: &str) -> bool;
 }